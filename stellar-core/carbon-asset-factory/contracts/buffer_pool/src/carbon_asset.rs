@@ -0,0 +1,20 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Subset of the CarbonAsset interface used for donations, partial withdrawals
+/// and vintage segmentation
+#[allow(dead_code)]
+#[contractclient(name = "CarbonAssetClient")]
+pub trait CarbonAssetInterface {
+    /// Quantity of credits (e.g. tonnes) represented by `token_id`
+    fn amount_of(env: Env, token_id: u32) -> i128;
+
+    /// Move `token_id` from `from` to `to`
+    fn transfer(env: Env, from: Address, to: Address, token_id: u32);
+
+    /// Move `amount` of `token_id` held by `from` to `to`
+    fn transfer_amount(env: Env, from: Address, to: Address, token_id: u32, amount: i128);
+
+    /// Vintage year of `token_id`. Optional: donated credits of assets
+    /// without it are left out of the per-vintage segments.
+    fn vintage_of(env: Env, token_id: u32) -> u32;
+}
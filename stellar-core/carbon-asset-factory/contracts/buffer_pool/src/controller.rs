@@ -0,0 +1,8 @@
+use soroban_sdk::{contractclient, Env};
+
+/// Interface of a controller contract that pauses a whole deployment at once
+#[allow(dead_code)]
+#[contractclient(name = "ControllerClient")]
+pub trait ControllerInterface {
+    fn is_paused(env: Env) -> bool;
+}
@@ -1,5 +1,7 @@
 use soroban_sdk::contracterror;
 
+/// Error codes are part of the public interface: discriminants are assigned
+/// explicitly and must never be renumbered or reused.
 #[contracterror]
 #[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
 #[repr(u32)]
@@ -11,4 +13,18 @@ pub enum Error {
     TokenNotFound = 5,
     AlreadyExists = 6,
     InvalidState = 7,
+    PercentageCooldownActive = 8,
+    RecipientNotSet = 9,
+    Paused = 10,
+    NotVoter = 11,
+    ProposalNotFound = 12,
+    AlreadyVoted = 13,
+    InsufficientVotes = 14,
+    UnknownParameter = 15,
+    ProposalExpired = 16,
+}
+
+/// Stable numeric code of an `Error`, as seen by cross-contract callers
+pub fn error_code(error: Error) -> u32 {
+    error as u32
 }
@@ -1,4 +1,111 @@
-use soroban_sdk::{symbol_short, Address, Env, String, Symbol};
+use crate::storage::{FundingStatus, TvlChangeReason};
+use soroban_sdk::{contractevent, symbol_short, Address, Env, String, Symbol};
+
+/// Emitted when a TVL or issuance change moves the reserve across a target band boundary
+#[contractevent]
+pub struct FundingStatusChangedEvent {
+    pub old_status: FundingStatus,
+    pub new_status: FundingStatus,
+    pub total_value_locked: i128,
+    pub total_issued: i128,
+}
+
+/// Emitted on every TVL change with the signed delta and resulting total
+#[contractevent]
+pub struct TvlChangedEvent {
+    pub reason: TvlChangeReason,
+    pub delta: i128,
+    pub new_tvl: i128,
+}
+
+/// Emitted when governance slashes a defaulted project's recorded contribution
+#[contractevent]
+pub struct ContributionSlashedEvent {
+    pub project_id: String,
+    pub amount: i128,
+    pub remaining_contribution: i128,
+    pub total_slashed: i128,
+}
+
+/// Emitted when governance withdraws part of a pooled token's amount
+#[contractevent]
+pub struct PartialWithdrawEvent {
+    pub token_id: u32,
+    pub amount: i128,
+    pub to: Address,
+    pub remaining_amount: i128,
+}
+
+/// Emitted when a third party donates a credit to the pool
+#[contractevent]
+pub struct DonationEvent {
+    pub token_id: u32,
+    pub donor: Address,
+    pub total_donations: i128,
+}
+
+/// Emitted when part of a credit's amount is donated through `donate_amount`
+#[contractevent]
+pub struct AmountDonatedEvent {
+    pub token_id: u32,
+    pub donor: Address,
+    pub amount: i128,
+    pub total_amount: i128,
+}
+
+/// Emitted when governance moves part of a donated amount out of the pool,
+/// through `withdraw_donated_amount` or `sweep_donated_amounts`
+#[contractevent]
+pub struct DonatedAmountWithdrawnEvent {
+    pub token_id: u32,
+    pub amount: i128,
+    pub to: Address,
+    pub remaining_amount: i128,
+}
+
+/// Emitted for each credit moved to another pool by `migrate_reserve`
+#[contractevent]
+pub struct ReserveMigratedEvent {
+    pub token_id: u32,
+    pub destination: Address,
+    pub project_id: String,
+}
+
+/// Emitted for each credit evacuated by `emergency_sweep`
+#[contractevent]
+pub struct EmergencySweepEvent {
+    pub token_id: u32,
+    pub safe_address: Address,
+    pub project_id: String,
+}
+
+/// Emitted for each project paid by `execute_prorata_release`
+#[contractevent]
+pub struct ProRataReleaseEvent {
+    pub project_id: String,
+    pub recipient: Address,
+    pub token_id: u32,
+    pub amount: i128,
+}
+
+/// Emitted when a voter proposes a parameter change
+#[contractevent]
+pub struct ProposalCreatedEvent {
+    pub proposal_id: u32,
+    pub proposer: Address,
+    pub key: Symbol,
+    pub value: i128,
+}
+
+/// Emitted for each vote cast on a proposal
+#[contractevent]
+pub struct VoteCastEvent {
+    pub proposal_id: u32,
+    pub voter: Address,
+    pub approve: bool,
+    pub approvals: u32,
+    pub rejections: u32,
+}
 
 pub fn emit_deposit_event(env: &Env, token_id: u32, depositor: &Address, project_id: &String) {
     #[allow(deprecated)]
@@ -22,9 +129,184 @@ pub fn emit_auto_deposit_event(env: &Env, token_id: u32, project_id: &String) {
         .publish((symbol_short!("auto_dep"),), (token_id, project_id));
 }
 
-#[allow(dead_code)]
 pub fn emit_config_update_event(env: &Env, param_name: &Symbol, new_value: i64) {
     #[allow(deprecated)]
     env.events()
         .publish((symbol_short!("config"),), (param_name, new_value));
 }
+
+pub fn emit_funding_status_changed_event(
+    env: &Env,
+    old_status: FundingStatus,
+    new_status: FundingStatus,
+    total_value_locked: i128,
+    total_issued: i128,
+) {
+    FundingStatusChangedEvent {
+        old_status,
+        new_status,
+        total_value_locked,
+        total_issued,
+    }
+    .publish(env);
+}
+
+pub fn emit_contribution_slashed_event(
+    env: &Env,
+    project_id: String,
+    amount: i128,
+    remaining_contribution: i128,
+    total_slashed: i128,
+) {
+    ContributionSlashedEvent {
+        project_id,
+        amount,
+        remaining_contribution,
+        total_slashed,
+    }
+    .publish(env);
+}
+
+pub fn emit_partial_withdraw_event(
+    env: &Env,
+    token_id: u32,
+    amount: i128,
+    to: Address,
+    remaining_amount: i128,
+) {
+    PartialWithdrawEvent {
+        token_id,
+        amount,
+        to,
+        remaining_amount,
+    }
+    .publish(env);
+}
+
+pub fn emit_donation_event(env: &Env, token_id: u32, donor: Address, total_donations: i128) {
+    DonationEvent {
+        token_id,
+        donor,
+        total_donations,
+    }
+    .publish(env);
+}
+
+pub fn emit_amount_donated_event(
+    env: &Env,
+    token_id: u32,
+    donor: Address,
+    amount: i128,
+    total_amount: i128,
+) {
+    AmountDonatedEvent {
+        token_id,
+        donor,
+        amount,
+        total_amount,
+    }
+    .publish(env);
+}
+
+pub fn emit_donated_amount_withdrawn_event(
+    env: &Env,
+    token_id: u32,
+    amount: i128,
+    to: Address,
+    remaining_amount: i128,
+) {
+    DonatedAmountWithdrawnEvent {
+        token_id,
+        amount,
+        to,
+        remaining_amount,
+    }
+    .publish(env);
+}
+
+pub fn emit_tvl_changed_event(env: &Env, reason: TvlChangeReason, delta: i128, new_tvl: i128) {
+    TvlChangedEvent {
+        reason,
+        delta,
+        new_tvl,
+    }
+    .publish(env);
+}
+
+pub fn emit_reserve_migrated_event(
+    env: &Env,
+    token_id: u32,
+    destination: Address,
+    project_id: String,
+) {
+    ReserveMigratedEvent {
+        token_id,
+        destination,
+        project_id,
+    }
+    .publish(env);
+}
+
+pub fn emit_emergency_sweep_event(
+    env: &Env,
+    token_id: u32,
+    safe_address: Address,
+    project_id: String,
+) {
+    EmergencySweepEvent {
+        token_id,
+        safe_address,
+        project_id,
+    }
+    .publish(env);
+}
+
+pub fn emit_prorata_release_event(
+    env: &Env,
+    project_id: String,
+    recipient: Address,
+    token_id: u32,
+    amount: i128,
+) {
+    ProRataReleaseEvent {
+        project_id,
+        recipient,
+        token_id,
+        amount,
+    }
+    .publish(env);
+}
+
+pub fn emit_proposal_created_event(
+    env: &Env,
+    proposal_id: u32,
+    proposer: Address,
+    key: Symbol,
+    value: i128,
+) {
+    ProposalCreatedEvent {
+        proposal_id,
+        proposer,
+        key,
+        value,
+    }
+    .publish(env);
+}
+
+pub fn emit_vote_cast_event(
+    env: &Env,
+    proposal_id: u32,
+    voter: Address,
+    approve: bool,
+    approvals: u32,
+    rejections: u32,
+) {
+    VoteCastEvent {
+        proposal_id,
+        voter,
+        approve,
+        approvals,
+        rejections,
+    }
+    .publish(env);
+}
@@ -1,16 +1,38 @@
 #![no_std]
 
+mod carbon_asset;
+mod controller;
 mod errors;
 mod events;
 mod storage;
 #[cfg(test)]
 mod test;
 
-use errors::Error;
+use carbon_asset::CarbonAssetClient;
+use controller::ControllerClient;
+pub use errors::{error_code, Error};
 use events::*;
-use soroban_sdk::{contract, contractimpl, Address, Env, String};
+use soroban_sdk::{contract, contractimpl, Address, Env, String, Symbol, Vec};
 use storage::*;
 
+/// Maximum number of credits moved by a single `migrate_reserve` call
+pub const MAX_MIGRATION_BATCH: u32 = 25;
+
+/// Maximum number of credits moved by a single `emergency_sweep` call
+pub const MAX_SWEEP_BATCH: u32 = 25;
+
+/// Maximum number of custody list slots read by a single `get_held_tokens` call
+pub const MAX_HELD_PAGE: u32 = 100;
+
+/// Maximum number of contributing projects a pro-rata release can split across
+pub const MAX_PRORATA_PROJECTS: u32 = 100;
+
+/// Maximum number of projects paid by a single `execute_prorata_release` call
+pub const MAX_PRORATA_BATCH: u32 = 25;
+
+/// Number of ledgers (~7 days) a proposal stays open for votes and execution
+pub const PROPOSAL_LIFETIME_LEDGERS: u32 = 7 * 17280;
+
 #[contract]
 pub struct BufferPoolContract;
 
@@ -38,17 +60,32 @@ impl BufferPoolContract {
         set_carbon_asset_contract(&env, &carbon_asset_contract);
         set_replenishment_percentage(&env, initial_percentage);
         set_total_value_locked(&env, 0);
+        extend_instance_ttl(&env);
 
         Ok(())
     }
 
+    /// Initialize the pool from a named-field config so admin and governance
+    /// cannot be transposed. Same validation as `initialize`.
+    pub fn initialize_cfg(env: Env, cfg: BufferPoolInitConfig) -> Result<(), Error> {
+        Self::initialize(
+            env,
+            cfg.admin,
+            cfg.governance,
+            cfg.carbon_asset_contract,
+            cfg.initial_percentage,
+        )
+    }
+
     /// Manually deposit a carbon credit token into the pool.
-    /// Only admin or carbon_asset_contract can call this.
+    /// Only admin or carbon_asset_contract can call this. `vintage`, if
+    /// given, places the credit in that vintage's TVL segment.
     pub fn deposit(
         env: Env,
         caller: Address,
         token_id: u32,
         project_id: String,
+        vintage: Option<u32>,
     ) -> Result<(), Error> {
         let admin = get_admin(&env);
         let carbon_contract = get_carbon_asset_contract(&env);
@@ -59,6 +96,8 @@ impl BufferPoolContract {
 
         caller.require_auth();
 
+        require_not_paused(&env)?;
+
         if has_custody_record(&env, token_id) {
             return Err(Error::AlreadyExists);
         }
@@ -71,11 +110,138 @@ impl BufferPoolContract {
         };
 
         set_custody_record(&env, token_id, &record);
+        set_contribution(&env, &project_id, get_contribution(&env, &project_id) + 1);
+        enter_vintage_segment(&env, token_id, vintage);
 
-        let tvl = get_total_value_locked(&env);
-        set_total_value_locked(&env, tvl + 1);
+        adjust_total_value_locked(&env, 1, TvlChangeReason::Deposit);
+        extend_instance_ttl(&env);
 
         emit_deposit_event(&env, token_id, &caller, &project_id);
+        update_funding_status(&env);
+
+        Ok(())
+    }
+
+    /// Donate a credit to the pool. Open to anyone: the credit is transferred
+    /// from `from` into the pool and counted as a donation rather than a
+    /// project contribution.
+    pub fn donate(env: Env, from: Address, token_id: u32) -> Result<(), Error> {
+        from.require_auth();
+
+        require_not_paused(&env)?;
+
+        if has_custody_record(&env, token_id) {
+            return Err(Error::AlreadyExists);
+        }
+
+        let asset = CarbonAssetClient::new(&env, &get_carbon_asset_contract(&env));
+        asset.transfer(&from, &env.current_contract_address(), &token_id);
+
+        let record = CustodyRecord {
+            token_id,
+            deposited_at: env.ledger().timestamp(),
+            depositor: from.clone(),
+            project_id: String::from_str(&env, DONATION_PROJECT_ID),
+        };
+        set_custody_record(&env, token_id, &record);
+        // The donor, not the asset, is calling, so the asset can be asked
+        let vintage = match asset.try_vintage_of(&token_id) {
+            Ok(Ok(vintage)) => Some(vintage),
+            _ => None,
+        };
+        enter_vintage_segment(&env, token_id, vintage);
+
+        let total_donations = get_total_donations(&env) + 1;
+        set_total_donations(&env, total_donations);
+
+        adjust_total_value_locked(&env, 1, TvlChangeReason::Donation);
+        extend_instance_ttl(&env);
+
+        emit_donation_event(&env, token_id, from, total_donations);
+        update_funding_status(&env);
+
+        Ok(())
+    }
+
+    /// Donate part of a credit's amount to the pool. Open to anyone: `amount`
+    /// of `token_id` is moved from `from` to the pool through the asset's
+    /// `transfer_amount`. The pool receives an amount rather than a credit,
+    /// so TVL and custody are unchanged; received amounts are tracked per
+    /// credit and in `get_total_donated_amount` instead, and leave the pool
+    /// through `withdraw_donated_amount` or `sweep_donated_amounts`.
+    pub fn donate_amount(
+        env: Env,
+        from: Address,
+        token_id: u32,
+        amount: i128,
+    ) -> Result<(), Error> {
+        from.require_auth();
+
+        require_not_paused(&env)?;
+
+        if amount <= 0 {
+            return Err(Error::InvalidState);
+        }
+
+        let asset = CarbonAssetClient::new(&env, &get_carbon_asset_contract(&env));
+        asset.transfer_amount(&from, &env.current_contract_address(), &token_id, &amount);
+
+        let total_amount = get_donated_amount(&env, token_id) + amount;
+        set_donated_amount(&env, token_id, total_amount);
+        extend_instance_ttl(&env);
+
+        emit_amount_donated_event(&env, token_id, from, amount, total_amount);
+
+        Ok(())
+    }
+
+    /// Amount of `token_id` received through `donate_amount` and still held
+    pub fn get_donated_amount(env: Env, token_id: u32) -> i128 {
+        get_donated_amount(&env, token_id)
+    }
+
+    /// Amount held across all credits through `donate_amount`. Reported
+    /// apart from TVL, which counts credits in custody.
+    pub fn get_total_donated_amount(env: Env) -> i128 {
+        get_total_donated_amount(&env)
+    }
+
+    /// Governance withdraws part of an amount received through
+    /// `donate_amount` to `to`. Fails with `InsufficientBalance` beyond the
+    /// amount donated for `token_id`.
+    pub fn withdraw_donated_amount(
+        env: Env,
+        caller: Address,
+        token_id: u32,
+        amount: i128,
+        to: Address,
+    ) -> Result<(), Error> {
+        let governance = get_governance(&env);
+
+        if caller != governance {
+            return Err(Error::Unauthorized);
+        }
+
+        caller.require_auth();
+
+        require_not_paused(&env)?;
+
+        if amount <= 0 {
+            return Err(Error::InvalidState);
+        }
+
+        let donated = get_donated_amount(&env, token_id);
+        if amount > donated {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let asset = CarbonAssetClient::new(&env, &get_carbon_asset_contract(&env));
+        asset.transfer_amount(&env.current_contract_address(), &to, &token_id, &amount);
+
+        set_donated_amount(&env, token_id, donated - amount);
+        extend_instance_ttl(&env);
+
+        emit_donated_amount_withdrawn_event(&env, token_id, amount, to, donated - amount);
 
         Ok(())
     }
@@ -95,28 +261,454 @@ impl BufferPoolContract {
 
         governance_caller.require_auth();
 
+        require_not_paused(&env)?;
+
         if !has_custody_record(&env, token_id) {
             return Err(Error::TokenNotFound);
         }
 
-        env.storage()
-            .persistent()
-            .remove(&(storage::CUSTODY, token_id));
+        remove_custody_record(&env, token_id);
+        adjust_vintage_tvl(&env, token_id, -1);
+        remove_token_vintage(&env, token_id);
 
-        let tvl = get_total_value_locked(&env);
-        set_total_value_locked(&env, tvl - 1);
+        adjust_total_value_locked(&env, -1, TvlChangeReason::Withdraw);
+        extend_instance_ttl(&env);
 
         emit_withdraw_event(&env, token_id, target_invalidated_token, &governance_caller);
+        update_funding_status(&env);
+
+        Ok(())
+    }
+
+    /// Governance withdraws part of a pooled token's amount to `to`.
+    /// TVL counts credits in custody, so it only drops (by one) once the
+    /// full amount is withdrawn and the token leaves custody.
+    pub fn withdraw_amount(
+        env: Env,
+        caller: Address,
+        token_id: u32,
+        amount: i128,
+        to: Address,
+    ) -> Result<(), Error> {
+        let governance = get_governance(&env);
+
+        if caller != governance {
+            return Err(Error::Unauthorized);
+        }
+
+        caller.require_auth();
+
+        require_not_paused(&env)?;
+
+        if !has_custody_record(&env, token_id) {
+            return Err(Error::TokenNotFound);
+        }
+
+        if amount <= 0 {
+            return Err(Error::InvalidState);
+        }
+
+        let asset = CarbonAssetClient::new(&env, &get_carbon_asset_contract(&env));
+        let held = asset.amount_of(&token_id);
+        if amount > held {
+            return Err(Error::InsufficientBalance);
+        }
+
+        asset.transfer_amount(&env.current_contract_address(), &to, &token_id, &amount);
+
+        if amount == held {
+            remove_custody_record(&env, token_id);
+            adjust_vintage_tvl(&env, token_id, -1);
+            remove_token_vintage(&env, token_id);
+            adjust_total_value_locked(&env, -1, TvlChangeReason::PartialWithdraw);
+        }
+
+        extend_instance_ttl(&env);
+
+        emit_partial_withdraw_event(&env, token_id, amount, to, held - amount);
+        update_funding_status(&env);
+
+        Ok(())
+    }
+
+    /// Governance moves held credits to another buffer pool, e.g. on upgrade.
+    /// Each credit is transferred to `destination` and deposited there under
+    /// its original project id, so the destination must accept this pool as
+    /// a depositor (for instance by naming it admin for the migration).
+    ///
+    /// At most `MAX_MIGRATION_BATCH` ids are processed per call, starting at
+    /// `cursor`; the returned cursor is `None` once `token_ids` is exhausted.
+    /// Any id not held by the pool fails the whole call with `TokenNotFound`.
+    /// Amounts received through `donate_amount` are not credits in custody
+    /// and stay behind; move them with `withdraw_donated_amount`.
+    pub fn migrate_reserve(
+        env: Env,
+        caller: Address,
+        destination: Address,
+        token_ids: Vec<u32>,
+        cursor: u32,
+    ) -> Result<Option<u32>, Error> {
+        let governance = get_governance(&env);
+
+        if caller != governance {
+            return Err(Error::Unauthorized);
+        }
+
+        caller.require_auth();
+
+        require_not_paused(&env)?;
+
+        let asset = CarbonAssetClient::new(&env, &get_carbon_asset_contract(&env));
+        let destination_pool = BufferPoolContractClient::new(&env, &destination);
+        let this_pool = env.current_contract_address();
+        let end = cursor
+            .saturating_add(MAX_MIGRATION_BATCH)
+            .min(token_ids.len());
+
+        for i in cursor..end {
+            let token_id = token_ids.get_unchecked(i);
+            let record = get_custody_record(&env, token_id).ok_or(Error::TokenNotFound)?;
+            let vintage = get_token_vintage(&env, token_id);
+
+            remove_custody_record(&env, token_id);
+            adjust_vintage_tvl(&env, token_id, -1);
+            remove_token_vintage(&env, token_id);
+            adjust_total_value_locked(&env, -1, TvlChangeReason::Migration);
+
+            asset.transfer(&this_pool, &destination, &token_id);
+            destination_pool.deposit(&this_pool, &token_id, &record.project_id, &vintage);
+
+            emit_reserve_migrated_event(&env, token_id, destination.clone(), record.project_id);
+        }
+
+        extend_instance_ttl(&env);
+        update_funding_status(&env);
+
+        Ok(if end < token_ids.len() {
+            Some(end)
+        } else {
+            None
+        })
+    }
+
+    /// Last-resort control: governance evacuates every held credit to
+    /// `safe_address`, e.g. while an exploit is suspected.
+    ///
+    /// Walks at most `MAX_SWEEP_BATCH` slots of the pool's custody list per
+    /// call, starting at `cursor`, and moves the credit in each used slot;
+    /// the returned cursor is `None` once the end of the list is reached, so
+    /// start at 0 and call again with each returned cursor. TVL drops by one
+    /// per credit moved, so a non-zero TVL afterwards means credits that
+    /// entered custody before the list existed remain: list them with
+    /// `backfill_held_tokens` and sweep again. Donated amounts are swept by
+    /// `sweep_donated_amounts`. Works while the controller reports paused.
+    pub fn emergency_sweep(
+        env: Env,
+        caller: Address,
+        safe_address: Address,
+        cursor: u32,
+    ) -> Result<Option<u32>, Error> {
+        let governance = get_governance(&env);
+
+        if caller != governance {
+            return Err(Error::Unauthorized);
+        }
+
+        caller.require_auth();
+
+        let asset = CarbonAssetClient::new(&env, &get_carbon_asset_contract(&env));
+        let this_pool = env.current_contract_address();
+        let slots = get_held_slot_count(&env);
+        let end = cursor.saturating_add(MAX_SWEEP_BATCH).min(slots);
+
+        for slot in cursor..end {
+            let Some(token_id) = get_held_token(&env, slot) else {
+                continue;
+            };
+            let project_id = get_custody_record(&env, token_id)
+                .map(|record| record.project_id)
+                .ok_or(Error::TokenNotFound)?;
+
+            remove_custody_record(&env, token_id);
+            adjust_vintage_tvl(&env, token_id, -1);
+            remove_token_vintage(&env, token_id);
+            adjust_total_value_locked(&env, -1, TvlChangeReason::EmergencySweep);
+
+            asset.transfer(&this_pool, &safe_address, &token_id);
+
+            emit_emergency_sweep_event(&env, token_id, safe_address.clone(), project_id);
+        }
+
+        extend_instance_ttl(&env);
+        update_funding_status(&env);
+
+        Ok(if end < slots { Some(end) } else { None })
+    }
+
+    /// Governance adds credits already in custody to the pool's custody
+    /// list, for credits deposited before the list was kept. Returns how
+    /// many were newly listed. Ids without a custody record fail the call
+    /// with `TokenNotFound`; more than `MAX_SWEEP_BATCH` ids with
+    /// `InvalidState`.
+    pub fn backfill_held_tokens(
+        env: Env,
+        caller: Address,
+        token_ids: Vec<u32>,
+    ) -> Result<u32, Error> {
+        let governance = get_governance(&env);
+
+        if caller != governance {
+            return Err(Error::Unauthorized);
+        }
+
+        caller.require_auth();
+
+        if token_ids.len() > MAX_SWEEP_BATCH {
+            return Err(Error::InvalidState);
+        }
+
+        let mut listed = 0;
+        for token_id in token_ids.iter() {
+            if !has_custody_record(&env, token_id) {
+                return Err(Error::TokenNotFound);
+            }
+            if add_held_token(&env, token_id) {
+                listed += 1;
+            }
+        }
+
+        extend_instance_ttl(&env);
+
+        Ok(listed)
+    }
+
+    /// A page of the pool's custody list, in the order credits entered
+    /// custody: the credits in the `limit` slots from `start`, at most
+    /// `MAX_HELD_PAGE`, and the slot to read next, or `None` at the end of
+    /// the list. Emptied slots are skipped, so a page can come back short.
+    pub fn get_held_tokens(env: Env, start: u32, limit: u32) -> (Vec<u32>, Option<u32>) {
+        let slots = get_held_slot_count(&env);
+        let end = start.saturating_add(limit.min(MAX_HELD_PAGE)).min(slots);
+
+        let mut held = Vec::new(&env);
+        for slot in start..end {
+            if let Some(token_id) = get_held_token(&env, slot) {
+                held.push_back(token_id);
+            }
+        }
+
+        (held, if end < slots { Some(end) } else { None })
+    }
+
+    /// Number of credits on the pool's custody list
+    pub fn get_held_token_count(env: Env) -> u32 {
+        get_held_token_count(&env)
+    }
+
+    /// Emergency counterpart of `withdraw_donated_amount`: governance moves
+    /// the whole donated amount of each of `token_ids` to `safe_address`.
+    /// Donated amounts are not on the custody list, so the ids are given,
+    /// e.g. from `AmountDonatedEvent`s; ids with nothing donated are skipped.
+    /// More than `MAX_SWEEP_BATCH` ids fail with `InvalidState`. Returns the
+    /// total amount moved. Works while the controller reports paused.
+    pub fn sweep_donated_amounts(
+        env: Env,
+        caller: Address,
+        safe_address: Address,
+        token_ids: Vec<u32>,
+    ) -> Result<i128, Error> {
+        let governance = get_governance(&env);
+
+        if caller != governance {
+            return Err(Error::Unauthorized);
+        }
+
+        caller.require_auth();
+
+        if token_ids.len() > MAX_SWEEP_BATCH {
+            return Err(Error::InvalidState);
+        }
+
+        let asset = CarbonAssetClient::new(&env, &get_carbon_asset_contract(&env));
+        let this_pool = env.current_contract_address();
+        let mut swept: i128 = 0;
+        for token_id in token_ids.iter() {
+            let donated = get_donated_amount(&env, token_id);
+            if donated == 0 {
+                continue;
+            }
+
+            set_donated_amount(&env, token_id, 0);
+            asset.transfer_amount(&this_pool, &safe_address, &token_id, &donated);
+            swept += donated;
+
+            emit_donated_amount_withdrawn_event(&env, token_id, donated, safe_address.clone(), 0);
+        }
+
+        extend_instance_ttl(&env);
+
+        Ok(swept)
+    }
+
+    /// Governance sets the address that receives a project's share of
+    /// pro-rata releases.
+    pub fn set_project_recipient(
+        env: Env,
+        caller: Address,
+        project_id: String,
+        recipient: Address,
+    ) -> Result<(), Error> {
+        let governance = get_governance(&env);
+
+        if caller != governance {
+            return Err(Error::Unauthorized);
+        }
+
+        caller.require_auth();
+
+        set_project_recipient(&env, &project_id, &recipient);
+        extend_instance_ttl(&env);
 
         Ok(())
     }
 
+    pub fn get_project_recipient(env: Env, project_id: String) -> Option<Address> {
+        get_project_recipient(&env, &project_id)
+    }
+
+    /// Splits `total_release` across contributing projects in proportion to
+    /// their recorded contributions, in order of first contribution.
+    ///
+    /// Shares are cut at cumulative contribution boundaries, so rounding never
+    /// loses a unit and the shares always sum to `total_release`. Projects
+    /// with no remaining contribution are left out. Fails with
+    /// `InvalidState` for a non-positive total or more than
+    /// `MAX_PRORATA_PROJECTS` registered projects.
+    pub fn compute_prorata_release(
+        env: Env,
+        total_release: i128,
+    ) -> Result<Vec<(String, i128)>, Error> {
+        prorata_shares(&env, total_release)
+    }
+
+    /// Governance pays out `total_release` of a pooled token's amount to the
+    /// contributing projects' recipients, using the shares from
+    /// `compute_prorata_release`.
+    ///
+    /// The first call, at cursor 0, fixes the shares and records the release
+    /// as in progress; later calls pay from those shares even if
+    /// contributions change meanwhile. At most `MAX_PRORATA_BATCH` projects
+    /// are paid per call, starting at `cursor`, which must be the cursor
+    /// returned by the previous call; the returned cursor is `None` once every
+    /// share is paid and the release is closed. Any other cursor or total
+    /// fails with `InvalidState`, so a batch is never paid twice. A project
+    /// without a recipient fails the call with `RecipientNotSet`, and a
+    /// release larger than the token's remaining amount with
+    /// `InsufficientBalance`. TVL counts credits in custody, so it drops by
+    /// one only once a batch empties the token.
+    pub fn execute_prorata_release(
+        env: Env,
+        caller: Address,
+        token_id: u32,
+        total_release: i128,
+        cursor: u32,
+    ) -> Result<Option<u32>, Error> {
+        let governance = get_governance(&env);
+
+        if caller != governance {
+            return Err(Error::Unauthorized);
+        }
+
+        caller.require_auth();
+
+        require_not_paused(&env)?;
+
+        if !has_custody_record(&env, token_id) {
+            return Err(Error::TokenNotFound);
+        }
+
+        let asset = CarbonAssetClient::new(&env, &get_carbon_asset_contract(&env));
+        let held = asset.amount_of(&token_id);
+
+        let mut release = match get_prorata_release(&env, token_id) {
+            Some(release) => release,
+            None if cursor == 0 => {
+                if total_release > held {
+                    return Err(Error::InsufficientBalance);
+                }
+                ProRataRelease {
+                    total_release,
+                    shares: prorata_shares(&env, total_release)?,
+                    next: 0,
+                }
+            }
+            None => return Err(Error::InvalidState),
+        };
+        if release.total_release != total_release || release.next != cursor {
+            return Err(Error::InvalidState);
+        }
+
+        let shares = release.shares.clone();
+        let end = cursor.saturating_add(MAX_PRORATA_BATCH).min(shares.len());
+
+        let mut batch_total: i128 = 0;
+        for i in cursor..end {
+            batch_total += shares.get_unchecked(i).1;
+        }
+        if batch_total > held {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let this_pool = env.current_contract_address();
+        for i in cursor..end {
+            let (project_id, share) = shares.get_unchecked(i);
+            if share == 0 {
+                continue;
+            }
+
+            let recipient =
+                get_project_recipient(&env, &project_id).ok_or(Error::RecipientNotSet)?;
+            asset.transfer_amount(&this_pool, &recipient, &token_id, &share);
+
+            emit_prorata_release_event(&env, project_id, recipient, token_id, share);
+        }
+
+        if end < shares.len() {
+            release.next = end;
+            set_prorata_release(&env, token_id, &release);
+        } else {
+            remove_prorata_release(&env, token_id);
+        }
+
+        if batch_total == held {
+            remove_custody_record(&env, token_id);
+            adjust_vintage_tvl(&env, token_id, -1);
+            remove_token_vintage(&env, token_id);
+            adjust_total_value_locked(&env, -1, TvlChangeReason::ProRataRelease);
+        }
+
+        extend_instance_ttl(&env);
+        update_funding_status(&env);
+
+        Ok(if end < shares.len() { Some(end) } else { None })
+    }
+
+    /// Pro-rata release of `token_id` in progress, if any
+    pub fn get_prorata_release(env: Env, token_id: u32) -> Option<ProRataRelease> {
+        get_prorata_release(&env, token_id)
+    }
+
+    /// Called by the CarbonAsset contract on every mint. Takes one credit in
+    /// every `10000 / rate` into custody and returns whether it did. The
+    /// asset passes the credit's `vintage` itself, since the pool cannot
+    /// call back into a contract that is calling it.
     pub fn auto_deposit(
         env: Env,
         carbon_contract_caller: Address,
         token_id: u32,
         project_id: String,
-        _total_minted: u32,
+        total_minted: u32,
+        vintage: Option<u32>,
     ) -> Result<bool, Error> {
         let carbon_contract = get_carbon_asset_contract(&env);
         if carbon_contract_caller != carbon_contract {
@@ -125,10 +717,18 @@ impl BufferPoolContract {
 
         carbon_contract_caller.require_auth();
 
+        require_not_paused(&env)?;
+
+        // Track issuance so the reserve ratio can be measured against it
+        if total_minted as i128 > get_total_issued(&env) {
+            set_total_issued(&env, total_minted as i128);
+        }
+
+        // A rate of 0 turns auto-deposits off
         let percentage = get_replenishment_percentage(&env);
-        let modulo = (10000 / percentage) as u32;
+        let deposited = percentage > 0 && token_id.is_multiple_of((10000 / percentage) as u32);
 
-        if token_id % modulo == 0 {
+        if deposited {
             let record = CustodyRecord {
                 token_id,
                 deposited_at: env.ledger().timestamp(),
@@ -137,16 +737,18 @@ impl BufferPoolContract {
             };
 
             set_custody_record(&env, token_id, &record);
+            set_contribution(&env, &project_id, get_contribution(&env, &project_id) + 1);
+            enter_vintage_segment(&env, token_id, vintage);
 
-            let tvl = get_total_value_locked(&env);
-            set_total_value_locked(&env, tvl + 1);
+            adjust_total_value_locked(&env, 1, TvlChangeReason::AutoDeposit);
+            extend_instance_ttl(&env);
 
             emit_auto_deposit_event(&env, token_id, &project_id);
-
-            Ok(true)
-        } else {
-            Ok(false)
         }
+
+        update_funding_status(&env);
+
+        Ok(deposited)
     }
 
     pub fn set_governance_address(
@@ -163,6 +765,7 @@ impl BufferPoolContract {
         current_governance.require_auth();
 
         set_governance(&env, &new_governance);
+        extend_instance_ttl(&env);
 
         Ok(())
     }
@@ -180,19 +783,373 @@ impl BufferPoolContract {
 
         governance.require_auth();
 
-        if !(0..=10000).contains(&new_percentage) {
-            return Err(Error::InvalidPercentage);
+        apply_replenishment_rate(&env, new_percentage)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Set the minimum number of seconds between replenishment rate changes.
+    /// Governance only. Zero (the default) allows changes at any time.
+    pub fn set_percentage_cooldown(
+        env: Env,
+        governance: Address,
+        seconds: u64,
+    ) -> Result<(), Error> {
+        let current_governance = get_governance(&env);
+
+        if governance != current_governance {
+            return Err(Error::Unauthorized);
+        }
+
+        governance.require_auth();
+
+        set_percentage_cooldown(&env, seconds);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Governance sets or clears the shared controller whose `is_paused`
+    /// halts deposits, donations, withdrawals, migrations, pro-rata releases
+    /// and slashing. Without a controller the pool is never paused.
+    pub fn set_controller(
+        env: Env,
+        governance: Address,
+        controller: Option<Address>,
+    ) -> Result<(), Error> {
+        if governance != get_governance(&env) {
+            return Err(Error::Unauthorized);
+        }
+
+        governance.require_auth();
+
+        set_controller(&env, &controller);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    pub fn get_controller(env: Env) -> Option<Address> {
+        get_controller(&env)
+    }
+
+    /// Set the target reserve band in basis points of total issuance.
+    /// Governance only. Requires 0 <= min_bps <= max_bps <= 10000.
+    pub fn set_target_band(
+        env: Env,
+        governance: Address,
+        min_bps: i64,
+        max_bps: i64,
+    ) -> Result<(), Error> {
+        let current_governance = get_governance(&env);
+
+        if governance != current_governance {
+            return Err(Error::Unauthorized);
+        }
+
+        governance.require_auth();
+
+        apply_target_band(&env, min_bps, max_bps)?;
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Governance sets the voter set and the number of approvals a proposal
+    /// needs before `execute_proposal` applies it. Requires
+    /// 1 <= threshold <= voters; an empty voter set with threshold 0 turns
+    /// proposals off. Proposals opened under the previous set can no longer
+    /// be voted on or executed, so their approvals do not carry over.
+    pub fn set_voters(
+        env: Env,
+        governance: Address,
+        voters: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), Error> {
+        if governance != get_governance(&env) {
+            return Err(Error::Unauthorized);
+        }
+
+        governance.require_auth();
+
+        let disabled = voters.is_empty() && threshold == 0;
+        if !disabled && (threshold == 0 || threshold > voters.len()) {
+            return Err(Error::InvalidState);
+        }
+
+        set_voters(&env, &voters, threshold);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    pub fn get_voters(env: Env) -> (Vec<Address>, u32) {
+        (get_voters(&env), get_vote_threshold(&env))
+    }
+
+    /// A voter proposes setting a numeric parameter: `rep_pct` (replenishment
+    /// rate), `tgt_min` or `tgt_max` (target band bounds), all in basis
+    /// points. The value is validated when the proposal is executed.
+    /// The proposal stays open for `PROPOSAL_LIFETIME_LEDGERS` ledgers and
+    /// while the voter set is unchanged. Returns the proposal id.
+    pub fn propose_parameter(
+        env: Env,
+        proposer: Address,
+        key: Symbol,
+        value: i128,
+    ) -> Result<u32, Error> {
+        proposer.require_auth();
+
+        if !get_voters(&env).contains(&proposer) {
+            return Err(Error::NotVoter);
+        }
+        if key != REPLENISH_PCT && key != TARGET_MIN && key != TARGET_MAX {
+            return Err(Error::UnknownParameter);
+        }
+
+        let proposal_id = get_proposal_count(&env) + 1;
+        set_proposal_count(&env, proposal_id);
+        set_proposal(
+            &env,
+            &Proposal {
+                id: proposal_id,
+                proposer: proposer.clone(),
+                key: key.clone(),
+                value,
+                approvals: 0,
+                rejections: 0,
+                executed: false,
+                created_at: env.ledger().timestamp(),
+                voter_epoch: get_voter_epoch(&env),
+                expires_at_ledger: env
+                    .ledger()
+                    .sequence()
+                    .saturating_add(PROPOSAL_LIFETIME_LEDGERS),
+            },
+        );
+        extend_instance_ttl(&env);
+
+        emit_proposal_created_event(&env, proposal_id, proposer, key, value);
+
+        Ok(proposal_id)
+    }
+
+    /// A voter approves or rejects an open proposal, once per proposal.
+    /// Fails with `ProposalExpired` once the proposal is past its expiry
+    /// ledger or the voter set has changed since it was opened.
+    pub fn vote(env: Env, voter: Address, proposal_id: u32, approve: bool) -> Result<(), Error> {
+        voter.require_auth();
+
+        if !get_voters(&env).contains(&voter) {
+            return Err(Error::NotVoter);
+        }
+
+        let mut proposal = get_proposal(&env, proposal_id).ok_or(Error::ProposalNotFound)?;
+        if proposal.executed {
+            return Err(Error::InvalidState);
+        }
+        require_proposal_open(&env, &proposal)?;
+        if has_voted(&env, proposal_id, &voter) {
+            return Err(Error::AlreadyVoted);
+        }
+
+        set_vote(&env, proposal_id, &voter, approve);
+        if approve {
+            proposal.approvals += 1;
+        } else {
+            proposal.rejections += 1;
+        }
+        set_proposal(&env, &proposal);
+
+        emit_vote_cast_event(
+            &env,
+            proposal_id,
+            voter,
+            approve,
+            proposal.approvals,
+            proposal.rejections,
+        );
+
+        Ok(())
+    }
+
+    /// Apply a proposal once its approvals reach the voter threshold. Open to
+    /// anyone. The change goes through the same validation as the governance
+    /// setters, including the replenishment rate cooldown, and emits a config
+    /// update event. Expired proposals fail with `ProposalExpired`, as in
+    /// `vote`.
+    pub fn execute_proposal(env: Env, proposal_id: u32) -> Result<(), Error> {
+        let mut proposal = get_proposal(&env, proposal_id).ok_or(Error::ProposalNotFound)?;
+        if proposal.executed {
+            return Err(Error::InvalidState);
         }
+        require_proposal_open(&env, &proposal)?;
 
-        set_replenishment_percentage(&env, new_percentage);
+        let threshold = get_vote_threshold(&env);
+        if threshold == 0 || proposal.approvals < threshold {
+            return Err(Error::InsufficientVotes);
+        }
+
+        let value = i64::try_from(proposal.value).map_err(|_| Error::InvalidPercentage)?;
+        let (min_bps, max_bps) = get_target_band(&env);
+        if proposal.key == REPLENISH_PCT {
+            apply_replenishment_rate(&env, value)?;
+        } else if proposal.key == TARGET_MIN {
+            apply_target_band(&env, value, max_bps)?;
+        } else if proposal.key == TARGET_MAX {
+            apply_target_band(&env, min_bps, value)?;
+        } else {
+            return Err(Error::UnknownParameter);
+        }
+
+        proposal.executed = true;
+        set_proposal(&env, &proposal);
+        extend_instance_ttl(&env);
+
+        emit_config_update_event(&env, &proposal.key, value);
 
         Ok(())
     }
 
+    pub fn get_proposal(env: Env, proposal_id: u32) -> Option<Proposal> {
+        get_proposal(&env, proposal_id)
+    }
+
+    /// Slash part of a defaulted project's recorded contribution.
+    /// Governance only. The slashed amount is tracked in a separate ledger and
+    /// cannot exceed the project's remaining contribution.
+    pub fn slash_contribution(
+        env: Env,
+        caller: Address,
+        project_id: String,
+        amount: i128,
+    ) -> Result<(), Error> {
+        let governance = get_governance(&env);
+
+        if caller != governance {
+            return Err(Error::Unauthorized);
+        }
+
+        caller.require_auth();
+
+        require_not_paused(&env)?;
+
+        if amount <= 0 {
+            return Err(Error::InvalidState);
+        }
+
+        let contribution = get_contribution(&env, &project_id);
+        if amount > contribution {
+            return Err(Error::InsufficientBalance);
+        }
+
+        let remaining = contribution - amount;
+        let total_slashed = get_slashed(&env, &project_id) + amount;
+        set_contribution(&env, &project_id, remaining);
+        set_slashed(&env, &project_id, total_slashed);
+        extend_instance_ttl(&env);
+
+        emit_contribution_slashed_event(&env, project_id, amount, remaining, total_slashed);
+
+        Ok(())
+    }
+
+    /// Ledger timestamp and sequence as the pool observes them, for replay
+    /// tests of time-dependent logic.
+    pub fn get_ledger_context(env: Env) -> (u64, u32) {
+        (env.ledger().timestamp(), env.ledger().sequence())
+    }
+
+    /// Whether `who` is the pool admin; false before initialization.
+    pub fn can_admin(env: Env, who: Address) -> bool {
+        env.storage().instance().get::<_, Address>(&storage::ADMIN) == Some(who)
+    }
+
+    /// Whether `who` is the governance address; false before initialization.
+    pub fn can_govern(env: Env, who: Address) -> bool {
+        env.storage()
+            .instance()
+            .get::<_, Address>(&storage::GOVERNANCE)
+            == Some(who)
+    }
+
+    /// Number of credits a project has contributed to the pool, net of slashing.
+    pub fn get_contribution(env: Env, project_id: String) -> i128 {
+        get_contribution(&env, &project_id)
+    }
+
+    /// Replenishment rate in basis points.
+    pub fn get_replenishment_rate(env: Env) -> i64 {
+        get_replenishment_percentage(&env)
+    }
+
+    /// Minimum seconds between replenishment rate changes.
+    pub fn get_percentage_cooldown(env: Env) -> u64 {
+        get_percentage_cooldown(&env)
+    }
+
+    /// Ledger timestamp of the last replenishment rate change, if any.
+    pub fn get_last_percentage_change(env: Env) -> Option<u64> {
+        get_last_percentage_change(&env)
+    }
+
+    /// Number of credits donated to the pool through `donate`.
+    pub fn get_total_donations(env: Env) -> i128 {
+        get_total_donations(&env)
+    }
+
+    /// Total amount slashed from a project's contribution.
+    pub fn get_slashed(env: Env, project_id: String) -> i128 {
+        get_slashed(&env, &project_id)
+    }
+
+    pub fn get_target_band(env: Env) -> (i64, i64) {
+        get_target_band(&env)
+    }
+
+    pub fn get_total_issued(env: Env) -> i128 {
+        get_total_issued(&env)
+    }
+
+    /// Reserve ratio (TVL / total issued) compared against the target band.
+    /// Reports Healthy while nothing has been issued.
+    pub fn get_funding_status(env: Env) -> FundingStatus {
+        compute_funding_status(&env)
+    }
+
+    /// Credits needed to bring TVL up to the target band minimum.
+    /// Returns 0 while nothing has been issued or the pool is already at target.
+    pub fn get_required_topup(env: Env) -> i128 {
+        let total_issued = get_total_issued(&env);
+        if total_issued == 0 {
+            return 0;
+        }
+
+        let (min_bps, _) = get_target_band(&env);
+        let target = min_bps as i128 * total_issued / 10000;
+        (target - get_total_value_locked(&env)).max(0)
+    }
+
+    /// Credits in custody. Amounts received through `donate_amount` are
+    /// reported separately by `get_total_donated_amount`.
     pub fn get_total_value_locked(env: Env) -> i128 {
         get_total_value_locked(&env)
     }
 
+    /// TVL held in credits of `vintage`. Each segment moves with the pool's
+    /// TVL for credits whose vintage is known when they enter custody: given
+    /// to `deposit` and `auto_deposit`, or reported by the asset's
+    /// `vintage_of` for donations. Other credits count towards no segment.
+    pub fn get_tvl_by_vintage(env: Env, vintage: u32) -> i128 {
+        get_vintage_tvl(&env, vintage)
+    }
+
+    /// Vintage recorded for a pooled token, if one was known on deposit
+    pub fn get_token_vintage(env: Env, token_id: u32) -> Option<u32> {
+        get_token_vintage(&env, token_id)
+    }
+
     pub fn get_custody_record(env: Env, token_id: u32) -> Option<CustodyRecord> {
         get_custody_record(&env, token_id)
     }
@@ -200,4 +1157,161 @@ impl BufferPoolContract {
     pub fn is_token_in_pool(env: Env, token_id: u32) -> bool {
         has_custody_record(&env, token_id)
     }
+
+    /// Extend the TTL of a custody record and the pool's instance storage.
+    /// Anyone may call this to keep a record from being archived.
+    pub fn bump_ttl(env: Env, token_id: u32) -> Result<(), Error> {
+        if !has_custody_record(&env, token_id) {
+            return Err(Error::TokenNotFound);
+        }
+
+        extend_custody_ttl(&env, token_id);
+        extend_instance_ttl(&env);
+
+        Ok(())
+    }
+}
+
+/// Per-project shares of `total_release`, cut at cumulative contribution
+/// boundaries so they sum to the total
+fn prorata_shares(env: &Env, total_release: i128) -> Result<Vec<(String, i128)>, Error> {
+    if total_release <= 0 {
+        return Err(Error::InvalidState);
+    }
+
+    let projects = get_projects(env);
+    if projects.len() > MAX_PRORATA_PROJECTS {
+        return Err(Error::InvalidState);
+    }
+
+    let mut shares = Vec::new(env);
+    let total = get_total_contributions(env);
+    if total <= 0 {
+        return Ok(shares);
+    }
+
+    let mut cumulative: i128 = 0;
+    let mut released: i128 = 0;
+    for project_id in projects.iter() {
+        let contribution = get_contribution(env, &project_id);
+        if contribution <= 0 {
+            continue;
+        }
+
+        cumulative += contribution;
+        let boundary = total_release * cumulative / total;
+        shares.push_back((project_id, boundary - released));
+        released = boundary;
+    }
+
+    Ok(shares)
+}
+
+/// Validate and store a new replenishment rate, enforcing the cooldown
+fn apply_replenishment_rate(env: &Env, new_percentage: i64) -> Result<(), Error> {
+    if !(0..=10000).contains(&new_percentage) {
+        return Err(Error::InvalidPercentage);
+    }
+
+    let now = env.ledger().timestamp();
+    if let Some(last_change) = get_last_percentage_change(env) {
+        if now < last_change.saturating_add(get_percentage_cooldown(env)) {
+            return Err(Error::PercentageCooldownActive);
+        }
+    }
+
+    set_replenishment_percentage(env, new_percentage);
+    set_last_percentage_change(env, now);
+    Ok(())
+}
+
+/// Validate and store a new target band, re-evaluating the funding status
+fn apply_target_band(env: &Env, min_bps: i64, max_bps: i64) -> Result<(), Error> {
+    if min_bps < 0 || max_bps > 10000 || min_bps > max_bps {
+        return Err(Error::InvalidPercentage);
+    }
+
+    set_target_band(env, min_bps, max_bps);
+    update_funding_status(env);
+    Ok(())
+}
+
+/// Fail with `Paused` while the shared controller, if any, is paused
+fn require_not_paused(env: &Env) -> Result<(), Error> {
+    match get_controller(env) {
+        Some(controller) if ControllerClient::new(env, &controller).is_paused() => {
+            Err(Error::Paused)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Fail with `ProposalExpired` once `proposal` is past its expiry ledger or
+/// was opened under a previous voter set
+fn require_proposal_open(env: &Env, proposal: &Proposal) -> Result<(), Error> {
+    if env.ledger().sequence() > proposal.expires_at_ledger
+        || proposal.voter_epoch != get_voter_epoch(env)
+    {
+        return Err(Error::ProposalExpired);
+    }
+    Ok(())
+}
+
+/// Record the vintage of a token entering custody and add it to that vintage's
+/// segment. A token without a known vintage is left unsegmented.
+fn enter_vintage_segment(env: &Env, token_id: u32, vintage: Option<u32>) {
+    if let Some(vintage) = vintage {
+        set_token_vintage(env, token_id, vintage);
+        set_vintage_tvl(env, vintage, get_vintage_tvl(env, vintage) + 1);
+    }
+}
+
+/// Apply a TVL change of a pooled token to its vintage segment, if any
+fn adjust_vintage_tvl(env: &Env, token_id: u32, delta: i128) {
+    if let Some(vintage) = get_token_vintage(env, token_id) {
+        set_vintage_tvl(env, vintage, get_vintage_tvl(env, vintage) + delta);
+    }
+}
+
+/// Apply `delta` to TVL and emit a TvlChangedEvent with the new total.
+/// Every TVL mutation goes through here so indexers can replay the history.
+fn adjust_total_value_locked(env: &Env, delta: i128, reason: TvlChangeReason) {
+    let new_tvl = get_total_value_locked(env) + delta;
+    set_total_value_locked(env, new_tvl);
+    emit_tvl_changed_event(env, reason, delta, new_tvl);
+}
+
+fn compute_funding_status(env: &Env) -> FundingStatus {
+    let total_issued = get_total_issued(env);
+    if total_issued == 0 {
+        return FundingStatus::Healthy;
+    }
+
+    let ratio_bps = get_total_value_locked(env) * 10000 / total_issued;
+    let (min_bps, max_bps) = get_target_band(env);
+
+    if ratio_bps < min_bps as i128 {
+        FundingStatus::Underfunded
+    } else if ratio_bps > max_bps as i128 {
+        FundingStatus::Overfunded
+    } else {
+        FundingStatus::Healthy
+    }
+}
+
+/// Emit a FundingStatusChangedEvent if the reserve has crossed a band boundary.
+fn update_funding_status(env: &Env) {
+    let old_status = get_last_funding_status(env);
+    let new_status = compute_funding_status(env);
+
+    if new_status != old_status {
+        set_last_funding_status(env, new_status);
+        emit_funding_status_changed_event(
+            env,
+            old_status,
+            new_status,
+            get_total_value_locked(env),
+            get_total_issued(env),
+        );
+    }
 }
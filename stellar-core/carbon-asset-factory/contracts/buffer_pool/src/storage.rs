@@ -1,4 +1,4 @@
-use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol};
+use soroban_sdk::{contracttype, symbol_short, Address, Env, String, Symbol, Vec};
 
 #[contracttype]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -9,12 +9,129 @@ pub struct CustodyRecord {
     pub project_id: String,
 }
 
+/// Named-field initialization parameters, see `initialize_cfg`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BufferPoolInitConfig {
+    pub admin: Address,
+    pub governance: Address,
+    pub carbon_asset_contract: Address,
+    pub initial_percentage: i64,
+}
+
+/// Numeric parameter change put to the voter set, see `propose_parameter`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub id: u32,
+    pub proposer: Address,
+    pub key: Symbol,
+    pub value: i128,
+    pub approvals: u32,
+    pub rejections: u32,
+    pub executed: bool,
+    pub created_at: u64,
+    /// Voter set the proposal was opened under, see `set_voters`
+    pub voter_epoch: u32,
+    /// Last ledger sequence at which the proposal can be voted on or executed
+    pub expires_at_ledger: u32,
+}
+
+/// Pro-rata release of a pooled token in progress, see `execute_prorata_release`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProRataRelease {
+    pub total_release: i128,
+    /// Per-project shares, fixed when the release started
+    pub shares: Vec<(String, i128)>,
+    /// Index of the next share to pay
+    pub next: u32,
+}
+
+/// Reserve health relative to the configured target band
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum FundingStatus {
+    Underfunded,
+    Healthy,
+    Overfunded,
+}
+
+/// Why the pool's TVL changed, carried on every TvlChangedEvent
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TvlChangeReason {
+    Deposit,
+    AutoDeposit,
+    Donation,
+    Withdraw,
+    PartialWithdraw,
+    Migration,
+    ProRataRelease,
+    EmergencySweep,
+}
+
 pub const ADMIN: Symbol = symbol_short!("admin");
 pub const GOVERNANCE: Symbol = symbol_short!("gov");
 pub const CARBON_CONTRACT: Symbol = symbol_short!("carbon");
 pub const REPLENISH_PCT: Symbol = symbol_short!("rep_pct");
 pub const TVL: Symbol = symbol_short!("tvl");
 pub const CUSTODY: Symbol = symbol_short!("custody");
+pub const TARGET_MIN: Symbol = symbol_short!("tgt_min");
+pub const TARGET_MAX: Symbol = symbol_short!("tgt_max");
+pub const ISSUED: Symbol = symbol_short!("issued");
+pub const FUND_STATUS: Symbol = symbol_short!("fund_st");
+pub const CONTRIB: Symbol = symbol_short!("contrib");
+pub const SLASHED: Symbol = symbol_short!("slashed");
+pub const DONATIONS: Symbol = symbol_short!("donated");
+pub const PCT_COOLDOWN: Symbol = symbol_short!("pct_cd");
+pub const LAST_PCT_CHANGE: Symbol = symbol_short!("pct_at");
+pub const PROJECTS: Symbol = symbol_short!("projects");
+pub const TOTAL_CONTRIB: Symbol = symbol_short!("contrib_t");
+pub const RECIPIENT: Symbol = symbol_short!("recipient");
+pub const CONTROLLER: Symbol = symbol_short!("ctrl");
+pub const VINTAGE: Symbol = symbol_short!("vintage");
+pub const VINTAGE_TVL: Symbol = symbol_short!("vint_tvl");
+pub const VOTERS: Symbol = symbol_short!("voters");
+pub const VOTE_THRESHOLD: Symbol = symbol_short!("vote_thr");
+pub const VOTER_EPOCH: Symbol = symbol_short!("vote_ep");
+pub const PROPOSAL_COUNT: Symbol = symbol_short!("prop_cnt");
+pub const PROPOSAL: Symbol = symbol_short!("proposal");
+pub const VOTE: Symbol = symbol_short!("vote");
+pub const HELD: Symbol = symbol_short!("held");
+pub const HELD_SLOT: Symbol = symbol_short!("held_slot");
+pub const HELD_NEXT: Symbol = symbol_short!("held_next");
+pub const HELD_COUNT: Symbol = symbol_short!("held_cnt");
+pub const DONATED_AMT: Symbol = symbol_short!("don_amt");
+pub const DONATED_AMT_TOTAL: Symbol = symbol_short!("don_amt_t");
+pub const PRORATA: Symbol = symbol_short!("prorata");
+
+/// Project id recorded on custody records created by `donate`
+pub const DONATION_PROJECT_ID: &str = "DONATION";
+
+// Pool config and TVL live in instance storage and are bumped to 30 days on
+// every mutation. Custody records and per-project contribution ledgers are
+// audit data and are bumped to ~120 days, re-extending once less than ~90
+// days remain.
+const DAY_IN_LEDGERS: u32 = 17280;
+const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+pub const PERSISTENT_BUMP_AMOUNT: u32 = 120 * DAY_IN_LEDGERS;
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+pub fn extend_custody_ttl(env: &Env, token_id: u32) {
+    env.storage().persistent().extend_ttl(
+        &(CUSTODY, token_id),
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
 
 pub fn get_admin(env: &Env) -> Address {
     env.storage().instance().get(&ADMIN).unwrap()
@@ -48,6 +165,97 @@ pub fn set_replenishment_percentage(env: &Env, percentage: i64) {
     env.storage().instance().set(&REPLENISH_PCT, &percentage);
 }
 
+pub fn get_percentage_cooldown(env: &Env) -> u64 {
+    env.storage().instance().get(&PCT_COOLDOWN).unwrap_or(0)
+}
+
+pub fn set_percentage_cooldown(env: &Env, seconds: u64) {
+    env.storage().instance().set(&PCT_COOLDOWN, &seconds);
+}
+
+pub fn get_last_percentage_change(env: &Env) -> Option<u64> {
+    env.storage().instance().get(&LAST_PCT_CHANGE)
+}
+
+pub fn set_last_percentage_change(env: &Env, timestamp: u64) {
+    env.storage().instance().set(&LAST_PCT_CHANGE, &timestamp);
+}
+
+pub fn get_controller(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&CONTROLLER)
+}
+
+pub fn set_controller(env: &Env, controller: &Option<Address>) {
+    match controller {
+        Some(controller) => env.storage().instance().set(&CONTROLLER, controller),
+        None => env.storage().instance().remove(&CONTROLLER),
+    }
+}
+
+pub fn get_voters(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&VOTERS)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn get_vote_threshold(env: &Env) -> u32 {
+    env.storage().instance().get(&VOTE_THRESHOLD).unwrap_or(0)
+}
+
+/// Number of times the voter set has been replaced
+pub fn get_voter_epoch(env: &Env) -> u32 {
+    env.storage().instance().get(&VOTER_EPOCH).unwrap_or(0)
+}
+
+/// Replaces the voter set and threshold and starts a new voter epoch, which
+/// closes every proposal opened under the previous set.
+pub fn set_voters(env: &Env, voters: &Vec<Address>, threshold: u32) {
+    env.storage().instance().set(&VOTERS, voters);
+    env.storage().instance().set(&VOTE_THRESHOLD, &threshold);
+    env.storage()
+        .instance()
+        .set(&VOTER_EPOCH, &(get_voter_epoch(env) + 1));
+}
+
+pub fn get_proposal_count(env: &Env) -> u32 {
+    env.storage().instance().get(&PROPOSAL_COUNT).unwrap_or(0)
+}
+
+pub fn set_proposal_count(env: &Env, count: u32) {
+    env.storage().instance().set(&PROPOSAL_COUNT, &count);
+}
+
+pub fn get_proposal(env: &Env, proposal_id: u32) -> Option<Proposal> {
+    env.storage().persistent().get(&(PROPOSAL, proposal_id))
+}
+
+pub fn set_proposal(env: &Env, proposal: &Proposal) {
+    let key = (PROPOSAL, proposal.id);
+    env.storage().persistent().set(&key, proposal);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub fn has_voted(env: &Env, proposal_id: u32, voter: &Address) -> bool {
+    env.storage()
+        .persistent()
+        .has(&(VOTE, proposal_id, voter.clone()))
+}
+
+pub fn set_vote(env: &Env, proposal_id: u32, voter: &Address, approve: bool) {
+    let key = (VOTE, proposal_id, voter.clone());
+    env.storage().persistent().set(&key, &approve);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
 pub fn get_total_value_locked(env: &Env) -> i128 {
     env.storage().instance().get(&TVL).unwrap_or(0)
 }
@@ -61,9 +269,281 @@ pub fn get_custody_record(env: &Env, token_id: u32) -> Option<CustodyRecord> {
 }
 
 pub fn set_custody_record(env: &Env, token_id: u32, record: &CustodyRecord) {
+    if !has_custody_record(env, token_id) {
+        add_held_token(env, token_id);
+    }
     env.storage().persistent().set(&(CUSTODY, token_id), record);
+    extend_custody_ttl(env, token_id);
+}
+
+/// Removes the custody record, along with any pro-rata release of the token
+/// still in progress
+pub fn remove_custody_record(env: &Env, token_id: u32) {
+    env.storage().persistent().remove(&(CUSTODY, token_id));
+    remove_prorata_release(env, token_id);
+    remove_held_token(env, token_id);
+}
+
+// The custody list is kept one entry per slot: `(HELD, slot)` holds a token
+// ID and `(HELD_SLOT, token_id)` its slot, so listing and unlisting a token
+// touch a fixed number of entries however many credits are held. Slots are
+// handed out in deposit order and never reused; an unlisted token leaves an
+// empty slot behind.
+
+/// Token ID listed in custody list slot `slot`, if the slot is still in use
+pub fn get_held_token(env: &Env, slot: u32) -> Option<u32> {
+    env.storage().persistent().get(&(HELD, slot))
+}
+
+/// Number of custody list slots handed out so far, used or not
+pub fn get_held_slot_count(env: &Env) -> u32 {
+    env.storage().instance().get(&HELD_NEXT).unwrap_or(0)
+}
+
+/// Number of tokens currently on the custody list
+pub fn get_held_token_count(env: &Env) -> u32 {
+    env.storage().instance().get(&HELD_COUNT).unwrap_or(0)
+}
+
+/// Append `token_id` to the custody list unless it is already listed.
+/// Returns whether it was added.
+pub fn add_held_token(env: &Env, token_id: u32) -> bool {
+    let slot_key = (HELD_SLOT, token_id);
+    if env.storage().persistent().has(&slot_key) {
+        return false;
+    }
+
+    let slot = get_held_slot_count(env);
+    let held_key = (HELD, slot);
+    env.storage().persistent().set(&slot_key, &slot);
+    env.storage().persistent().set(&held_key, &token_id);
+    for key in [slot_key, held_key] {
+        env.storage().persistent().extend_ttl(
+            &key,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+    env.storage().instance().set(&HELD_NEXT, &(slot + 1));
+    env.storage()
+        .instance()
+        .set(&HELD_COUNT, &(get_held_token_count(env) + 1));
+    true
+}
+
+/// Take `token_id` off the custody list, emptying its slot
+fn remove_held_token(env: &Env, token_id: u32) {
+    let slot_key = (HELD_SLOT, token_id);
+    if let Some(slot) = env.storage().persistent().get::<_, u32>(&slot_key) {
+        env.storage().persistent().remove(&slot_key);
+        env.storage().persistent().remove(&(HELD, slot));
+        env.storage()
+            .instance()
+            .set(&HELD_COUNT, &(get_held_token_count(env) - 1));
+    }
 }
 
 pub fn has_custody_record(env: &Env, token_id: u32) -> bool {
     env.storage().persistent().has(&(CUSTODY, token_id))
 }
+
+pub fn get_prorata_release(env: &Env, token_id: u32) -> Option<ProRataRelease> {
+    env.storage().persistent().get(&(PRORATA, token_id))
+}
+
+pub fn set_prorata_release(env: &Env, token_id: u32, release: &ProRataRelease) {
+    let key = (PRORATA, token_id);
+    env.storage().persistent().set(&key, release);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub fn remove_prorata_release(env: &Env, token_id: u32) {
+    env.storage().persistent().remove(&(PRORATA, token_id));
+}
+
+pub fn get_target_band(env: &Env) -> (i64, i64) {
+    let min = env.storage().instance().get(&TARGET_MIN).unwrap_or(0);
+    let max = env.storage().instance().get(&TARGET_MAX).unwrap_or(10000);
+    (min, max)
+}
+
+pub fn set_target_band(env: &Env, min_bps: i64, max_bps: i64) {
+    env.storage().instance().set(&TARGET_MIN, &min_bps);
+    env.storage().instance().set(&TARGET_MAX, &max_bps);
+}
+
+pub fn get_total_issued(env: &Env) -> i128 {
+    env.storage().instance().get(&ISSUED).unwrap_or(0)
+}
+
+pub fn set_total_issued(env: &Env, total: i128) {
+    env.storage().instance().set(&ISSUED, &total);
+}
+
+pub fn get_last_funding_status(env: &Env) -> FundingStatus {
+    env.storage()
+        .instance()
+        .get(&FUND_STATUS)
+        .unwrap_or(FundingStatus::Healthy)
+}
+
+pub fn set_last_funding_status(env: &Env, status: FundingStatus) {
+    env.storage().instance().set(&FUND_STATUS, &status);
+}
+
+pub fn get_contribution(env: &Env, project_id: &String) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&(CONTRIB, project_id.clone()))
+        .unwrap_or(0)
+}
+
+/// Writes a project's contribution, registering the project on its first
+/// contribution and keeping the pool-wide total in step.
+pub fn set_contribution(env: &Env, project_id: &String, amount: i128) {
+    let key = (CONTRIB, project_id.clone());
+    let previous: Option<i128> = env.storage().persistent().get(&key);
+    if previous.is_none() {
+        let mut projects = get_projects(env);
+        projects.push_back(project_id.clone());
+        env.storage().persistent().set(&PROJECTS, &projects);
+        env.storage().persistent().extend_ttl(
+            &PROJECTS,
+            PERSISTENT_LIFETIME_THRESHOLD,
+            PERSISTENT_BUMP_AMOUNT,
+        );
+    }
+    let total = get_total_contributions(env) - previous.unwrap_or(0) + amount;
+    env.storage().instance().set(&TOTAL_CONTRIB, &total);
+    env.storage().persistent().set(&key, &amount);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+/// Projects that have ever contributed, in order of first contribution
+pub fn get_projects(env: &Env) -> Vec<String> {
+    env.storage()
+        .persistent()
+        .get(&PROJECTS)
+        .unwrap_or_else(|| Vec::new(env))
+}
+
+pub fn get_total_contributions(env: &Env) -> i128 {
+    env.storage().instance().get(&TOTAL_CONTRIB).unwrap_or(0)
+}
+
+pub fn get_project_recipient(env: &Env, project_id: &String) -> Option<Address> {
+    env.storage()
+        .persistent()
+        .get(&(RECIPIENT, project_id.clone()))
+}
+
+pub fn set_project_recipient(env: &Env, project_id: &String, recipient: &Address) {
+    let key = (RECIPIENT, project_id.clone());
+    env.storage().persistent().set(&key, recipient);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+/// Vintage recorded for a pooled token when it entered custody
+pub fn get_token_vintage(env: &Env, token_id: u32) -> Option<u32> {
+    env.storage().persistent().get(&(VINTAGE, token_id))
+}
+
+pub fn set_token_vintage(env: &Env, token_id: u32, vintage: u32) {
+    let key = (VINTAGE, token_id);
+    env.storage().persistent().set(&key, &vintage);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub fn remove_token_vintage(env: &Env, token_id: u32) {
+    env.storage().persistent().remove(&(VINTAGE, token_id));
+}
+
+pub fn get_vintage_tvl(env: &Env, vintage: u32) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&(VINTAGE_TVL, vintage))
+        .unwrap_or(0)
+}
+
+pub fn set_vintage_tvl(env: &Env, vintage: u32, tvl: i128) {
+    let key = (VINTAGE_TVL, vintage);
+    env.storage().persistent().set(&key, &tvl);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub fn get_slashed(env: &Env, project_id: &String) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&(SLASHED, project_id.clone()))
+        .unwrap_or(0)
+}
+
+pub fn set_slashed(env: &Env, project_id: &String, amount: i128) {
+    let key = (SLASHED, project_id.clone());
+    env.storage().persistent().set(&key, &amount);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub fn get_total_donations(env: &Env) -> i128 {
+    env.storage().instance().get(&DONATIONS).unwrap_or(0)
+}
+
+pub fn set_total_donations(env: &Env, total: i128) {
+    env.storage().instance().set(&DONATIONS, &total);
+}
+
+pub fn get_donated_amount(env: &Env, token_id: u32) -> i128 {
+    env.storage()
+        .persistent()
+        .get(&(DONATED_AMT, token_id))
+        .unwrap_or(0)
+}
+
+/// Writes the amount held for `token_id` through `donate_amount`, keeping
+/// the pool-wide total in step. A zero amount removes the entry.
+pub fn set_donated_amount(env: &Env, token_id: u32, amount: i128) {
+    let key = (DONATED_AMT, token_id);
+    let total = get_total_donated_amount(env) - get_donated_amount(env, token_id) + amount;
+    env.storage().instance().set(&DONATED_AMT_TOTAL, &total);
+    if amount == 0 {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+    env.storage().persistent().set(&key, &amount);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub fn get_total_donated_amount(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&DONATED_AMT_TOTAL)
+        .unwrap_or(0)
+}
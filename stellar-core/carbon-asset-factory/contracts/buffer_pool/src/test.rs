@@ -1,7 +1,77 @@
 #![cfg(test)]
 
-use crate::{BufferPoolContract, BufferPoolContractClient};
-use soroban_sdk::{testutils::Address as _, Address, Env, String};
+use crate::errors::{error_code, Error};
+use crate::events::{AmountDonatedEvent, EmergencySweepEvent, TvlChangedEvent};
+use crate::storage::{
+    get_total_value_locked, set_total_value_locked, BufferPoolInitConfig, CustodyRecord,
+    FundingStatus, TvlChangeReason, CUSTODY, PERSISTENT_BUMP_AMOUNT,
+};
+use crate::{
+    BufferPoolContract, BufferPoolContractClient, MAX_MIGRATION_BATCH, MAX_PRORATA_BATCH,
+    MAX_SWEEP_BATCH,
+};
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{storage::Persistent as _, Address as _, Events as _, Ledger},
+    vec, Address, Env, Event, Map, String, Symbol, TryFromVal, Val, Vec,
+};
+
+#[contract]
+pub struct MockCarbonAsset;
+
+#[contractimpl]
+impl MockCarbonAsset {
+    pub fn set_amount(env: Env, token_id: u32, amount: i128) {
+        env.storage().persistent().set(&token_id, &amount);
+    }
+
+    pub fn amount_of(env: Env, token_id: u32) -> i128 {
+        env.storage().persistent().get(&token_id).unwrap_or(0)
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u32) {
+        from.require_auth();
+        env.storage().persistent().set(&(from, token_id), &false);
+        env.storage().persistent().set(&(to, token_id), &true);
+    }
+
+    pub fn holds(env: Env, owner: Address, token_id: u32) -> bool {
+        env.storage()
+            .persistent()
+            .get(&(owner, token_id))
+            .unwrap_or(false)
+    }
+
+    pub fn transfer_amount(env: Env, from: Address, to: Address, token_id: u32, amount: i128) {
+        from.require_auth();
+        let remaining = Self::amount_of(env.clone(), token_id) - amount;
+        Self::set_amount(env.clone(), token_id, remaining);
+        let received = Self::received(env.clone(), to.clone(), token_id) + amount;
+        env.storage()
+            .persistent()
+            .set(&(Symbol::new(&env, "received"), to, token_id), &received);
+    }
+
+    pub fn set_vintage(env: Env, token_id: u32, vintage: u32) {
+        env.storage()
+            .persistent()
+            .set(&(Symbol::new(&env, "vintage"), token_id), &vintage);
+    }
+
+    pub fn vintage_of(env: Env, token_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "vintage"), token_id))
+            .unwrap()
+    }
+
+    pub fn received(env: Env, to: Address, token_id: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(Symbol::new(&env, "received"), to, token_id))
+            .unwrap_or(0)
+    }
+}
 
 fn setup_test_env<'a>() -> (Env, Address, Address, Address, BufferPoolContractClient<'a>) {
     let env = Env::default();
@@ -26,6 +96,49 @@ fn test_initialize() {
     assert_eq!(tvl, 0);
 }
 
+#[test]
+fn test_can_admin_and_can_govern() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+    assert!(!client.can_admin(&admin));
+    assert!(!client.can_govern(&governance));
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+
+    assert!(client.can_admin(&admin));
+    assert!(client.can_govern(&governance));
+    assert!(!client.can_admin(&governance));
+    assert!(!client.can_govern(&admin));
+    assert!(!client.can_admin(&Address::generate(&env)));
+}
+
+#[test]
+fn test_get_ledger_context() {
+    let (env, _, _, _, client) = setup_test_env();
+    env.ledger().set_timestamp(12_345);
+    env.ledger().set_sequence_number(678);
+    assert_eq!(client.get_ledger_context(), (12_345, 678));
+}
+
+#[test]
+fn test_initialize_cfg() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize_cfg(&BufferPoolInitConfig {
+        admin: admin.clone(),
+        governance: governance.clone(),
+        carbon_asset_contract: carbon_contract,
+        initial_percentage: 500,
+    });
+
+    // Governance-only setter accepts governance and rejects admin
+    client.set_replenishment_rate(&governance, &1000);
+    assert!(client.try_set_replenishment_rate(&admin, &1000).is_err());
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.deposit(&admin, &1, &project_id, &None);
+    assert_eq!(client.get_total_value_locked(), 1);
+}
+
 #[test]
 fn test_initialize_twice_fails() {
     let (_, admin, governance, carbon_contract, client) = setup_test_env();
@@ -43,7 +156,7 @@ fn test_deposit_as_admin() {
     client.initialize(&admin, &governance, &carbon_contract, &500);
 
     let project_id = String::from_str(&env, "PROJECT-001");
-    client.deposit(&admin, &1, &project_id);
+    client.deposit(&admin, &1, &project_id, &None);
 
     let tvl = client.get_total_value_locked();
     assert_eq!(tvl, 1);
@@ -59,9 +172,9 @@ fn test_deposit_duplicate_fails() {
     client.initialize(&admin, &governance, &carbon_contract, &500);
 
     let project_id = String::from_str(&env, "PROJECT-001");
-    client.deposit(&admin, &1, &project_id);
+    client.deposit(&admin, &1, &project_id, &None);
 
-    let result = client.try_deposit(&admin, &1, &project_id);
+    let result = client.try_deposit(&admin, &1, &project_id, &None);
     assert!(result.is_err());
 }
 
@@ -72,7 +185,7 @@ fn test_withdraw_by_governance() {
     client.initialize(&admin, &governance, &carbon_contract, &500);
 
     let project_id = String::from_str(&env, "PROJECT-001");
-    client.deposit(&admin, &1, &project_id);
+    client.deposit(&admin, &1, &project_id, &None);
 
     client.withdraw_to_replace(&governance, &1, &999);
 
@@ -89,11 +202,33 @@ fn test_auto_deposit_calculation() {
     let project_id = String::from_str(&env, "PROJECT-001");
 
     // With 5% (500 bp), every 20th token should be deposited
-    let deposited = client.auto_deposit(&carbon_contract, &20, &project_id, &20);
-    assert_eq!(deposited, true);
+    let deposited = client.auto_deposit(&carbon_contract, &20, &project_id, &20, &None);
+    assert!(deposited);
 
-    let not_deposited = client.auto_deposit(&carbon_contract, &21, &project_id, &21);
-    assert_eq!(not_deposited, false);
+    let not_deposited = client.auto_deposit(&carbon_contract, &21, &project_id, &21, &None);
+    assert!(!not_deposited);
+}
+
+#[test]
+fn test_zero_rate_disables_auto_deposit() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+    let project_id = String::from_str(&env, "PROJECT-001");
+
+    client.set_replenishment_rate(&governance, &0);
+    assert!(!client.auto_deposit(&carbon_contract, &20, &project_id, &20, &None));
+    assert_eq!(client.get_total_value_locked(), 0);
+
+    // The same holds for a rate set through a proposal
+    client.set_replenishment_rate(&governance, &500);
+    let voter = Address::generate(&env);
+    client.set_voters(&governance, &vec![&env, voter.clone()], &1);
+    let proposal_id = client.propose_parameter(&voter, &Symbol::new(&env, "rep_pct"), &0);
+    client.vote(&voter, &proposal_id, &true);
+    client.execute_proposal(&proposal_id);
+    assert_eq!(client.get_replenishment_rate(), 0);
+    assert!(!client.auto_deposit(&carbon_contract, &40, &project_id, &40, &None));
+    assert!(!client.is_token_in_pool(&40));
 }
 
 #[test]
@@ -121,7 +256,7 @@ fn test_get_custody_record() {
     client.initialize(&admin, &governance, &carbon_contract, &500);
 
     let project_id = String::from_str(&env, "PROJECT-001");
-    client.deposit(&admin, &1, &project_id);
+    client.deposit(&admin, &1, &project_id, &None);
 
     let record = client.get_custody_record(&1);
     assert!(record.is_some());
@@ -138,4 +273,996 @@ fn test_set_replenishment_rate() {
     client.initialize(&admin, &governance, &carbon_contract, &500);
 
     client.set_replenishment_rate(&governance, &1000);
+    assert_eq!(client.get_replenishment_rate(), 1000);
+}
+
+#[test]
+fn test_replenishment_rate_cooldown() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+    client.set_percentage_cooldown(&governance, &3600);
+    env.ledger().set_timestamp(10_000);
+
+    client.set_replenishment_rate(&governance, &1000);
+    assert_eq!(client.get_last_percentage_change(), Some(10_000));
+
+    env.ledger().set_timestamp(13_599);
+    assert_eq!(
+        client.try_set_replenishment_rate(&governance, &1500),
+        Err(Ok(Error::PercentageCooldownActive))
+    );
+    assert_eq!(client.get_replenishment_rate(), 1000);
+
+    env.ledger().set_timestamp(13_600);
+    client.set_replenishment_rate(&governance, &1500);
+    assert_eq!(client.get_replenishment_rate(), 1500);
+}
+
+#[test]
+fn test_deposit_extends_custody_ttl() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.deposit(&admin, &1, &project_id, &None);
+
+    env.as_contract(&client.address, || {
+        let ttl = env.storage().persistent().get_ttl(&(CUSTODY, 1u32));
+        assert!(ttl >= PERSISTENT_BUMP_AMOUNT - 1);
+    });
+}
+
+#[test]
+fn test_bump_ttl() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.deposit(&admin, &1, &project_id, &None);
+    client.bump_ttl(&1);
+
+    let result = client.try_bump_ttl(&2);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_funding_status_across_band() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+    client.set_target_band(&governance, &400, &600);
+    assert_eq!(client.get_target_band(), (400, 600));
+
+    // Nothing issued yet
+    assert_eq!(client.get_funding_status(), FundingStatus::Healthy);
+
+    // 100 credits issued, none reserved
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.auto_deposit(&carbon_contract, &1, &project_id, &100, &None);
+    assert_eq!(client.get_total_issued(), 100);
+    assert_eq!(client.get_funding_status(), FundingStatus::Underfunded);
+
+    // 5% reserve
+    for token_id in 1000..1005 {
+        client.deposit(&admin, &token_id, &project_id, &None);
+    }
+    assert_eq!(client.get_funding_status(), FundingStatus::Healthy);
+
+    // 7% reserve
+    client.deposit(&admin, &1005, &project_id, &None);
+    client.deposit(&admin, &1006, &project_id, &None);
+    assert_eq!(client.get_funding_status(), FundingStatus::Overfunded);
+
+    // Back to 5%
+    client.withdraw_to_replace(&governance, &1005, &1);
+    client.withdraw_to_replace(&governance, &1006, &2);
+    assert_eq!(client.get_funding_status(), FundingStatus::Healthy);
+}
+
+#[test]
+fn test_invalid_target_band() {
+    let (_, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+
+    assert!(client.try_set_target_band(&governance, &600, &400).is_err());
+    assert!(client.try_set_target_band(&governance, &0, &10001).is_err());
+    assert!(client.try_set_target_band(&admin, &400, &600).is_err());
+}
+
+#[test]
+fn test_slash_contribution() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.deposit(&admin, &1, &project_id, &None);
+    client.deposit(&admin, &2, &project_id, &None);
+    client.auto_deposit(&carbon_contract, &20, &project_id, &20, &None);
+    assert_eq!(client.get_contribution(&project_id), 3);
+
+    client.slash_contribution(&governance, &project_id, &2);
+
+    assert_eq!(client.get_contribution(&project_id), 1);
+    assert_eq!(client.get_slashed(&project_id), 2);
+    assert_eq!(
+        client.try_slash_contribution(&admin, &project_id, &1),
+        Err(Ok(Error::Unauthorized))
+    );
+}
+
+#[test]
+fn test_slash_more_than_contribution_fails() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.deposit(&admin, &1, &project_id, &None);
+
+    assert_eq!(
+        client.try_slash_contribution(&governance, &project_id, &2),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    assert_eq!(client.get_contribution(&project_id), 1);
+    assert_eq!(client.get_slashed(&project_id), 0);
+}
+
+#[test]
+fn test_required_topup() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+    assert_eq!(client.get_required_topup(), 0);
+
+    // 5%-10% band over 100 issued credits: target minimum is 5
+    client.set_target_band(&governance, &500, &1000);
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.auto_deposit(&carbon_contract, &1, &project_id, &100, &None);
+    client.deposit(&admin, &1001, &project_id, &None);
+    client.deposit(&admin, &1002, &project_id, &None);
+    assert_eq!(client.get_funding_status(), FundingStatus::Underfunded);
+    assert_eq!(client.get_required_topup(), 3);
+
+    for token_id in 1003..1006 {
+        client.deposit(&admin, &token_id, &project_id, &None);
+    }
+    assert_eq!(client.get_funding_status(), FundingStatus::Healthy);
+    assert_eq!(client.get_required_topup(), 0);
+
+    for token_id in 1006..1012 {
+        client.deposit(&admin, &token_id, &project_id, &None);
+    }
+    assert_eq!(client.get_funding_status(), FundingStatus::Overfunded);
+    assert_eq!(client.get_required_topup(), 0);
+}
+
+#[test]
+fn test_withdraw_amount() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let recipient = Address::generate(&env);
+
+    client.initialize(&admin, &governance, &asset.address, &500);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.deposit(&admin, &1, &project_id, &None);
+    client.deposit(&admin, &2, &project_id, &None);
+    client.deposit(&admin, &3, &project_id, &None);
+    asset.set_amount(&1, &2);
+
+    // TVL counts credits in custody, which a partial withdrawal leaves alone
+    client.withdraw_amount(&governance, &1, &1, &recipient);
+    assert_eq!(asset.amount_of(&1), 1);
+    assert!(client.is_token_in_pool(&1));
+    assert_eq!(client.get_total_value_locked(), 3);
+
+    // Withdrawing the exact remaining amount releases the token
+    client.withdraw_amount(&governance, &1, &1, &recipient);
+    assert_eq!(asset.amount_of(&1), 0);
+    assert!(!client.is_token_in_pool(&1));
+    assert_eq!(client.get_total_value_locked(), 2);
+    assert!(client.is_token_in_pool(&2) && client.is_token_in_pool(&3));
+}
+
+#[test]
+fn test_tvl_by_vintage() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+
+    client.initialize(&admin, &governance, &asset.address, &500);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.deposit(&admin, &1, &project_id, &Some(2019));
+    client.deposit(&admin, &2, &project_id, &Some(2019));
+    client.deposit(&admin, &3, &project_id, &Some(2021));
+    client.deposit(&admin, &4, &project_id, &None);
+    // Donations take the vintage the asset reports
+    asset.set_vintage(&5, &2021);
+    client.donate(&Address::generate(&env), &5);
+
+    assert_eq!(client.get_tvl_by_vintage(&2019), 2);
+    assert_eq!(client.get_tvl_by_vintage(&2021), 2);
+    assert_eq!(client.get_tvl_by_vintage(&2020), 0);
+    assert_eq!(client.get_token_vintage(&3), Some(2021));
+    // Token 4 has no vintage, so it only counts towards the aggregate
+    assert_eq!(client.get_token_vintage(&4), None);
+    assert_eq!(client.get_total_value_locked(), 5);
+    client.withdraw_to_replace(&governance, &5, &98);
+
+    client.withdraw_to_replace(&governance, &1, &99);
+    assert_eq!(client.get_tvl_by_vintage(&2019), 1);
+    assert_eq!(client.get_token_vintage(&1), None);
+    assert_eq!(client.get_tvl_by_vintage(&2021), 1);
+
+    // Segments count credits like the aggregate TVL, so only emptying a
+    // token moves its segment
+    asset.set_amount(&3, &5);
+    let recipient = Address::generate(&env);
+    client.withdraw_amount(&governance, &3, &2, &recipient);
+    assert_eq!(client.get_tvl_by_vintage(&2021), 1);
+    client.withdraw_amount(&governance, &3, &3, &recipient);
+    assert_eq!(client.get_tvl_by_vintage(&2021), 0);
+    assert_eq!(client.get_total_value_locked(), 2);
+}
+
+/// Minter that deposits into the pool while minting, the way CarbonAsset does
+#[contract]
+pub struct MockMinter;
+
+#[contractimpl]
+impl MockMinter {
+    pub fn mint(env: Env, pool: Address, token_id: u32, project_id: String, vintage: u32) -> bool {
+        BufferPoolContractClient::new(&env, &pool).auto_deposit(
+            &env.current_contract_address(),
+            &token_id,
+            &project_id,
+            &token_id,
+            &Some(vintage),
+        )
+    }
+
+    pub fn vintage_of(_env: Env, _token_id: u32) -> u32 {
+        panic!("the pool must not call back into a minting asset")
+    }
+}
+
+#[test]
+fn test_auto_deposit_from_minting_asset_records_vintage() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let minter = MockMinterClient::new(&env, &env.register(MockMinter, ()));
+    client.initialize(&admin, &governance, &minter.address, &500);
+    env.set_auths(&[]);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    assert!(minter.mint(&client.address, &20, &project_id, &2022));
+    assert!(!minter.mint(&client.address, &21, &project_id, &2022));
+    assert!(minter.mint(&client.address, &40, &project_id, &2023));
+
+    assert_eq!(client.get_token_vintage(&20), Some(2022));
+    assert_eq!(client.get_tvl_by_vintage(&2022), 1);
+    assert_eq!(client.get_tvl_by_vintage(&2023), 1);
+    assert_eq!(client.get_total_value_locked(), 2);
+}
+
+#[test]
+fn test_withdraw_amount_exceeding_held_fails() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let recipient = Address::generate(&env);
+
+    client.initialize(&admin, &governance, &asset.address, &500);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.deposit(&admin, &1, &project_id, &None);
+    asset.set_amount(&1, &5);
+
+    assert_eq!(
+        client.try_withdraw_amount(&governance, &1, &6, &recipient),
+        Err(Ok(Error::InsufficientBalance))
+    );
+    assert_eq!(
+        client.try_withdraw_amount(&admin, &1, &1, &recipient),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(asset.amount_of(&1), 5);
+}
+
+fn setup_prorata_projects(
+    env: &Env,
+    admin: &Address,
+    client: &BufferPoolContractClient,
+) -> Vec<String> {
+    let projects = vec![
+        env,
+        String::from_str(env, "PROJECT-A"),
+        String::from_str(env, "PROJECT-B"),
+        String::from_str(env, "PROJECT-C"),
+    ];
+
+    // Contributions of 1, 2 and 3 credits
+    let mut token_id = 1;
+    for (i, project_id) in projects.iter().enumerate() {
+        for _ in 0..=i {
+            client.deposit(admin, &token_id, &project_id, &None);
+            token_id += 1;
+        }
+    }
+
+    projects
+}
+
+#[test]
+fn test_compute_prorata_release_sums_to_total() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+    let projects = setup_prorata_projects(&env, &admin, &client);
+
+    let shares = client.compute_prorata_release(&100);
+    assert_eq!(
+        shares,
+        vec![
+            &env,
+            (projects.get_unchecked(0), 16),
+            (projects.get_unchecked(1), 34),
+            (projects.get_unchecked(2), 50),
+        ]
+    );
+    assert_eq!(shares.iter().map(|(_, share)| share).sum::<i128>(), 100);
+
+    // Slashed-out projects drop out of the split
+    client.slash_contribution(&governance, &projects.get_unchecked(0), &1);
+    let shares = client.compute_prorata_release(&7);
+    assert_eq!(shares.len(), 2);
+    assert_eq!(shares.iter().map(|(_, share)| share).sum::<i128>(), 7);
+
+    assert_eq!(
+        client.try_compute_prorata_release(&0),
+        Err(Ok(Error::InvalidState))
+    );
+}
+
+#[test]
+fn test_execute_prorata_release() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    client.initialize(&admin, &governance, &asset.address, &500);
+    let projects = setup_prorata_projects(&env, &admin, &client);
+    asset.set_amount(&1, &10);
+
+    assert_eq!(
+        client.try_execute_prorata_release(&governance, &1, &5, &0),
+        Err(Ok(Error::RecipientNotSet))
+    );
+
+    let mut recipients = Vec::new(&env);
+    for project_id in projects.iter() {
+        let recipient = Address::generate(&env);
+        client.set_project_recipient(&governance, &project_id, &recipient);
+        recipients.push_back(recipient);
+    }
+
+    assert_eq!(
+        client.try_execute_prorata_release(&admin, &1, &5, &0),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_execute_prorata_release(&governance, &1, &11, &0),
+        Err(Ok(Error::InsufficientBalance))
+    );
+
+    assert_eq!(
+        client.execute_prorata_release(&governance, &1, &5, &0),
+        None
+    );
+    assert_eq!(asset.received(&recipients.get_unchecked(0), &1), 0);
+    assert_eq!(asset.received(&recipients.get_unchecked(1), &1), 2);
+    assert_eq!(asset.received(&recipients.get_unchecked(2), &1), 3);
+    assert_eq!(asset.amount_of(&1), 5);
+    assert!(client.is_token_in_pool(&1));
+    // TVL counts the six credits in custody, not their amounts
+    assert_eq!(client.get_total_value_locked(), 6);
+
+    // Releasing the rest empties token 1, which then leaves custody
+    client.execute_prorata_release(&governance, &1, &5, &0);
+    assert_eq!(asset.amount_of(&1), 0);
+    assert!(!client.is_token_in_pool(&1));
+    assert_eq!(client.get_total_value_locked(), 5);
+}
+
+#[test]
+fn test_prorata_release_resumes_from_snapshot() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    client.initialize(&admin, &governance, &asset.address, &500);
+
+    let projects = MAX_PRORATA_BATCH + 5;
+    let mut recipients = Vec::new(&env);
+    for token_id in 0..projects {
+        let mut name = [b'P', b'-', b'0', b'0'];
+        name[2] += (token_id / 10) as u8;
+        name[3] += (token_id % 10) as u8;
+        let project_id = String::from_bytes(&env, &name);
+        client.deposit(&admin, &token_id, &project_id, &None);
+        let recipient = Address::generate(&env);
+        client.set_project_recipient(&governance, &project_id, &recipient);
+        recipients.push_back((project_id, recipient));
+    }
+    let total_release = 10 * projects as i128;
+    asset.set_amount(&0, &total_release);
+
+    let cursor = client.execute_prorata_release(&governance, &0, &total_release, &0);
+    assert_eq!(cursor, Some(MAX_PRORATA_BATCH));
+    assert_eq!(
+        client.get_prorata_release(&0).unwrap().next,
+        MAX_PRORATA_BATCH
+    );
+
+    // A replay from the start or with another total is refused
+    assert_eq!(
+        client.try_execute_prorata_release(&governance, &0, &total_release, &0),
+        Err(Ok(Error::InvalidState))
+    );
+    assert_eq!(
+        client.try_execute_prorata_release(&governance, &0, &5, &MAX_PRORATA_BATCH),
+        Err(Ok(Error::InvalidState))
+    );
+
+    // Shares were fixed when the release started
+    let (last_project, _) = recipients.get_unchecked(projects - 1);
+    client.slash_contribution(&governance, &last_project, &1);
+
+    let cursor =
+        client.execute_prorata_release(&governance, &0, &total_release, &MAX_PRORATA_BATCH);
+    assert_eq!(cursor, None);
+    assert_eq!(client.get_prorata_release(&0), None);
+    for (_, recipient) in recipients.iter() {
+        assert_eq!(asset.received(&recipient, &0), 10);
+    }
+    assert_eq!(asset.amount_of(&0), 0);
+    assert!(!client.is_token_in_pool(&0));
+}
+
+#[test]
+fn test_donate_from_non_governance_account() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let donor = Address::generate(&env);
+
+    client.initialize(&admin, &governance, &asset.address, &500);
+    client.donate(&donor, &7);
+
+    assert!(asset.holds(&client.address, &7));
+    assert!(!asset.holds(&donor, &7));
+    assert_eq!(client.get_total_donations(), 1);
+    assert_eq!(client.get_total_value_locked(), 1);
+    let record = client.get_custody_record(&7).unwrap();
+    assert_eq!(record.depositor, donor);
+    assert_eq!(record.project_id, String::from_str(&env, "DONATION"));
+    assert_eq!(
+        client.get_contribution(&String::from_str(&env, "DONATION")),
+        0
+    );
+
+    assert_eq!(client.try_donate(&donor, &7), Err(Ok(Error::AlreadyExists)));
+}
+
+#[test]
+fn test_donate_amount() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let donor = Address::generate(&env);
+
+    client.initialize(&admin, &governance, &asset.address, &500);
+    asset.set_amount(&7, &10);
+
+    client.donate_amount(&donor, &7, &3);
+    assert_eq!(
+        vec![&env, env.events().all().last().unwrap()],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                AmountDonatedEvent {
+                    token_id: 7,
+                    donor: donor.clone(),
+                    amount: 3,
+                    total_amount: 3,
+                }
+                .topics(&env),
+                AmountDonatedEvent {
+                    token_id: 7,
+                    donor: donor.clone(),
+                    amount: 3,
+                    total_amount: 3,
+                }
+                .data(&env),
+            )
+        ]
+    );
+    client.donate_amount(&donor, &7, &2);
+
+    assert_eq!(asset.received(&client.address, &7), 5);
+    assert_eq!(asset.amount_of(&7), 5);
+    assert_eq!(client.get_donated_amount(&7), 5);
+    // An amount is not a credit, so custody and TVL are unchanged
+    assert!(!client.is_token_in_pool(&7));
+    assert_eq!(client.get_total_value_locked(), 0);
+
+    assert_eq!(
+        client.try_donate_amount(&donor, &7, &0),
+        Err(Ok(Error::InvalidState))
+    );
+}
+
+#[test]
+fn test_donated_amounts_leave_through_governance() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let donor = Address::generate(&env);
+    let recipient = Address::generate(&env);
+    let safe_address = Address::generate(&env);
+
+    client.initialize(&admin, &governance, &asset.address, &500);
+    asset.set_amount(&7, &10);
+    asset.set_amount(&8, &10);
+    client.donate_amount(&donor, &7, &5);
+    client.donate_amount(&donor, &8, &4);
+    assert_eq!(client.get_total_donated_amount(), 9);
+
+    assert_eq!(
+        client.try_withdraw_donated_amount(&admin, &7, &1, &recipient),
+        Err(Ok(Error::Unauthorized))
+    );
+    assert_eq!(
+        client.try_withdraw_donated_amount(&governance, &7, &6, &recipient),
+        Err(Ok(Error::InsufficientBalance))
+    );
+
+    client.withdraw_donated_amount(&governance, &7, &2, &recipient);
+    assert_eq!(asset.received(&recipient, &7), 2);
+    assert_eq!(client.get_donated_amount(&7), 3);
+    assert_eq!(client.get_total_donated_amount(), 7);
+
+    // Ids with nothing donated are skipped
+    assert_eq!(
+        client.sweep_donated_amounts(&governance, &safe_address, &vec![&env, 7, 8, 9]),
+        7
+    );
+    assert_eq!(asset.received(&safe_address, &7), 3);
+    assert_eq!(asset.received(&safe_address, &8), 4);
+    assert_eq!(client.get_donated_amount(&7), 0);
+    assert_eq!(client.get_total_donated_amount(), 0);
+    assert_eq!(
+        client.sweep_donated_amounts(&governance, &safe_address, &vec![&env, 7]),
+        0
+    );
+}
+
+/// Delta and new TVL carried by the TvlChangedEvent of the last invocation
+fn last_tvl_change(env: &Env) -> (i128, i128) {
+    let topics = TvlChangedEvent {
+        reason: TvlChangeReason::Deposit,
+        delta: 0,
+        new_tvl: 0,
+    }
+    .topics(env);
+    let (_, _, data) = env
+        .events()
+        .all()
+        .iter()
+        .find(|(_, event_topics, _)| *event_topics == topics)
+        .unwrap();
+    let data = Map::<Symbol, Val>::try_from_val(env, &data).unwrap();
+    let field =
+        |name: &str| i128::try_from_val(env, &data.get(Symbol::new(env, name)).unwrap()).unwrap();
+    (field("delta"), field("new_tvl"))
+}
+
+#[test]
+fn test_tvl_change_events_sum_to_tvl() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.initialize(&admin, &governance, &asset.address, &500);
+
+    let mut replayed: i128 = 0;
+    let mut record = |expected_delta: i128| {
+        let (delta, new_tvl) = last_tvl_change(&env);
+        assert_eq!(delta, expected_delta);
+        replayed += delta;
+        assert_eq!(new_tvl, replayed);
+    };
+
+    client.deposit(&admin, &1, &project_id, &None);
+    record(1);
+    client.deposit(&admin, &2, &project_id, &None);
+    record(1);
+    client.auto_deposit(&asset.address, &20, &project_id, &20, &None);
+    record(1);
+    client.donate(&Address::generate(&env), &7);
+    record(1);
+    client.withdraw_to_replace(&governance, &1, &99);
+    record(-1);
+    asset.set_amount(&2, &2);
+    client.withdraw_amount(&governance, &2, &1, &Address::generate(&env));
+    // Token 2 stays in custody, so the TVL is unchanged
+    assert_eq!(client.get_total_value_locked(), 3);
+    client.withdraw_amount(&governance, &2, &1, &Address::generate(&env));
+    record(-1);
+
+    assert_eq!(replayed, client.get_total_value_locked());
+    assert_eq!(replayed, 2);
+}
+
+#[test]
+fn test_migrate_reserve_subset() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let destination = BufferPoolContractClient::new(&env, &env.register(BufferPoolContract, ()));
+    client.initialize(&admin, &governance, &asset.address, &500);
+    // The new pool accepts deposits from the old one during migration
+    destination.initialize(&client.address, &governance, &asset.address, &500);
+
+    let project_a = String::from_str(&env, "PROJECT-A");
+    let project_b = String::from_str(&env, "PROJECT-B");
+    client.deposit(&admin, &1, &project_a, &None);
+    client.deposit(&admin, &2, &project_a, &None);
+    client.deposit(&admin, &3, &project_b, &Some(2020));
+
+    let result =
+        client.try_migrate_reserve(&governance, &destination.address, &vec![&env, 1, 9], &0);
+    assert_eq!(result, Err(Ok(Error::TokenNotFound)));
+    let result = client.try_migrate_reserve(&admin, &destination.address, &vec![&env, 1], &0);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let cursor = client.migrate_reserve(&governance, &destination.address, &vec![&env, 1, 3], &0);
+    assert_eq!(cursor, None);
+
+    assert_eq!(client.get_total_value_locked(), 1);
+    assert!(client.is_token_in_pool(&2));
+    assert!(!client.is_token_in_pool(&1));
+    assert_eq!(destination.get_total_value_locked(), 2);
+    assert_eq!(
+        destination.get_custody_record(&3).unwrap().project_id,
+        project_b
+    );
+    assert_eq!(destination.get_tvl_by_vintage(&2020), 1);
+    assert_eq!(client.get_tvl_by_vintage(&2020), 0);
+    assert!(asset.holds(&destination.address, &1));
+    assert!(asset.holds(&destination.address, &3));
+}
+
+#[test]
+fn test_migrate_reserve_resumes_from_cursor() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let destination = BufferPoolContractClient::new(&env, &env.register(BufferPoolContract, ()));
+    client.initialize(&admin, &governance, &asset.address, &500);
+    destination.initialize(&client.address, &governance, &asset.address, &500);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    let mut token_ids = Vec::new(&env);
+    for token_id in 0..MAX_MIGRATION_BATCH + 2 {
+        client.deposit(&admin, &token_id, &project_id, &None);
+        token_ids.push_back(token_id);
+    }
+
+    let cursor = client.migrate_reserve(&governance, &destination.address, &token_ids, &0);
+    assert_eq!(cursor, Some(MAX_MIGRATION_BATCH));
+    assert_eq!(client.get_total_value_locked(), 2);
+
+    let cursor = client.migrate_reserve(
+        &governance,
+        &destination.address,
+        &token_ids,
+        &MAX_MIGRATION_BATCH,
+    );
+    assert_eq!(cursor, None);
+    assert_eq!(client.get_total_value_locked(), 0);
+    assert_eq!(
+        destination.get_total_value_locked(),
+        (MAX_MIGRATION_BATCH + 2) as i128
+    );
+}
+
+#[test]
+fn test_emergency_sweep_evacuates_all_holdings() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    client.initialize(&admin, &governance, &asset.address, &500);
+    let safe_address = Address::generate(&env);
+
+    let project_a = String::from_str(&env, "PROJECT-A");
+    let project_b = String::from_str(&env, "PROJECT-B");
+    let total = MAX_SWEEP_BATCH + 3;
+    for token_id in 0..total {
+        let project_id = if token_id % 2 == 0 {
+            &project_a
+        } else {
+            &project_b
+        };
+        client.deposit(&admin, &token_id, project_id, &None);
+    }
+    // A credit that already left custody is not swept
+    client.withdraw_to_replace(&governance, &0, &100);
+    assert_eq!(client.get_held_token_count(), total - 1);
+    assert_eq!(
+        client.get_held_tokens(&0, &5),
+        (vec![&env, 1, 2, 3, 4], Some(5))
+    );
+
+    let result = client.try_emergency_sweep(&admin, &safe_address, &0);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+
+    let cursor = client.emergency_sweep(&governance, &safe_address, &0);
+    assert_eq!(cursor, Some(MAX_SWEEP_BATCH));
+    // The emptied slot of credit 0 counts towards the batch
+    assert_eq!(client.get_total_value_locked(), 3);
+    assert!(!client.is_token_in_pool(&1));
+    assert!(asset.holds(&safe_address, &1));
+
+    assert_eq!(
+        client.emergency_sweep(&governance, &safe_address, &MAX_SWEEP_BATCH),
+        None
+    );
+    assert_eq!(
+        vec![&env, env.events().all().last().unwrap()],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                EmergencySweepEvent {
+                    token_id: total - 1,
+                    safe_address: safe_address.clone(),
+                    project_id: project_b.clone(),
+                }
+                .topics(&env),
+                EmergencySweepEvent {
+                    token_id: total - 1,
+                    safe_address: safe_address.clone(),
+                    project_id: project_b.clone(),
+                }
+                .data(&env),
+            )
+        ]
+    );
+    assert_eq!(client.get_total_value_locked(), 0);
+    for token_id in 1..total {
+        assert!(!client.is_token_in_pool(&token_id));
+        assert!(asset.holds(&safe_address, &token_id));
+    }
+    assert!(!asset.holds(&safe_address, &0));
+
+    // Nothing left to move
+    assert_eq!(client.get_held_token_count(), 0);
+    assert_eq!(
+        client.get_held_tokens(&0, &MAX_SWEEP_BATCH),
+        (Vec::new(&env), Some(MAX_SWEEP_BATCH))
+    );
+}
+
+#[test]
+fn test_emergency_sweep_with_unlisted_custody() {
+    let (env, admin, governance, _, client) = setup_test_env();
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    client.initialize(&admin, &governance, &asset.address, &500);
+    let safe_address = Address::generate(&env);
+
+    let project_id = String::from_str(&env, "PROJECT-001");
+    client.deposit(&admin, &1, &project_id, &None);
+    client.deposit(&admin, &2, &project_id, &None);
+
+    // Credit 7 entered custody before the pool kept its custody list
+    env.as_contract(&client.address, || {
+        let record = CustodyRecord {
+            token_id: 7,
+            deposited_at: 0,
+            depositor: admin.clone(),
+            project_id: project_id.clone(),
+        };
+        env.storage().persistent().set(&(CUSTODY, 7u32), &record);
+        set_total_value_locked(&env, get_total_value_locked(&env) + 1);
+    });
+    assert_eq!(client.get_held_tokens(&0, &10), (vec![&env, 1, 2], None));
+
+    // The listed credits leave, but the unlisted one stays counted
+    assert_eq!(client.emergency_sweep(&governance, &safe_address, &0), None);
+    assert_eq!(client.get_total_value_locked(), 1);
+    assert!(client.is_token_in_pool(&7));
+    assert!(!asset.holds(&safe_address, &7));
+
+    let result = client.try_backfill_held_tokens(&admin, &vec![&env, 7]);
+    assert_eq!(result, Err(Ok(Error::Unauthorized)));
+    let result = client.try_backfill_held_tokens(&governance, &vec![&env, 8]);
+    assert_eq!(result, Err(Ok(Error::TokenNotFound)));
+
+    assert_eq!(client.backfill_held_tokens(&governance, &vec![&env, 7]), 1);
+    // Already listed ids are skipped
+    assert_eq!(client.backfill_held_tokens(&governance, &vec![&env, 7]), 0);
+    assert_eq!(client.get_held_tokens(&0, &10), (vec![&env, 7], None));
+
+    assert_eq!(client.emergency_sweep(&governance, &safe_address, &0), None);
+    assert_eq!(client.get_total_value_locked(), 0);
+    assert!(!client.is_token_in_pool(&7));
+    assert!(asset.holds(&safe_address, &7));
+}
+
+#[test]
+fn test_error_codes_are_stable() {
+    assert_eq!(error_code(Error::Unauthorized), 1);
+    assert_eq!(error_code(Error::InvalidTokenId), 2);
+    assert_eq!(error_code(Error::InvalidPercentage), 3);
+    assert_eq!(error_code(Error::InsufficientBalance), 4);
+    assert_eq!(error_code(Error::TokenNotFound), 5);
+    assert_eq!(error_code(Error::AlreadyExists), 6);
+    assert_eq!(error_code(Error::InvalidState), 7);
+    assert_eq!(error_code(Error::PercentageCooldownActive), 8);
+    assert_eq!(error_code(Error::RecipientNotSet), 9);
+    assert_eq!(error_code(Error::Paused), 10);
+    assert_eq!(error_code(Error::NotVoter), 11);
+    assert_eq!(error_code(Error::ProposalNotFound), 12);
+    assert_eq!(error_code(Error::AlreadyVoted), 13);
+    assert_eq!(error_code(Error::InsufficientVotes), 14);
+    assert_eq!(error_code(Error::UnknownParameter), 15);
+    assert_eq!(error_code(Error::ProposalExpired), 16);
+}
+
+#[test]
+fn test_parameter_proposal_from_creation_to_execution() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+
+    let voters = vec![
+        &env,
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    assert_eq!(
+        client.try_set_voters(&governance, &voters, &4),
+        Err(Ok(Error::InvalidState))
+    );
+    client.set_voters(&governance, &voters, &2);
+    assert_eq!(client.get_voters(), (voters.clone(), 2));
+
+    let stranger = Address::generate(&env);
+    let rate = Symbol::new(&env, "rep_pct");
+    assert_eq!(
+        client.try_propose_parameter(&stranger, &rate, &1000),
+        Err(Ok(Error::NotVoter))
+    );
+    assert_eq!(
+        client.try_propose_parameter(&voters.get_unchecked(0), &Symbol::new(&env, "tvl"), &1),
+        Err(Ok(Error::UnknownParameter))
+    );
+
+    let proposal_id = client.propose_parameter(&voters.get_unchecked(0), &rate, &1000);
+    client.vote(&voters.get_unchecked(0), &proposal_id, &true);
+    client.vote(&voters.get_unchecked(1), &proposal_id, &false);
+    assert_eq!(
+        client.try_vote(&voters.get_unchecked(0), &proposal_id, &true),
+        Err(Ok(Error::AlreadyVoted))
+    );
+    assert_eq!(
+        client.try_execute_proposal(&proposal_id),
+        Err(Ok(Error::InsufficientVotes))
+    );
+
+    client.vote(&voters.get_unchecked(2), &proposal_id, &true);
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    assert_eq!((proposal.approvals, proposal.rejections), (2, 1));
+
+    client.execute_proposal(&proposal_id);
+    assert_eq!(client.get_replenishment_rate(), 1000);
+    assert!(client.get_proposal(&proposal_id).unwrap().executed);
+    assert_eq!(
+        client.try_execute_proposal(&proposal_id),
+        Err(Ok(Error::InvalidState))
+    );
+
+    // Band bounds are validated against the current band on execution
+    let band_min = client.propose_parameter(
+        &voters.get_unchecked(1),
+        &Symbol::new(&env, "tgt_min"),
+        &2000,
+    );
+    client.vote(&voters.get_unchecked(0), &band_min, &true);
+    client.vote(&voters.get_unchecked(1), &band_min, &true);
+    client.execute_proposal(&band_min);
+    assert_eq!(client.get_target_band(), (2000, 10000));
+    assert_eq!(
+        client.try_execute_proposal(&99),
+        Err(Ok(Error::ProposalNotFound))
+    );
+}
+
+#[test]
+fn test_proposals_close_on_voter_change_and_expiry() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+    let rate = Symbol::new(&env, "rep_pct");
+
+    let voters = vec![&env, Address::generate(&env), Address::generate(&env)];
+    client.set_voters(&governance, &voters, &2);
+    let proposal_id = client.propose_parameter(&voters.get_unchecked(0), &rate, &1000);
+    client.vote(&voters.get_unchecked(0), &proposal_id, &true);
+    client.vote(&voters.get_unchecked(1), &proposal_id, &true);
+
+    // Replacing the voter set voids approvals given under the old one
+    let new_voters = vec![&env, voters.get_unchecked(0), Address::generate(&env)];
+    client.set_voters(&governance, &new_voters, &1);
+    assert_eq!(
+        client.try_execute_proposal(&proposal_id),
+        Err(Ok(Error::ProposalExpired))
+    );
+    assert_eq!(
+        client.try_vote(&new_voters.get_unchecked(1), &proposal_id, &true),
+        Err(Ok(Error::ProposalExpired))
+    );
+    assert_eq!(client.get_replenishment_rate(), 500);
+
+    let proposal_id = client.propose_parameter(&new_voters.get_unchecked(1), &rate, &800);
+    let proposal = client.get_proposal(&proposal_id).unwrap();
+    env.ledger()
+        .set_sequence_number(proposal.expires_at_ledger + 1);
+    assert_eq!(
+        client.try_vote(&new_voters.get_unchecked(1), &proposal_id, &true),
+        Err(Ok(Error::ProposalExpired))
+    );
+
+    // A fresh proposal within its lifetime still goes through
+    let proposal_id = client.propose_parameter(&new_voters.get_unchecked(1), &rate, &800);
+    client.vote(&new_voters.get_unchecked(1), &proposal_id, &true);
+    client.execute_proposal(&proposal_id);
+    assert_eq!(client.get_replenishment_rate(), 800);
+}
+
+#[contract]
+pub struct MockController;
+
+#[contractimpl]
+impl MockController {
+    pub fn set_paused(env: Env, paused: bool) {
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "paused"), &paused);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, "paused"))
+            .unwrap_or(false)
+    }
+}
+
+#[test]
+fn test_controller_pause_blocks_deposit() {
+    let (env, admin, governance, carbon_contract, client) = setup_test_env();
+    let controller = MockControllerClient::new(&env, &env.register(MockController, ()));
+    client.initialize(&admin, &governance, &carbon_contract, &500);
+    let project_id = String::from_str(&env, "PROJECT-001");
+
+    assert_eq!(
+        client.try_set_controller(&admin, &Some(controller.address.clone())),
+        Err(Ok(Error::Unauthorized))
+    );
+    client.set_controller(&governance, &Some(controller.address.clone()));
+    assert_eq!(client.get_controller(), Some(controller.address.clone()));
+
+    controller.set_paused(&true);
+    assert_eq!(
+        client.try_deposit(&admin, &1, &project_id, &None),
+        Err(Ok(Error::Paused))
+    );
+    assert!(!client.is_token_in_pool(&1));
+
+    controller.set_paused(&false);
+    client.deposit(&admin, &1, &project_id, &None);
+    assert!(client.is_token_in_pool(&1));
 }
@@ -24,12 +24,12 @@ fn test_full_lifecycle() {
     let project_id = String::from_str(&env, "PROJECT-001");
 
     for i in 1..=100 {
-        let should_deposit = client.auto_deposit(&carbon_contract, &i, &project_id, &i);
+        let should_deposit = client.auto_deposit(&carbon_contract, &i, &project_id, &i, &None);
 
         if i % 20 == 0 {
-            assert_eq!(should_deposit, true);
+            assert!(should_deposit);
         } else {
-            assert_eq!(should_deposit, false);
+            assert!(!should_deposit);
         }
     }
 
@@ -52,7 +52,7 @@ fn test_full_lifecycle() {
 
     // Admin manually deposits
     let project_id_2 = String::from_str(&env, "PROJECT-002");
-    client.deposit(&admin, &999, &project_id_2);
+    client.deposit(&admin, &999, &project_id_2, &None);
 
     let tvl = client.get_total_value_locked();
     assert_eq!(tvl, 5);
@@ -90,11 +90,11 @@ fn test_governance_updates() {
     let project_id = String::from_str(&env, "PROJECT-001");
 
     // With 10% (1000 bp), every 10th token should be deposited
-    let should_deposit = client.auto_deposit(&carbon_contract, &10, &project_id, &10);
-    assert_eq!(should_deposit, true);
+    let should_deposit = client.auto_deposit(&carbon_contract, &10, &project_id, &10, &None);
+    assert!(should_deposit);
 
-    let should_not_deposit = client.auto_deposit(&carbon_contract, &11, &project_id, &11);
-    assert_eq!(should_not_deposit, false);
+    let should_not_deposit = client.auto_deposit(&carbon_contract, &11, &project_id, &11, &None);
+    assert!(!should_not_deposit);
 }
 
 #[test]
@@ -111,14 +111,14 @@ fn test_multiple_projects() {
     client.initialize(&admin, &governance, &carbon_contract, &500);
 
     // Deposit from multiple projects
-    let projects = vec![
+    let projects = [
         String::from_str(&env, "PROJECT-A"),
         String::from_str(&env, "PROJECT-B"),
         String::from_str(&env, "PROJECT-C"),
     ];
 
     for (i, project) in projects.iter().enumerate() {
-        client.deposit(&admin, &((i as u32) + 1), project);
+        client.deposit(&admin, &((i as u32) + 1), project, &None);
     }
 
     let tvl = client.get_total_value_locked();
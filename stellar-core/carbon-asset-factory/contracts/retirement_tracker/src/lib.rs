@@ -1,7 +1,7 @@
 #![no_std]
 use soroban_sdk::{
-    contract, contracterror, contractevent, contractimpl, contracttype, Address, Bytes, BytesN,
-    Env, IntoVal, String, Symbol, Vec,
+    contract, contractclient, contracterror, contractevent, contractimpl, contracttype, Address,
+    Bytes, BytesN, Env, Executable, IntoVal, Map, String, Symbol, Vec,
 };
 
 // ========================================================================
@@ -9,14 +9,203 @@ use soroban_sdk::{
 // ========================================================================
 
 /// Core retirement record (immutable once written)
-#[derive(Clone)]
+#[derive(Clone, Debug, Eq, PartialEq)]
 #[contracttype]
 pub struct RetirementRecord {
-    pub token_id: u32,            // ID of the retired CarbonAsset
-    pub retiring_entity: Address, // Stellar account who retired the credit
-    pub timestamp: u64,           // Ledger timestamp of retirement
-    pub tx_hash: BytesN<32>,      // Hash of the retirement transaction
-    pub reason: Option<String>,   // Optional field for corporate reporting
+    pub token_id: u32,                 // ID of the retired CarbonAsset
+    pub retiring_entity: Address,      // Stellar account who retired the credit
+    pub timestamp: u64,                // Ledger timestamp of retirement
+    pub tx_hash: BytesN<32>,           // Hash of the retirement transaction
+    pub reason: Option<String>,        // Optional field for corporate reporting
+    pub initiated_by: Option<Address>, // Intermediary contract that invoked the retirement
+    pub ledger_seq: u32,               // Ledger sequence of retirement
+    pub fee: i128,                     // Amount sent to the buffer pool as retirement fee
+    pub standard: Option<Symbol>,      // Issuing standard of the credit, e.g. VERRA
+    pub project_id: Option<u32>,       // Project the credit was issued under, if known
+}
+
+/// Certificate anchored alongside a retirement by `retire_certified`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetirementCertificate {
+    pub certificate_id: BytesN<32>, // Deterministic id derived from the record
+    pub token_id: u32,              // ID of the retired CarbonAsset
+    pub beneficiary: Address,       // Party the offset is claimed on behalf of
+    pub certificate_hash: BytesN<32>, // Hash of the off-chain certificate document
+}
+
+/// Reservation held while an atomic-mode retirement is burning its token
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PendingRetirement {
+    pub token_id: u32,            // ID of the token being retired
+    pub retiring_entity: Address, // Account the token is being burned from
+    pub reserved_at: u64,         // Ledger timestamp the reservation was taken
+}
+
+/// Filing details stored alongside a retirement by `retire_compliance`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ComplianceDetails {
+    pub beneficiary: Address, // Party the offset is claimed on behalf of
+    pub standard: Symbol,     // Issuing standard named in the filing, e.g. VERRA
+    pub vintage: u32,         // Vintage year of the credit
+    pub serial: String,       // Registry serial number of the credit
+}
+
+/// Aggregate retirement state captured by `take_snapshot`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct Snapshot {
+    pub snapshot_id: u32,   // Sequential id, starting at 0
+    pub total_retired: u64, // Number of tokens retired so far
+    pub total_amount: i128, // Credits retired so far through amount-aware calls
+    pub timestamp: u64,     // Ledger timestamp of the snapshot
+    pub ledger_seq: u32,    // Ledger sequence of the snapshot
+}
+
+/// Aggregate reporting figures returned by `get_stats`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetirementStats {
+    pub total_retired: u64, // Number of tokens retired so far
+    pub total_amount: i128, // Credits retired so far through amount-aware calls
+    pub entity_count: u32,  // Number of distinct retiring entities
+    pub units: Symbol,      // Unit of `total_amount`, e.g. tCO2e
+    pub decimals: u32,      // Decimal places of `total_amount`
+}
+
+/// Retirements aggregated over a time window, returned by `get_range_stats`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RangeStats {
+    pub count: u64,             // Tokens fully retired in the window
+    pub total_amount: i128,     // Credits retired in the window through amount-aware calls
+    pub distinct_entities: u32, // Number of distinct retiring entities in the window
+}
+
+/// Retirements of one UTC day, kept for `get_range_stats`
+///
+/// Entities are not listed. Each one active that day is counted under the
+/// number of days since it was last active, capped at `MAX_STATS_DAYS`, so
+/// a window can count an entity on its first day inside it only.
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct DayRetirements {
+    pub count: u64,                     // Tokens fully retired that day
+    pub total_amount: i128,             // Credits retired that day through amount-aware calls
+    pub entities_by_gap: Map<u32, u32>, // days since previous activity -> entities
+}
+
+/// Confirmation requirement attached to a retirement by `retire_with_claim`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetirementClaim {
+    pub deadline: u64,   // Ledger timestamp by which the claim must be confirmed
+    pub confirmed: bool, // Set by `confirm_claim`
+}
+
+/// State of a retirement's offset claim, derived from its `RetirementClaim`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ClaimStatus {
+    NotRequired,  // Retired without a claim deadline
+    Pending(u64), // Awaiting confirmation until the deadline
+    Confirmed,    // Confirmed by the admin in time
+    Expired,      // Deadline passed without confirmation; the offset claim is void
+}
+
+/// Full tracker configuration returned by `get_config`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct TrackerConfig {
+    pub admin: Address,                      // Current admin
+    pub carbon_asset_contract: Address,      // CarbonAsset contract burned against
+    pub fee_bps: u32,                        // Retirement fee in basis points
+    pub buffer_pool: Option<Address>,        // Pool receiving retirement fees
+    pub time_lock_contract: Option<Address>, // TimeLock consulted by `get_token_status`
+    pub notify_contract: Option<Address>,    // Listener notified after each retirement
+    pub registry_root: Option<BytesN<32>>,   // Merkle root checked by `retire_with_proof`
+    pub units: Symbol,                       // Unit of retired amounts
+    pub decimals: u32,                       // Decimal places of retired amounts
+    pub controller: Option<Address>,         // Shared controller that can pause retirements
+    pub atomic_retire: bool,                 // Whether retirements reserve before burning
+    pub index_by_entity: bool,               // Whether EntityIndex is maintained
+}
+
+/// Denormalized view of one retirement, returned by `get_retirement_report_row`
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct ReportRow {
+    pub token_id: u32,                        // ID of the retired CarbonAsset
+    pub retiring_entity: Address,             // Stellar account who retired the credit
+    pub timestamp: u64,                       // Ledger timestamp of retirement
+    pub ledger_seq: u32,                      // Ledger sequence of retirement
+    pub tx_hash: BytesN<32>,                  // Hash of the retirement transaction
+    pub reason: Option<String>,               // Reason given at retirement
+    pub initiated_by: Option<Address>,        // Intermediary contract, if any
+    pub fee: i128,                            // Retirement fee sent to the buffer pool
+    pub standard: Option<Symbol>,             // Issuing standard of the credit
+    pub project_id: Option<u32>,              // Project the credit was issued under
+    pub partial_retired: i128,                // Credits burned by earlier partial retirements
+    pub beneficiary: Option<Address>,         // Certificate beneficiary, if certified
+    pub certificate_id: Option<BytesN<32>>,   // Certificate id, if certified
+    pub certificate_hash: Option<BytesN<32>>, // Off-chain certificate document hash
+    pub claim_status: ClaimStatus,            // State of a time-limited offset claim
+    pub units: Symbol,                        // Unit of `fee` and `partial_retired`
+    pub decimals: u32,                        // Decimal places of `fee` and `partial_retired`
+}
+
+/// Reporting category for amount-based retirements
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum RetirementReason {
+    Voluntary,
+    Compliance,
+    Other,
+}
+
+/// Where a token currently sits across the TimeLock and the retirement ledger
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum TokenStatus {
+    Free,
+    Locked(u64),  // Unlock timestamp from the TimeLock
+    Retired(u64), // Retirement timestamp
+}
+
+/// One partial burn of a token, appended to its retirement history
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct PartialRetirement {
+    pub amount: i128,             // Credits burned from the token
+    pub timestamp: u64,           // Ledger timestamp of the burn
+    pub retiring_entity: Address, // Account the credits were burned from
+}
+
+/// Cap on the credits an entity may retire through amount-aware calls per period
+#[derive(Clone, Debug, Eq, PartialEq)]
+#[contracttype]
+pub struct RetirementQuota {
+    pub amount: i128,              // Credits allowed per window
+    pub period_seconds: u64,       // Window length
+    pub used: i128,                // Credits retired within the last `period_seconds`
+    pub charges: Vec<(u64, i128)>, // (timestamp, credits) of retirements still in the window
+}
+
+/// Asset-side details of a token, read before it is burned
+struct IssuanceInfo {
+    standard: Option<Symbol>, // Issuing standard, for assets exposing `standard_of`
+    project_id: Option<u32>,  // Project, for assets exposing `project_of`
+    vintage: Option<u32>,     // Vintage year, for assets exposing `vintage_of`
+    amount: i128,             // Credits the token represents, 0 when not needed
+}
+
+/// Fee and quota charges of retiring a whole token, settled around its burn
+struct RetirementCharges {
+    fee: i128,                      // Credits donated to the buffer pool
+    buffer_pool: Option<Address>,   // Pool receiving the fee, when one is charged
+    quota: Option<RetirementQuota>, // Entity quota including this retirement
 }
 
 /// Storage keys for the contract
@@ -25,16 +214,86 @@ pub struct RetirementRecord {
 pub enum DataKey {
     Admin,
     CarbonAssetContract,
-    RetirementLedger(u32), // token_id -> RetirementRecord
-    EntityIndex(Address),  // retiring_entity -> Vec<u32>
+    RetirementLedger(u32),            // token_id -> RetirementRecord
+    EntityIndex(Address),             // retiring_entity -> Vec<u32>
+    NotifyContract,                   // Optional listener notified after each retirement
+    Certificate(u32),                 // token_id -> RetirementCertificate
+    CertificateIndex(BytesN<32>),     // certificate_id -> token_id
+    EntityList(u32),                  // position -> retiring entity, in first-retirement order
+    EntityCount,                      // Number of entries in EntityList
+    PartialRetired(u32),              // token_id -> amount retired by partial burns
+    TotalRetired,                     // Number of tokens retired
+    TotalAmountRetired,               // Credits retired through amount-aware calls
+    Snapshot(u32),                    // snapshot_id -> Snapshot
+    SnapshotCount,                    // Number of snapshots taken
+    TimeLockContract,                 // Optional TimeLock queried by get_token_status
+    CategoryAmount(RetirementReason), // category -> credits retired through amount-aware calls
+    LedgerBucket(u32),                // ledger_seq / LEDGER_BUCKET_SIZE -> Vec<u32> of token IDs
+    Quota(Address),                   // retiring_entity -> RetirementQuota
+    RetirementFeeBps,                 // Fee charged by `retire`, in basis points of the amount
+    BufferPool,                       // Optional buffer pool receiving retirement fees
+    RegistryRoot,                     // Merkle root of registry-approved serials
+    RetirementHistory(u32),           // token_id -> Vec<PartialRetirement>, append-only
+    TrustedRetirer(Address),          // contract -> true when it may retire its own holdings
+    Units,                            // Symbol naming the unit of retired amounts
+    Decimals,                         // Decimal places of retired amounts
+    LargestIndexes,                   // Vec<(Address, u32)> of the largest EntityIndex sizes
+    DayRetirements(u64),              // timestamp / SECONDS_PER_DAY -> DayRetirements
+    StandardCount(Symbol),            // issuing standard -> number of tokens retired under it
+    Claim(u32),                       // token_id -> RetirementClaim, for time-limited claims
+    Controller,                       // Optional shared controller checked before retirements
+    ProjectRetirements(u32),          // project_id -> Vec<u32> of retired token IDs
+    ProjectAmount(u32),               // project_id -> credits retired from the project's tokens
+    Compliance(u32),                  // token_id -> ComplianceDetails, for compliance filings
+    AtomicRetire,                     // bool, reserve-burn-commit retirements when set
+    PendingRetirement(u32),           // token_id -> PendingRetirement while its burn runs
+    RetiredIds(u32),                  // token_id / ID_BUCKET_SIZE -> sorted Vec<u32> of token IDs
+    MaxRetiredId,                     // Highest token ID in the RetiredIds index
+    IndexByEntity,                    // bool, EntityIndex is maintained unless set to false
+    VintageAmount(u32),               // vintage -> credits retired from tokens of that vintage
+    MemoHash(u32),                    // token_id -> BytesN<32> hash of a reason kept off-chain
+    RetiredCredits(u32),              // token_id -> (vintage, credits) in project/vintage totals
+    EntityRetired(Address),           // retiring_entity -> true, kept even without IndexByEntity
+    DayEntity(u64, Address),          // (day, retiring_entity) -> tokens it retired that day
+    EntityLastDay(Address),           // retiring_entity -> last day it was active
+}
+
+// ========================================================================
+// Storage TTL
+// ========================================================================
+
+// Retirement records are permanent audit data, so persistent entries are
+// bumped to ~120 days whenever they are written and re-bumped once less than
+// ~90 days remain. Instance storage (config) follows the usual 30 day window.
+const DAY_IN_LEDGERS: u32 = 17280; // Approximately 1 day worth of ledgers (5s per ledger)
+const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS;
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+const PERSISTENT_BUMP_AMOUNT: u32 = 120 * DAY_IN_LEDGERS;
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+
+fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+fn extend_persistent_ttl(env: &Env, key: &DataKey) {
+    env.storage().persistent().extend_ttl(
+        key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
 }
 
 // ========================================================================
 // Contract Errors
 // ========================================================================
 
-#[derive(Clone, Copy)]
+/// Error codes are part of the public interface: discriminants are assigned
+/// explicitly and must never be renumbered or reused.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
 #[contracterror]
+#[repr(u32)]
 pub enum ContractError {
     NotAuthorized = 1,
     TokenNotOwned = 2,
@@ -42,8 +301,63 @@ pub enum ContractError {
     InvalidTokenId = 4,
     BurnFailed = 5,
     ContractNotInitialized = 6,
+    InvalidRange = 7,
+    InsufficientBalance = 8,
+    InvalidAmount = 9,
+    EnumerationUnsupported = 10,
+    QuotaExceeded = 11,
+    TimeLockNotConfigured = 12,
+    InvalidProof = 13,
+    ClaimExpired = 14,
+    InvalidDeadline = 15,
+    Paused = 16,
+    InvalidComplianceData = 17,
+    RetirementPending = 18,
+    BurnNotFinalized = 19,
+    TokenNotRetired = 20,
+}
+
+/// Stable numeric code of a `ContractError`, as seen by cross-contract callers
+pub fn error_code(error: ContractError) -> u32 {
+    error as u32
 }
 
+/// Maximum number of token IDs covered by a single `retire_range` call
+pub const MAX_RANGE_SIZE: u32 = 100;
+
+/// Maximum number of tokens retired by a single `retire_all_held` call
+pub const MAX_HELD_BATCH_SIZE: u32 = 25;
+
+/// Number of ledgers grouped into one bucket of the ledger sequence index
+pub const LEDGER_BUCKET_SIZE: u32 = 1000;
+
+/// Maximum number of ledger buckets scanned by `get_retirements_by_ledger_range`
+pub const MAX_LEDGER_BUCKETS: u32 = 50;
+
+/// Number of token IDs grouped into one bucket of the sorted retired-id index
+pub const ID_BUCKET_SIZE: u32 = 100;
+
+/// Maximum number of retired-id buckets scanned by `export_retirements`
+pub const MAX_EXPORT_BUCKETS: u32 = 50;
+
+/// Maximum number of entries returned by a single paginated query
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Unit reported for retired amounts when none was set at initialization
+pub const DEFAULT_UNITS: &str = "tCO2e";
+
+/// Maximum number of sibling hashes accepted by `retire_with_proof`
+pub const MAX_PROOF_DEPTH: u32 = 20;
+
+/// Length of one bucket of the daily retirement index
+pub const SECONDS_PER_DAY: u64 = 86_400;
+
+/// Maximum number of days covered by a single `get_range_stats` call
+pub const MAX_STATS_DAYS: u64 = 92;
+
+/// Number of entities tracked by `get_largest_indexes`
+pub const MAX_LARGEST_INDEXES: u32 = 20;
+
 // ========================================================================
 // Events
 // ========================================================================
@@ -54,6 +368,58 @@ pub struct RetirementEvent {
     pub retiring_entity: Address,
     pub timestamp: u64,
     pub tx_hash: BytesN<32>,
+    pub initiated_by: Option<Address>,
+    pub reemitted: bool,        // True when replayed via reemit_retirement_event
+    pub fee: i128,              // Amount sent to the buffer pool as retirement fee
+    pub reason: Option<String>, // Reason given for the retirement, if any
+}
+
+/// Emitted when the admin voids a retirement recorded in error
+#[contractevent]
+pub struct RetirementVoidedEvent {
+    #[topic]
+    pub token_id: u32,
+    pub retiring_entity: Address,
+    pub voided_by: Address,
+}
+
+/// Emitted when the retirement listener rejects or fails a notification
+#[contractevent]
+pub struct NotificationFailedEvent {
+    pub token_id: u32,
+    pub listener: Address,
+}
+
+/// Emitted when part of a token's amount is burned without retiring the token
+#[contractevent]
+pub struct PartialRetirementEvent {
+    pub token_id: u32,
+    pub retiring_entity: Address,
+    pub amount: i128,
+    pub timestamp: u64,
+    pub fee: i128, // Credits donated to the buffer pool on top of `amount`
+}
+
+/// Emitted by `retire_compliance` with every field a compliance filing needs
+#[contractevent]
+pub struct ComplianceRetirementEvent {
+    #[topic]
+    pub token_id: u32,
+    pub retiring_entity: Address,
+    pub beneficiary: Address,
+    pub standard: Symbol,
+    pub vintage: u32,
+    pub serial: String,
+    pub reason: Option<String>,
+    pub timestamp: u64,
+    pub tx_hash: BytesN<32>,
+}
+
+/// Emitted when the admin confirms a time-limited offset claim
+#[contractevent]
+pub struct ClaimConfirmedEvent {
+    pub token_id: u32,
+    pub confirmed_by: Address,
 }
 
 #[contractevent]
@@ -63,6 +429,109 @@ pub struct ContractUpdatedEvent {
     pub updated_by: Address,
 }
 
+// ========================================================================
+// CarbonAsset Interface
+// ========================================================================
+
+/// Subset of the CarbonAsset interface used for amount and holdings based retirements
+#[allow(dead_code)]
+#[contractclient(name = "CarbonAssetClient")]
+pub trait CarbonAssetInterface {
+    /// Current owner of `token_id`, if it exists
+    fn owner_of(env: Env, token_id: u32) -> Option<Address>;
+
+    /// Owner and amount of `token_id` in a single call, checked against `from`
+    /// by the caller. Optional: assets without it are queried through
+    /// `owner_of` and `amount_of`.
+    fn retire_info(env: Env, token_id: u32, from: Address) -> (Address, i128);
+
+    /// IDs of all tokens currently held by `owner`
+    fn tokens_of(env: Env, owner: Address) -> Vec<u32>;
+
+    /// Quantity of credits (e.g. tonnes) represented by `token_id`
+    fn amount_of(env: Env, token_id: u32) -> i128;
+
+    /// Burn `amount` from `token_id` held by `from`, leaving the token in place
+    fn burn_amount(env: Env, token_id: u32, from: Address, amount: i128);
+
+    /// Move `amount` of `token_id` held by `from` to `to`
+    fn transfer_amount(env: Env, from: Address, to: Address, token_id: u32, amount: i128);
+
+    /// Issuing standard of `token_id` (e.g. VERRA, GOLD). Optional: records
+    /// of assets without it carry no standard.
+    fn standard_of(env: Env, token_id: u32) -> Symbol;
+
+    /// Project `token_id` was issued under. Optional: records of assets
+    /// without it carry no project.
+    fn project_of(env: Env, token_id: u32) -> u32;
+
+    /// Vintage year of `token_id`. Optional: retirements of assets without
+    /// it count towards no vintage.
+    fn vintage_of(env: Env, token_id: u32) -> u32;
+}
+
+/// Subset of the buffer pool interface used to pay retirement fees
+#[allow(dead_code)]
+#[contractclient(name = "BufferPoolClient")]
+pub trait BufferPoolInterface {
+    /// Pull `amount` of `token_id` from `from` into the pool as a donation
+    fn donate_amount(env: Env, from: Address, token_id: u32, amount: i128);
+}
+
+/// What the TimeLock does with a credit once its lock expires
+///
+/// Mirrors the TimeLock's `ExpiryAction` so `lock_credit` can be called
+/// across contracts.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[contracttype]
+pub enum ExpiryAction {
+    ReturnToOwner,
+    Retire,
+    DivertToBuffer,
+}
+
+/// Subset of the TimeLock interface used by `get_token_status` and
+/// `retire_and_lock_residue`
+#[allow(dead_code)]
+#[contractclient(name = "TimeLockClient")]
+pub trait TimeLockInterface {
+    /// Unlock timestamp of a locked token, or `None` if it is not locked
+    fn get_unlock_timestamp(env: Env, token_id: u32) -> Option<u64>;
+
+    /// Lock `token_id` held by `owner` until `unlock_timestamp`
+    fn lock_credit(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        token_id: u32,
+        unlock_timestamp: u64,
+        release_to: Option<Address>,
+        on_expiry: Option<ExpiryAction>,
+    );
+}
+
+// ========================================================================
+// Retirement Listener
+// ========================================================================
+
+/// Interface a listener contract implements to be notified of retirements
+#[allow(dead_code)]
+#[contractclient(name = "RetirementListenerClient")]
+pub trait RetirementListener {
+    fn on_retirement(env: Env, token_id: u32, retiring_entity: Address);
+}
+
+// ========================================================================
+// Circuit Breaker
+// ========================================================================
+
+/// Interface of a controller contract that pauses a whole deployment at once
+#[allow(dead_code)]
+#[contractclient(name = "ControllerClient")]
+pub trait ControllerInterface {
+    fn is_paused(env: Env) -> bool;
+}
+
 // ========================================================================
 // Contract Implementation
 // ========================================================================
@@ -89,10 +558,39 @@ impl RetirementTracker {
         env.storage()
             .instance()
             .set(&DataKey::CarbonAssetContract, &carbon_asset_contract);
+        extend_instance_ttl(&env);
+    }
+
+    /// Initialize the contract with the unit and precision of retired amounts
+    ///
+    /// Same as `initialize`, additionally recording the metadata clients use
+    /// to format amounts, e.g. `tCO2e` with 3 decimals for kilogram precision.
+    ///
+    /// # Arguments
+    /// * `admin` - CarbonScribe admin address
+    /// * `carbon_asset_contract` - Address of the CarbonAsset contract
+    /// * `units` - Unit of credit amounts
+    /// * `decimals` - Decimal places of credit amounts
+    pub fn initialize_with_units(
+        env: Env,
+        admin: Address,
+        carbon_asset_contract: Address,
+        units: Symbol,
+        decimals: u32,
+    ) {
+        Self::initialize(env.clone(), admin, carbon_asset_contract);
+
+        env.storage().instance().set(&DataKey::Units, &units);
+        env.storage().instance().set(&DataKey::Decimals, &decimals);
     }
 
     /// Retire a single carbon credit token
     ///
+    /// When a retirement fee and buffer pool are configured, `fee_bps` of the
+    /// token's amount is donated from the entity to the pool before the
+    /// token is burned, and recorded as `fee` on the record and event. Every
+    /// other retirement of a whole token charges the fee the same way.
+    ///
     /// # Arguments
     /// * `token_id` - The ID of the CarbonAsset token to retire
     /// * `retiring_entity` - The Stellar account address retiring the credit
@@ -104,6 +602,8 @@ impl RetirementTracker {
     /// # Errors
     /// * `ContractError::TokenNotOwned` - Caller does not own the token
     /// * `ContractError::TokenAlreadyRetired` - Token has already been retired
+    /// * `ContractError::NotAuthorized` - `retiring_entity` is an untrusted contract
+    /// * `ContractError::QuotaExceeded` - The token's amount exceeds the entity's remaining quota
     /// * `ContractError::BurnFailed` - Failed to burn the token
     pub fn retire(
         env: Env,
@@ -114,180 +614,2942 @@ impl RetirementTracker {
         // Verify caller is authenticated
         retiring_entity.require_auth();
 
-        // Check if token is already retired
-        let ledger_key = DataKey::RetirementLedger(token_id);
-        if env.storage().persistent().has(&ledger_key) {
-            return Err(ContractError::TokenAlreadyRetired);
-        }
-
-        // Get carbon asset contract address
-        let carbon_asset_contract: Address = env
-            .storage()
-            .instance()
-            .get(&DataKey::CarbonAssetContract)
-            .ok_or(ContractError::ContractNotInitialized)?;
-
-        // Get current timestamp
-        let timestamp = env.ledger().timestamp();
-
-        // Generate a unique transaction hash from current ledger state
-        // This combines ledger sequence, timestamp, and token_id for uniqueness
-        // Note: In production, you might want to pass the actual transaction hash as a parameter
-        // For now, we create a deterministic hash from available ledger data
-        let ledger_seq = env.ledger().sequence();
-
-        // Create hash input from components as a byte array
-        // We'll manually construct bytes from token_id, timestamp, and ledger_seq
-        let mut hash_bytes = [0u8; 20];
-        hash_bytes[0..4].copy_from_slice(&token_id.to_be_bytes());
-        hash_bytes[4..12].copy_from_slice(&timestamp.to_be_bytes());
-        hash_bytes[12..20].copy_from_slice(&ledger_seq.to_be_bytes());
+        Self::retire_internal(&env, token_id, retiring_entity, reason, None)
+    }
 
-        let hash_input = Bytes::from_array(&env, &hash_bytes);
-        let hash = env.crypto().sha256(&hash_input);
-        let tx_hash = BytesN::from_array(&env, &hash.to_array());
+    /// Retire a token without storing a retirement record
+    ///
+    /// For high-volume flows whose records are kept off-chain: the token is
+    /// burned and a `RetirementEvent` is emitted, but no `RetirementLedger`,
+    /// `EntityIndex` or ledger bucket entry is written and the listener is
+    /// not notified. The reason is only carried by the event. As a
+    /// result, `is_retired`, the entity queries and the totals do not reflect
+    /// these retirements; the burned token cannot be retired again.
+    ///
+    /// # Returns
+    /// The deterministic retirement hash carried by the event
+    ///
+    /// # Errors
+    /// * `ContractError::TokenAlreadyRetired` - Token has a stored retirement record
+    /// * `ContractError::NotAuthorized` - `retiring_entity` is an untrusted contract
+    /// * `ContractError::BurnFailed` - Failed to burn the token
+    pub fn retire_event_only(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        reason: Option<String>,
+    ) -> Result<BytesN<32>, ContractError> {
+        retiring_entity.require_auth();
 
-        // Call burn on CarbonAsset contract
-        // The contract must be pre-authorized as a burner on the CarbonAsset contract
-        // We assume CarbonAsset has a burn function that accepts (token_id: u32, from: Address)
-        // The CarbonAsset contract should verify ownership before allowing burn
-        let burn_symbol = Symbol::new(&env, "burn");
-        let mut burn_args = Vec::new(&env);
-        burn_args.push_back(token_id.into_val(&env));
-        burn_args.push_back(retiring_entity.clone().into_val(&env));
-        env.invoke_contract::<()>(&carbon_asset_contract, &burn_symbol, burn_args);
+        Self::check_retirable(&env, token_id, &retiring_entity)?;
+        let carbon_asset_contract = Self::carbon_asset_contract(&env)?;
+        let amount = if Self::is_retirement_charged(&env, &retiring_entity) {
+            let carbon_asset = CarbonAssetClient::new(&env, &carbon_asset_contract);
+            Self::owned_balance(&carbon_asset, token_id, &retiring_entity)?
+        } else {
+            0
+        };
+        let charges = Self::retirement_charges(&env, &retiring_entity, amount)?;
+        Self::pay_retirement_fee(&env, token_id, &retiring_entity, &charges);
+        Self::burn_token(&env, &carbon_asset_contract, token_id, &retiring_entity)?;
+        Self::store_quota(&env, &retiring_entity, charges.quota);
+        let fee = charges.fee;
 
-        // Create retirement record
-        let record = RetirementRecord {
+        let timestamp = env.ledger().timestamp();
+        let tx_hash = Self::compute_retire_hash(
+            env.clone(),
             token_id,
-            retiring_entity: retiring_entity.clone(),
+            retiring_entity.clone(),
             timestamp,
-            tx_hash: tx_hash.clone(),
-            reason: reason.clone(),
-        };
-
-        // Store in retirement ledger
-        env.storage().persistent().set(&ledger_key, &record);
-
-        // Update entity index
-        let entity_key = DataKey::EntityIndex(retiring_entity.clone());
-        let mut entity_retirements: Vec<u32> = env
-            .storage()
-            .persistent()
-            .get(&entity_key)
-            .unwrap_or(Vec::new(&env));
-        entity_retirements.push_back(token_id);
-        env.storage()
-            .persistent()
-            .set(&entity_key, &entity_retirements);
+            env.ledger().sequence(),
+        );
+        extend_instance_ttl(&env);
 
-        // Emit event
         RetirementEvent {
             token_id,
-            retiring_entity: retiring_entity.clone(),
+            retiring_entity,
             timestamp,
-            tx_hash,
+            tx_hash: tx_hash.clone(),
+            initiated_by: None,
+            reemitted: false,
+            fee,
+            reason,
         }
         .publish(&env);
-        Ok(record)
+
+        Ok(tx_hash)
     }
 
-    /// Retire multiple carbon credit tokens in a single transaction
+    /// Retire a token whose serial is proven to be in the registry's Merkle tree
     ///
-    /// # Arguments
-    /// * `token_ids` - Vector of token IDs to retire
-    /// * `retiring_entity` - The Stellar account address retiring the credits
-    /// * `reason` - Optional reason for retirement (applied to all tokens)
-    ///
-    /// # Returns
-    /// Vector of RetirementRecords created
+    /// The leaf is derived from the token and its registry serial via
+    /// `compute_proof_leaf`, so a proof only ever retires the token it was
+    /// issued for. It is hashed up the tree with each sibling in `proof`,
+    /// ordering every pair lexicographically before SHA-256 as the merkle
+    /// bridge does, and must reach the root set via `set_registry_root`. The
+    /// retirement itself then proceeds as in `retire`.
     ///
     /// # Errors
-    /// Returns errors for individual tokens that fail, but continues processing others
-    pub fn batch_retire(
+    /// * `ContractError::InvalidProof` - No registry root is set, the proof is
+    ///   deeper than `MAX_PROOF_DEPTH`, or it does not reach the root
+    /// * Otherwise the same as `retire`
+    pub fn retire_with_proof(
         env: Env,
-        token_ids: Vec<u32>,
+        token_id: u32,
         retiring_entity: Address,
+        serial: String,
+        proof: Vec<BytesN<32>>,
         reason: Option<String>,
-    ) -> Vec<RetirementRecord> {
-        retiring_entity.require_auth();
-
-        let mut results = Vec::new(&env);
-
-        for i in 0..token_ids.len() {
-            let token_id = token_ids.get(i).unwrap();
+    ) -> Result<RetirementRecord, ContractError> {
+        let root: BytesN<32> = env
+            .storage()
+            .instance()
+            .get(&DataKey::RegistryRoot)
+            .ok_or(ContractError::InvalidProof)?;
+        if proof.len() > MAX_PROOF_DEPTH {
+            return Err(ContractError::InvalidProof);
+        }
 
-            // Attempt to retire each token
-            // Continue even if one fails
-            if let Ok(record) = Self::retire(
-                env.clone(),
-                token_id,
-                retiring_entity.clone(),
-                reason.clone(),
-            ) {
-                results.push_back(record);
-            }
+        let mut current = Self::compute_proof_leaf(env.clone(), token_id, serial);
+        for sibling in proof.iter() {
+            let (left, right) = if current <= sibling {
+                (current, sibling)
+            } else {
+                (sibling, current)
+            };
+            let mut combined = Bytes::from_array(&env, &left.to_array());
+            combined.append(&Bytes::from_array(&env, &right.to_array()));
+            current = env.crypto().sha256(&combined).into();
+        }
+        if current != root {
+            return Err(ContractError::InvalidProof);
         }
 
-        results
+        Self::retire(env, token_id, retiring_entity, reason)
     }
 
-    /// Check if a token has been retired
-    ///
-    /// # Arguments
-    /// * `token_id` - The token ID to check
+    /// Compute the Merkle leaf `retire_with_proof` checks for a token
     ///
-    /// # Returns
-    /// `true` if the token is retired, `false` otherwise
-    pub fn is_retired(env: Env, token_id: u32) -> bool {
-        let ledger_key = DataKey::RetirementLedger(token_id);
-        env.storage().persistent().has(&ledger_key)
+    /// `sha256(token_id || serial)`, with `token_id` big-endian, so the
+    /// registry tree pairs each approved serial with the token it backs.
+    pub fn compute_proof_leaf(env: Env, token_id: u32, serial: String) -> BytesN<32> {
+        let mut preimage = Bytes::from_array(&env, &token_id.to_be_bytes());
+        preimage.append(&serial.to_bytes());
+        env.crypto().sha256(&preimage).into()
     }
 
-    /// Get the full retirement record for a token
+    /// Retire a single carbon credit token on behalf of an intermediary
+    ///
+    /// Used when retirement is initiated through another contract (e.g. a
+    /// marketplace). The intermediary must authorize the call and is recorded
+    /// as `initiated_by` on the record and event.
     ///
     /// # Arguments
-    /// * `token_id` - The token ID to query
+    /// * `token_id` - The ID of the CarbonAsset token to retire
+    /// * `retiring_entity` - The Stellar account address retiring the credit
+    /// * `via` - The intermediary contract initiating the retirement
+    /// * `reason` - Optional reason for retirement (for corporate reporting)
     ///
-    /// # Returns
-    /// `Some(RetirementRecord)` if the token is retired, `None` otherwise
-    pub fn get_retirement_record(env: Env, token_id: u32) -> Option<RetirementRecord> {
-        let ledger_key = DataKey::RetirementLedger(token_id);
-        env.storage().persistent().get(&ledger_key)
+    /// # Errors
+    /// Same as `retire`
+    pub fn retire_via(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        via: Address,
+        reason: Option<String>,
+    ) -> Result<RetirementRecord, ContractError> {
+        via.require_auth();
+        retiring_entity.require_auth();
+
+        Self::retire_internal(&env, token_id, retiring_entity, reason, Some(via))
     }
 
-    /// Get all token IDs retired by a specific entity
+    /// Record a retirement initiated by the CarbonAsset contract itself
+    ///
+    /// Lets the asset contract expose its own retire method: it verifies
+    /// ownership and burns the token, then calls back into the tracker, which
+    /// records the retirement without invoking `burn` again. Only the
+    /// configured CarbonAsset contract can authorize this call, and it is
+    /// recorded as `initiated_by`.
     ///
     /// # Arguments
-    /// * `retiring_entity` - The address to query
+    /// * `token_id` - The ID of the already burned CarbonAsset token
+    /// * `retiring_entity` - The address the token was burned from
+    /// * `reason` - Optional reason for retirement (for corporate reporting)
+    /// * `standard` - Issuing standard of the burned credit, if known
+    /// * `project_id` - Project the burned credit was issued under, if known
+    ///
+    /// # Errors
+    /// * `ContractError::ContractNotInitialized` - Contract has not been initialized
+    /// * `ContractError::TokenAlreadyRetired` - Token has already been retired
+    /// * `ContractError::NotAuthorized` - `retiring_entity` is an untrusted contract
+    pub fn record_asset_retirement(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        reason: Option<String>,
+        standard: Option<Symbol>,
+        project_id: Option<u32>,
+    ) -> Result<RetirementRecord, ContractError> {
+        let carbon_asset_contract = Self::carbon_asset_contract(&env)?;
+        carbon_asset_contract.require_auth();
+
+        Self::check_retirable(&env, token_id, &retiring_entity)?;
+        // The token is already burned, so its amount is no longer known
+        let issuance = IssuanceInfo {
+            standard,
+            project_id,
+            vintage: None,
+            amount: 0,
+        };
+        Ok(Self::record_retirement(
+            &env,
+            token_id,
+            retiring_entity,
+            reason,
+            Some(carbon_asset_contract),
+            0,
+            issuance,
+        ))
+    }
+
+    /// Retire a token and record everything a compliance filing needs
+    ///
+    /// Stores the filing details next to the retirement record and emits a
+    /// single `ComplianceRetirementEvent` bundling them with the record's
+    /// timestamp and hash, in addition to the usual `RetirementEvent`.
+    ///
+    /// # Arguments
+    /// * `token_id` - The ID of the CarbonAsset token to retire
+    /// * `retiring_entity` - The Stellar account address retiring the credit
+    /// * `beneficiary` - The party the offset is claimed on behalf of
+    /// * `standard` - Issuing standard named in the filing
+    /// * `vintage` - Vintage year of the credit
+    /// * `serial` - Registry serial number of the credit
+    /// * `reason` - Optional reason for retirement (for corporate reporting)
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidComplianceData` - `serial` is empty or `vintage` is 0
+    /// * Otherwise the same as `retire`
+    #[allow(clippy::too_many_arguments)]
+    pub fn retire_compliance(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        beneficiary: Address,
+        standard: Symbol,
+        vintage: u32,
+        serial: String,
+        reason: Option<String>,
+    ) -> Result<RetirementRecord, ContractError> {
+        retiring_entity.require_auth();
+
+        if serial.is_empty() || vintage == 0 {
+            return Err(ContractError::InvalidComplianceData);
+        }
+
+        let record = Self::retire_internal(
+            &env,
+            token_id,
+            retiring_entity.clone(),
+            reason.clone(),
+            None,
+        )?;
+
+        let details = ComplianceDetails {
+            beneficiary: beneficiary.clone(),
+            standard: standard.clone(),
+            vintage,
+            serial: serial.clone(),
+        };
+        let key = DataKey::Compliance(token_id);
+        env.storage().persistent().set(&key, &details);
+        extend_persistent_ttl(&env, &key);
+
+        ComplianceRetirementEvent {
+            token_id,
+            retiring_entity,
+            beneficiary,
+            standard,
+            vintage,
+            serial,
+            reason,
+            timestamp: record.timestamp,
+            tx_hash: record.tx_hash.clone(),
+        }
+        .publish(&env);
+
+        Ok(record)
+    }
+
+    /// Get the filing details stored by `retire_compliance`, if any
+    pub fn get_compliance_details(env: Env, token_id: u32) -> Option<ComplianceDetails> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Compliance(token_id))
+    }
+
+    /// Retire a token, recording only a hash of the retirement reason
+    ///
+    /// A privacy-preserving alternative to the `reason` string: the record
+    /// carries no reason, and the entity can later reveal the preimage of
+    /// `memo_hash` off-chain for anyone to check against `get_memo_hash`.
+    ///
+    /// # Errors
+    /// The same as `retire`
+    pub fn retire_with_memo_hash(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        memo_hash: BytesN<32>,
+    ) -> Result<RetirementRecord, ContractError> {
+        retiring_entity.require_auth();
+
+        let record = Self::retire_internal(&env, token_id, retiring_entity, None, None)?;
+
+        let key = DataKey::MemoHash(token_id);
+        env.storage().persistent().set(&key, &memo_hash);
+        extend_persistent_ttl(&env, &key);
+
+        Ok(record)
+    }
+
+    /// Get the memo hash stored by `retire_with_memo_hash`, if any
+    pub fn get_memo_hash(env: Env, token_id: u32) -> Option<BytesN<32>> {
+        env.storage().persistent().get(&DataKey::MemoHash(token_id))
+    }
+
+    /// Retire a token and anchor its certificate in one call
+    ///
+    /// The certificate id is `sha256(tx_hash || certificate_hash)` of the new
+    /// record, and resolves back to the record via `get_retirement_by_certificate`.
+    ///
+    /// # Arguments
+    /// * `token_id` - The ID of the CarbonAsset token to retire
+    /// * `retiring_entity` - The Stellar account address retiring the credit
+    /// * `beneficiary` - The party the offset is claimed on behalf of
+    /// * `reason` - Optional reason for retirement (for corporate reporting)
+    /// * `certificate_hash` - Hash of the off-chain retirement certificate
+    ///
+    /// # Returns
+    /// The certificate id
+    ///
+    /// # Errors
+    /// Same as `retire`
+    pub fn retire_certified(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        beneficiary: Address,
+        reason: Option<String>,
+        certificate_hash: BytesN<32>,
+    ) -> Result<BytesN<32>, ContractError> {
+        retiring_entity.require_auth();
+
+        let record = Self::retire_internal(&env, token_id, retiring_entity, reason, None)?;
+
+        Ok(Self::anchor_certificate(
+            &env,
+            &record,
+            beneficiary,
+            certificate_hash,
+        ))
+    }
+
+    /// Anchor certificates for tokens that were retired without one (admin only)
+    ///
+    /// Each certificate names the token's retiring entity as beneficiary and
+    /// gets the same id `retire_certified` would have issued. Certificates are
+    /// write-once: tokens that already have one are left untouched.
+    ///
+    /// # Returns
+    /// The token IDs skipped because they already had a certificate
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    /// * `ContractError::InvalidRange` - More than `MAX_PAGE_SIZE` entries supplied
+    /// * `ContractError::InvalidTokenId` - A token has not been retired
+    pub fn batch_anchor_certificates(
+        env: Env,
+        caller: Address,
+        entries: Vec<(u32, BytesN<32>)>,
+    ) -> Result<Vec<u32>, ContractError> {
+        Self::require_admin(&env, &caller)?;
+        if entries.len() > MAX_PAGE_SIZE {
+            return Err(ContractError::InvalidRange);
+        }
+
+        let mut skipped = Vec::new(&env);
+        for (token_id, certificate_hash) in entries.iter() {
+            let record = Self::get_retirement_record(env.clone(), token_id)
+                .ok_or(ContractError::InvalidTokenId)?;
+            if env
+                .storage()
+                .persistent()
+                .has(&DataKey::Certificate(token_id))
+            {
+                skipped.push_back(token_id);
+                continue;
+            }
+
+            let beneficiary = record.retiring_entity.clone();
+            Self::anchor_certificate(&env, &record, beneficiary, certificate_hash);
+        }
+
+        Ok(skipped)
+    }
+
+    /// Retire a token whose offset claim must be confirmed before a deadline
+    ///
+    /// Retires like `retire`, then requires the admin to `confirm_claim` the
+    /// retirement by `claim_deadline`. An unconfirmed claim reads as
+    /// `ClaimStatus::Expired` once the deadline has passed. The token stays
+    /// retired either way; expiry only voids the offset claim.
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidDeadline` - `claim_deadline` is not in the future
+    /// * Otherwise the same as `retire`
+    pub fn retire_with_claim(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        reason: Option<String>,
+        claim_deadline: u64,
+    ) -> Result<RetirementRecord, ContractError> {
+        if claim_deadline <= env.ledger().timestamp() {
+            return Err(ContractError::InvalidDeadline);
+        }
+
+        let record = Self::retire(env.clone(), token_id, retiring_entity, reason)?;
+
+        let claim_key = DataKey::Claim(token_id);
+        env.storage().persistent().set(
+            &claim_key,
+            &RetirementClaim {
+                deadline: claim_deadline,
+                confirmed: false,
+            },
+        );
+        extend_persistent_ttl(&env, &claim_key);
+
+        Ok(record)
+    }
+
+    /// Confirm a retirement's offset claim before its deadline (admin only)
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    /// * `ContractError::InvalidTokenId` - The retirement has no claim deadline
+    /// * `ContractError::ClaimExpired` - The deadline has passed
+    pub fn confirm_claim(env: Env, caller: Address, token_id: u32) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        let claim_key = DataKey::Claim(token_id);
+        let mut claim: RetirementClaim = env
+            .storage()
+            .persistent()
+            .get(&claim_key)
+            .ok_or(ContractError::InvalidTokenId)?;
+        if claim.confirmed {
+            return Ok(());
+        }
+        if env.ledger().timestamp() > claim.deadline {
+            return Err(ContractError::ClaimExpired);
+        }
+
+        claim.confirmed = true;
+        env.storage().persistent().set(&claim_key, &claim);
+        extend_persistent_ttl(&env, &claim_key);
+
+        ClaimConfirmedEvent {
+            token_id,
+            confirmed_by: caller,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Get the state of a retirement's offset claim
+    pub fn get_claim_status(env: Env, token_id: u32) -> ClaimStatus {
+        let claim: Option<RetirementClaim> =
+            env.storage().persistent().get(&DataKey::Claim(token_id));
+        match claim {
+            None => ClaimStatus::NotRequired,
+            Some(claim) if claim.confirmed => ClaimStatus::Confirmed,
+            Some(claim) if env.ledger().timestamp() > claim.deadline => ClaimStatus::Expired,
+            Some(claim) => ClaimStatus::Pending(claim.deadline),
+        }
+    }
+
+    /// Retire multiple carbon credit tokens in a single transaction
+    ///
+    /// # Arguments
+    /// * `token_ids` - Vector of token IDs to retire
+    /// * `retiring_entity` - The Stellar account address retiring the credits
+    /// * `reason` - Optional reason for retirement (applied to all tokens)
+    ///
+    /// Repeated token IDs are only attempted once, so results follow the
+    /// order of first occurrence.
+    ///
+    /// Tokens that cannot be retired are skipped, as long as nothing was
+    /// committed for them; see `retire_batch_entry`.
+    ///
+    /// # Returns
+    /// Vector of RetirementRecords created
+    ///
+    /// # Errors
+    /// * `ContractError::BurnFailed` - A token's burn failed after its fee was paid
+    /// * `ContractError::BurnNotFinalized` - In atomic mode, a token's burn did not take effect
+    pub fn batch_retire(
+        env: Env,
+        token_ids: Vec<u32>,
+        retiring_entity: Address,
+        reason: Option<String>,
+    ) -> Result<Vec<RetirementRecord>, ContractError> {
+        retiring_entity.require_auth();
+
+        let mut results = Vec::new(&env);
+        let mut seen: Vec<u32> = Vec::new(&env);
+
+        for i in 0..token_ids.len() {
+            let token_id = token_ids.get(i).unwrap();
+            if seen.contains(token_id) {
+                continue;
+            }
+            seen.push_back(token_id);
+
+            if let Some(record) =
+                Self::retire_batch_entry(&env, token_id, &retiring_entity, &reason)?
+            {
+                results.push_back(record);
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Check whether `retiring_entity` could retire a token right now
+    ///
+    /// Applies the same checks as `retire` and additionally requires the
+    /// entity to be the token's current owner on the CarbonAsset contract.
+    /// Read-only.
+    pub fn can_retire(env: Env, token_id: u32, retiring_entity: Address) -> bool {
+        if Self::check_retirable(&env, token_id, &retiring_entity).is_err() {
+            return false;
+        }
+
+        let carbon_asset = match Self::carbon_asset_contract(&env) {
+            Ok(contract) => CarbonAssetClient::new(&env, &contract),
+            Err(_) => return false,
+        };
+        matches!(carbon_asset.try_owner_of(&token_id), Ok(Ok(Some(owner))) if owner == retiring_entity)
+    }
+
+    /// Pre-flight a `batch_retire` without changing any state
+    ///
+    /// # Returns
+    /// `(token_id, can_retire)` for each supplied token, in input order
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidRange` - More than `MAX_PAGE_SIZE` token IDs supplied
+    pub fn simulate_batch_retire(
+        env: Env,
+        token_ids: Vec<u32>,
+        retiring_entity: Address,
+    ) -> Result<Vec<(u32, bool)>, ContractError> {
+        if token_ids.len() > MAX_PAGE_SIZE {
+            return Err(ContractError::InvalidRange);
+        }
+
+        let mut outcomes = Vec::new(&env);
+        for token_id in token_ids.iter() {
+            let retirable = Self::can_retire(env.clone(), token_id, retiring_entity.clone());
+            outcomes.push_back((token_id, retirable));
+        }
+        Ok(outcomes)
+    }
+
+    /// Retire every token in the inclusive range `start_id..=end_id`
+    ///
+    /// Tokens that are already retired or cannot be burned from the entity
+    /// are skipped, as long as nothing was committed for them; see
+    /// `retire_batch_entry`.
+    ///
+    /// # Arguments
+    /// * `start_id` - First token ID in the range
+    /// * `end_id` - Last token ID in the range (inclusive)
+    /// * `retiring_entity` - The Stellar account address retiring the credits
+    /// * `reason` - Optional reason for retirement (applied to all tokens)
+    ///
+    /// # Returns
+    /// Vector of RetirementRecords created
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidRange` - `start_id > end_id` or the range exceeds `MAX_RANGE_SIZE`
+    /// * `ContractError::BurnFailed` - A token's burn failed after its fee was paid
+    /// * `ContractError::BurnNotFinalized` - In atomic mode, a token's burn did not take effect
+    pub fn retire_range(
+        env: Env,
+        start_id: u32,
+        end_id: u32,
+        retiring_entity: Address,
+        reason: Option<String>,
+    ) -> Result<Vec<RetirementRecord>, ContractError> {
+        if start_id > end_id || end_id - start_id >= MAX_RANGE_SIZE {
+            return Err(ContractError::InvalidRange);
+        }
+
+        retiring_entity.require_auth();
+
+        let mut results = Vec::new(&env);
+
+        for token_id in start_id..=end_id {
+            if let Some(record) =
+                Self::retire_batch_entry(&env, token_id, &retiring_entity, &reason)?
+            {
+                results.push_back(record);
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Retire exactly `target` credits from the supplied tokens
+    ///
+    /// Tokens are consumed greedily in the given order. Each token whose full
+    /// amount, net of its retirement fee, fits in the outstanding target is
+    /// retired as usual; the last token is partially burned for the
+    /// remainder, with its fee on top, and stays unretired. Repeated token
+    /// IDs are only counted once. Exactly `target` credits are retired net
+    /// of fees.
+    ///
+    /// # Arguments
+    /// * `retiring_entity` - The Stellar account address retiring the credits
+    /// * `token_ids` - Candidate tokens, in the order they should be consumed
+    /// * `target` - Total amount to retire
+    /// * `reason` - Optional reason for retirement (applied to all tokens)
+    /// * `category` - Reporting category for the amount, `Other` when `None`
+    ///
+    /// # Returns
+    /// `(token_id, amount)` pairs for every token retired from, in order,
+    /// net of fees
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidAmount` - `target` is not positive
+    /// * `ContractError::TokenAlreadyRetired` - A supplied token is already retired
+    /// * `ContractError::InsufficientBalance` - The tokens hold less than `target` combined, net of fees
+    /// * `ContractError::QuotaExceeded` - `target` exceeds the entity's remaining quota
+    /// * `ContractError::BurnFailed` - Failed to burn a token
+    pub fn retire_target_amount(
+        env: Env,
+        retiring_entity: Address,
+        token_ids: Vec<u32>,
+        target: i128,
+        reason: Option<String>,
+        category: Option<RetirementReason>,
+    ) -> Result<Vec<(u32, i128)>, ContractError> {
+        if target <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        retiring_entity.require_auth();
+
+        let carbon_asset = CarbonAssetClient::new(&env, &Self::carbon_asset_contract(&env)?);
+        let buffer_pool = Self::fee_pool(&env, &retiring_entity);
+
+        // Resolve amounts up front so an insufficient balance burns nothing
+        let mut candidates: Vec<(u32, i128)> = Vec::new(&env);
+        let mut seen: Vec<u32> = Vec::new(&env);
+        let mut available: i128 = 0;
+        for token_id in token_ids.iter() {
+            if seen.contains(token_id) {
+                continue;
+            }
+            seen.push_back(token_id);
+
+            if Self::is_retired(env.clone(), token_id) {
+                return Err(ContractError::TokenAlreadyRetired);
+            }
+
+            let amount = carbon_asset.amount_of(&token_id);
+            let net = amount - Self::fee_for(&env, &buffer_pool, amount);
+            available = available.saturating_add(net);
+            candidates.push_back((token_id, net));
+        }
+
+        if available < target {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        let mut retired = Vec::new(&env);
+        let mut remaining = target;
+        for (token_id, net) in candidates.iter() {
+            if remaining == 0 {
+                break;
+            }
+
+            if net <= remaining {
+                Self::retire_internal(
+                    &env,
+                    token_id,
+                    retiring_entity.clone(),
+                    reason.clone(),
+                    None,
+                )?;
+                retired.push_back((token_id, net));
+                remaining -= net;
+            } else {
+                Self::retire_partial(&env, &carbon_asset, token_id, &retiring_entity, remaining)?;
+                retired.push_back((token_id, remaining));
+                remaining = 0;
+            }
+        }
+
+        Self::record_amount_retired(&env, &retiring_entity, target, category);
+
+        Ok(retired)
+    }
+
+    /// Partially retire a token and lock the remaining balance in the TimeLock
+    ///
+    /// Burns `retire_amount` from the token like `retire_target_amount`, then
+    /// locks the token with its residual amount via the TimeLock's
+    /// `lock_credit` until `unlock_timestamp`, pending verification. The
+    /// retired amount counts towards the `Other` category and the entity's
+    /// quota. The retirement fee is charged on top of `retire_amount`.
+    ///
+    /// # Returns
+    /// `(retired, locked)` amounts, summing with the fee to the token's
+    /// original amount
+    ///
+    /// # Errors
+    /// * `ContractError::TimeLockNotConfigured` - No TimeLock is configured
+    /// * `ContractError::InvalidAmount` - `retire_amount` is not positive
+    /// * `ContractError::TokenAlreadyRetired` - Token has already been retired
+    /// * `ContractError::TokenNotOwned` - `retiring_entity` does not own the token
+    /// * `ContractError::InsufficientBalance` - `retire_amount` and its fee leave no residue to lock
+    /// * `ContractError::RetirementPending` - The token is mid-retirement
+    /// * `ContractError::NotAuthorized` - `retiring_entity` is an untrusted contract
+    /// * `ContractError::QuotaExceeded` - `retire_amount` exceeds the entity's remaining quota
+    /// * `ContractError::BurnFailed` - Failed to burn the amount
+    pub fn retire_and_lock_residue(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        retire_amount: i128,
+        unlock_timestamp: u64,
+    ) -> Result<(i128, i128), ContractError> {
+        retiring_entity.require_auth();
+
+        let time_lock = Self::time_lock_client(&env).ok_or(ContractError::TimeLockNotConfigured)?;
+        if retire_amount <= 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+        if Self::is_retired(env.clone(), token_id) {
+            return Err(ContractError::TokenAlreadyRetired);
+        }
+
+        let carbon_asset = CarbonAssetClient::new(&env, &Self::carbon_asset_contract(&env)?);
+        let balance = Self::owned_balance(&carbon_asset, token_id, &retiring_entity)?;
+        let fee = Self::fee_for(&env, &Self::fee_pool(&env, &retiring_entity), retire_amount);
+        if retire_amount + fee >= balance {
+            return Err(ContractError::InsufficientBalance);
+        }
+
+        Self::retire_partial(
+            &env,
+            &carbon_asset,
+            token_id,
+            &retiring_entity,
+            retire_amount,
+        )?;
+        Self::record_amount_retired(&env, &retiring_entity, retire_amount, None);
+
+        time_lock.lock_credit(
+            &retiring_entity,
+            &retiring_entity,
+            &token_id,
+            &unlock_timestamp,
+            &None,
+            &None,
+        );
+
+        Ok((retire_amount, balance - retire_amount - fee))
+    }
+
+    /// Retire every token the entity currently holds on the CarbonAsset contract
+    ///
+    /// Holdings are enumerated via the asset's `tokens_of` and retired at
+    /// most `MAX_HELD_BATCH_SIZE` per call, starting at position `cursor`
+    /// (0 starts fresh). Retired tokens leave the holdings list while failed
+    /// ones stay, so the returned cursor skips only failures. It is `None`
+    /// once no holdings remain past this batch.
+    ///
+    /// # Returns
+    /// The RetirementRecords created and the cursor to resume from
+    ///
+    /// # Errors
+    /// * `ContractError::EnumerationUnsupported` - The asset contract cannot list holdings
+    /// * `ContractError::BurnFailed` - A token's burn failed after its fee was paid
+    /// * `ContractError::BurnNotFinalized` - In atomic mode, a token's burn did not take effect
+    pub fn retire_all_held(
+        env: Env,
+        retiring_entity: Address,
+        reason: Option<String>,
+        cursor: u32,
+    ) -> Result<(Vec<RetirementRecord>, Option<u32>), ContractError> {
+        retiring_entity.require_auth();
+
+        let carbon_asset = CarbonAssetClient::new(&env, &Self::carbon_asset_contract(&env)?);
+        let held = match carbon_asset.try_tokens_of(&retiring_entity) {
+            Ok(Ok(held)) => held,
+            _ => return Err(ContractError::EnumerationUnsupported),
+        };
+
+        let end = cursor.saturating_add(MAX_HELD_BATCH_SIZE).min(held.len());
+        let mut results = Vec::new(&env);
+        let mut failed = 0;
+
+        for i in cursor..end {
+            match Self::retire_batch_entry(&env, held.get_unchecked(i), &retiring_entity, &reason)?
+            {
+                Some(record) => results.push_back(record),
+                None => failed += 1,
+            }
+        }
+
+        let next_cursor = if end < held.len() {
+            Some(cursor + failed)
+        } else {
+            None
+        };
+        Ok((results, next_cursor))
+    }
+
+    /// Get the cumulative amount partially retired from a token
+    pub fn get_partial_retired_amount(env: Env, token_id: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::PartialRetired(token_id))
+            .unwrap_or(0)
+    }
+
+    /// Get every partial retirement of a token, oldest first
+    pub fn get_retirement_history(env: Env, token_id: u32) -> Vec<PartialRetirement> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::RetirementHistory(token_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Check if a token has been retired
+    ///
+    /// # Arguments
+    /// * `token_id` - The token ID to check
+    ///
+    /// # Returns
+    /// `true` if the token is retired, `false` otherwise
+    pub fn is_retired(env: Env, token_id: u32) -> bool {
+        let ledger_key = DataKey::RetirementLedger(token_id);
+        env.storage().persistent().has(&ledger_key)
+    }
+
+    /// Get the full retirement record for a token
+    ///
+    /// # Arguments
+    /// * `token_id` - The token ID to query
+    ///
+    /// # Returns
+    /// `Some(RetirementRecord)` if the token is retired, `None` otherwise
+    pub fn get_retirement_record(env: Env, token_id: u32) -> Option<RetirementRecord> {
+        let ledger_key = DataKey::RetirementLedger(token_id);
+        env.storage().persistent().get(&ledger_key)
+    }
+
+    /// Get all token IDs retired by a specific entity
+    ///
+    /// # Arguments
+    /// * `retiring_entity` - The address to query
+    ///
+    /// # Returns
+    /// Vector of token IDs retired by the entity; always empty while
+    /// `index_by_entity` is disabled
+    pub fn get_retirements_by_entity(env: Env, retiring_entity: Address) -> Vec<u32> {
+        if !Self::is_index_by_entity(env.clone()) {
+            return Vec::new(&env);
+        }
+        let entity_key = DataKey::EntityIndex(retiring_entity);
+        env.storage()
+            .persistent()
+            .get(&entity_key)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get a retirement record with its place in the retiring entity's history
+    ///
+    /// # Returns
+    /// `Some((record, position, total))` where `position` is the 1-based
+    /// position of the token in the entity's index and `total` the number of
+    /// retirements indexed for the entity. `None` if the token is not retired
+    /// or not found in the index (e.g. while `index_by_entity` is disabled).
+    pub fn get_retirement_with_position(
+        env: Env,
+        token_id: u32,
+    ) -> Option<(RetirementRecord, u32, u32)> {
+        let record = Self::get_retirement_record(env.clone(), token_id)?;
+        let token_ids = Self::get_retirements_by_entity(env, record.retiring_entity.clone());
+        let index = token_ids.first_index_of(token_id)?;
+        Some((record, index + 1, token_ids.len()))
+    }
+
+    /// Get the certificate anchored for a retired token, if any
+    pub fn get_certificate(env: Env, token_id: u32) -> Option<RetirementCertificate> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Certificate(token_id))
+    }
+
+    /// Resolve a certificate id back to its retirement record
+    ///
+    /// # Returns
+    /// `Some(RetirementRecord)` if the id was issued by `retire_certified`, `None` otherwise
+    pub fn get_retirement_by_certificate(
+        env: Env,
+        certificate_id: BytesN<32>,
+    ) -> Option<RetirementRecord> {
+        let token_id: u32 = env
+            .storage()
+            .persistent()
+            .get(&DataKey::CertificateIndex(certificate_id))?;
+        Self::get_retirement_record(env, token_id)
+    }
+
+    /// Get a retirement as one flat row for CSV/JSON exporters
+    ///
+    /// Joins the retirement record with its certificate, claim status,
+    /// partial retirements and the tracker's units. Certificate fields are
+    /// `None` for retirements that were never certified.
+    ///
+    /// # Returns
+    /// `Some(ReportRow)` if the token is retired, `None` otherwise
+    pub fn get_retirement_report_row(env: Env, token_id: u32) -> Option<ReportRow> {
+        let record = Self::get_retirement_record(env.clone(), token_id)?;
+        let certificate = Self::get_certificate(env.clone(), token_id);
+        let (units, decimals) = Self::get_units(env.clone());
+
+        Some(ReportRow {
+            token_id,
+            retiring_entity: record.retiring_entity,
+            timestamp: record.timestamp,
+            ledger_seq: record.ledger_seq,
+            tx_hash: record.tx_hash,
+            reason: record.reason,
+            initiated_by: record.initiated_by,
+            fee: record.fee,
+            standard: record.standard,
+            project_id: record.project_id,
+            partial_retired: Self::get_partial_retired_amount(env.clone(), token_id),
+            beneficiary: certificate.as_ref().map(|c| c.beneficiary.clone()),
+            certificate_id: certificate.as_ref().map(|c| c.certificate_id.clone()),
+            certificate_hash: certificate.map(|c| c.certificate_hash),
+            claim_status: Self::get_claim_status(env, token_id),
+            units,
+            decimals,
+        })
+    }
+
+    /// Strict variant of `is_retired` that fails on an uninitialized contract
+    ///
+    /// `is_retired` reports `false` for any token on a contract that was never
+    /// initialized; this variant surfaces the misconfiguration instead.
+    ///
+    /// # Errors
+    /// * `ContractError::ContractNotInitialized` - The contract has no admin set
+    pub fn is_retired_strict(env: Env, token_id: u32) -> Result<bool, ContractError> {
+        Self::require_initialized(&env)?;
+        Ok(Self::is_retired(env, token_id))
+    }
+
+    /// Strict variant of `get_retirement_record` that fails on an uninitialized contract
+    ///
+    /// # Errors
+    /// * `ContractError::ContractNotInitialized` - The contract has no admin set
+    pub fn get_retirement_record_strict(
+        env: Env,
+        token_id: u32,
+    ) -> Result<Option<RetirementRecord>, ContractError> {
+        Self::require_initialized(&env)?;
+        Ok(Self::get_retirement_record(env, token_id))
+    }
+
+    /// Strict variant of `get_retirements_by_entity` that fails on an uninitialized contract
+    ///
+    /// # Errors
+    /// * `ContractError::ContractNotInitialized` - The contract has no admin set
+    pub fn get_retirements_by_entity_strict(
+        env: Env,
+        retiring_entity: Address,
+    ) -> Result<Vec<u32>, ContractError> {
+        Self::require_initialized(&env)?;
+        Ok(Self::get_retirements_by_entity(env, retiring_entity))
+    }
+
+    /// Get the token IDs retired within the inclusive ledger range `start_seq..=end_seq`
+    ///
+    /// Results are ordered by ledger bucket, then by retirement order.
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidRange` - `start_seq > end_seq` or the range spans more
+    ///   than `MAX_LEDGER_BUCKETS` buckets
+    pub fn get_retirements_by_ledger_range(
+        env: Env,
+        start_seq: u32,
+        end_seq: u32,
+    ) -> Result<Vec<u32>, ContractError> {
+        let first_bucket = start_seq / LEDGER_BUCKET_SIZE;
+        let last_bucket = end_seq / LEDGER_BUCKET_SIZE;
+        if start_seq > end_seq || last_bucket - first_bucket >= MAX_LEDGER_BUCKETS {
+            return Err(ContractError::InvalidRange);
+        }
+
+        let mut results = Vec::new(&env);
+        for bucket in first_bucket..=last_bucket {
+            let token_ids: Vec<u32> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::LedgerBucket(bucket))
+                .unwrap_or(Vec::new(&env));
+
+            for token_id in token_ids.iter() {
+                if let Some(record) = Self::get_retirement_record(env.clone(), token_id) {
+                    if (start_seq..=end_seq).contains(&record.ledger_seq) {
+                        results.push_back(token_id);
+                    }
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Export retirement records in ascending token ID order, for archival
+    ///
+    /// Returns up to `limit` records (capped at `MAX_PAGE_SIZE`) with token
+    /// IDs of at least `start_token_id`, read from the sorted retired-id
+    /// index. At most `MAX_EXPORT_BUCKETS` buckets of `ID_BUCKET_SIZE` IDs
+    /// are scanned per call, so a page can come back short or even empty
+    /// across a gap in the IDs. Only retirements recorded since the index
+    /// was introduced are included.
+    ///
+    /// # Returns
+    /// The page and the token ID to continue from, `None` once the export
+    /// is complete
+    pub fn export_retirements(
+        env: Env,
+        start_token_id: u32,
+        limit: u32,
+    ) -> (Vec<RetirementRecord>, Option<u32>) {
+        let mut page = Vec::new(&env);
+        let max_id = match Self::get_max_retired_id(env.clone()) {
+            Some(max_id) if max_id >= start_token_id => max_id,
+            _ => return (page, None),
+        };
+
+        let limit = limit.min(MAX_PAGE_SIZE);
+        let first_bucket = start_token_id / ID_BUCKET_SIZE;
+        let last_bucket = (max_id / ID_BUCKET_SIZE).min(first_bucket + (MAX_EXPORT_BUCKETS - 1));
+        for bucket in first_bucket..=last_bucket {
+            let token_ids: Vec<u32> = env
+                .storage()
+                .persistent()
+                .get(&DataKey::RetiredIds(bucket))
+                .unwrap_or(Vec::new(&env));
+
+            for token_id in token_ids.iter().filter(|id| *id >= start_token_id) {
+                if page.len() >= limit {
+                    return (page, Some(token_id));
+                }
+                if let Some(record) = Self::get_retirement_record(env.clone(), token_id) {
+                    page.push_back(record);
+                }
+            }
+        }
+
+        let next_start = if last_bucket < max_id / ID_BUCKET_SIZE {
+            Some((last_bucket + 1) * ID_BUCKET_SIZE)
+        } else {
+            None
+        };
+        (page, next_start)
+    }
+
+    /// Get the highest token ID covered by `export_retirements`, if any
+    pub fn get_max_retired_id(env: Env) -> Option<u32> {
+        env.storage().instance().get(&DataKey::MaxRetiredId)
+    }
+
+    /// Aggregate the retirements between `start_ts` and `end_ts`
+    ///
+    /// Walks the daily index, so the window is widened to whole UTC days:
+    /// every retirement on the days containing `start_ts` and `end_ts` counts.
+    /// Only retirements recorded since the daily index was introduced are
+    /// included.
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidRange` - `start_ts > end_ts` or the window spans more
+    ///   than `MAX_STATS_DAYS` days
+    pub fn get_range_stats(
+        env: Env,
+        start_ts: u64,
+        end_ts: u64,
+    ) -> Result<RangeStats, ContractError> {
+        let first_day = start_ts / SECONDS_PER_DAY;
+        let last_day = end_ts / SECONDS_PER_DAY;
+        if start_ts > end_ts || last_day - first_day >= MAX_STATS_DAYS {
+            return Err(ContractError::InvalidRange);
+        }
+
+        let mut count = 0;
+        let mut total_amount = 0;
+        let mut distinct_entities = 0;
+        for day in first_day..=last_day {
+            let Some(retirements) = env
+                .storage()
+                .persistent()
+                .get::<_, DayRetirements>(&DataKey::DayRetirements(day))
+            else {
+                continue;
+            };
+
+            count += retirements.count;
+            total_amount += retirements.total_amount;
+            // Entities last active before the window are new to it today
+            for (gap, entities) in retirements.entities_by_gap.iter() {
+                if u64::from(gap) > day - first_day {
+                    distinct_entities += entities;
+                }
+            }
+        }
+
+        Ok(RangeStats {
+            count,
+            total_amount,
+            distinct_entities,
+        })
+    }
+
+    /// Get the number of tokens `retiring_entity` fully retired on the UTC
+    /// day containing `timestamp`
+    pub fn get_entity_day_count(env: Env, retiring_entity: Address, timestamp: u64) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::DayEntity(
+                timestamp / SECONDS_PER_DAY,
+                retiring_entity,
+            ))
+            .unwrap_or(0)
+    }
+
+    /// Get the number of tokens retired under an issuing standard
+    pub fn get_count_by_standard(env: Env, standard: Symbol) -> u64 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::StandardCount(standard))
+            .unwrap_or(0)
+    }
+
+    /// Get the token IDs retired from a project, in retirement order
+    pub fn get_retirements_by_project(env: Env, project_id: u32) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ProjectRetirements(project_id))
+            .unwrap_or(Vec::new(&env))
+    }
+
+    /// Get the credits retired from a project's tokens
+    ///
+    /// Counts the remaining amount of each fully retired token and every
+    /// partial burn. Tokens recorded through `record_asset_retirement` are
+    /// already burned, so only their retirement is indexed.
+    pub fn get_amount_by_project(env: Env, project_id: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::ProjectAmount(project_id))
+            .unwrap_or(0)
+    }
+
+    /// Get the cumulative credits retired from tokens of `vintage`
+    ///
+    /// Maintained on every retirement like `get_amount_by_project`, so it
+    /// tracks how far the vintage's supply has been depleted. Tokens recorded
+    /// through `record_asset_retirement` are not counted.
+    pub fn get_retired_amount_by_vintage(env: Env, vintage: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::VintageAmount(vintage))
+            .unwrap_or(0)
+    }
+
+    /// Compute the `tx_hash` recorded for a retirement
+    ///
+    /// Pure helper exposing the exact algorithm `retire` uses, so off-chain
+    /// code can verify a record's hash: `sha256(token_id || timestamp ||
+    /// ledger_seq)` with the integers big-endian. `retiring_entity` is not
+    /// part of the preimage, so hashes of existing records stay reproducible.
+    #[allow(unused_variables)]
+    pub fn compute_retire_hash(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        timestamp: u64,
+        ledger_seq: u32,
+    ) -> BytesN<32> {
+        let mut hash_bytes = [0u8; 16];
+        hash_bytes[0..4].copy_from_slice(&token_id.to_be_bytes());
+        hash_bytes[4..12].copy_from_slice(&timestamp.to_be_bytes());
+        hash_bytes[12..16].copy_from_slice(&ledger_seq.to_be_bytes());
+
+        let hash_input = Bytes::from_array(&env, &hash_bytes);
+        BytesN::from_array(&env, &env.crypto().sha256(&hash_input).to_array())
+    }
+
+    /// Get a token's current status across retirement and the TimeLock
+    ///
+    /// Retirement takes precedence. The TimeLock is only consulted when one
+    /// is configured via `set_time_lock_contract`.
+    pub fn get_token_status(env: Env, token_id: u32) -> TokenStatus {
+        let time_lock = Self::time_lock_client(&env);
+        Self::resolve_token_status(&env, time_lock.as_ref(), token_id)
+    }
+
+    /// Get the status of each token in `token_ids`, in input order
+    ///
+    /// Batch form of `get_token_status` for holdings views; the TimeLock
+    /// address is read once for the whole batch.
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidRange` - More than `MAX_PAGE_SIZE` token IDs supplied
+    pub fn get_token_statuses(
+        env: Env,
+        token_ids: Vec<u32>,
+    ) -> Result<Vec<TokenStatus>, ContractError> {
+        if token_ids.len() > MAX_PAGE_SIZE {
+            return Err(ContractError::InvalidRange);
+        }
+
+        let time_lock = Self::time_lock_client(&env);
+        let mut statuses = Vec::new(&env);
+        for token_id in token_ids.iter() {
+            statuses.push_back(Self::resolve_token_status(
+                &env,
+                time_lock.as_ref(),
+                token_id,
+            ));
+        }
+        Ok(statuses)
+    }
+
+    /// Check whether an entity has ever retired a credit
+    ///
+    /// Checks only for the presence of the entity's retired flag, avoiding
+    /// loading the full list of retired token IDs. The flag is kept even while
+    /// `index_by_entity` is disabled; entities that retired before it existed
+    /// are recognised by their `EntityIndex`.
+    ///
+    /// # Arguments
+    /// * `retiring_entity` - The address to query
+    pub fn has_retired(env: Env, retiring_entity: Address) -> bool {
+        let storage = env.storage().persistent();
+        storage.has(&DataKey::EntityRetired(retiring_entity.clone()))
+            || storage.has(&DataKey::EntityIndex(retiring_entity))
+    }
+
+    /// Get a page of distinct retiring entities in first-retirement order
+    ///
+    /// # Arguments
+    /// * `start` - Position of the first entity to return
+    /// * `limit` - Maximum number of entities to return (capped at `MAX_PAGE_SIZE`)
+    pub fn get_entities(env: Env, start: u32, limit: u32) -> Vec<Address> {
+        let end = start
+            .saturating_add(limit.min(MAX_PAGE_SIZE))
+            .min(Self::get_entity_count(env.clone()));
+
+        let mut page = Vec::new(&env);
+        for position in start..end {
+            if let Some(entity) = env
+                .storage()
+                .persistent()
+                .get(&DataKey::EntityList(position))
+            {
+                page.push_back(entity);
+            }
+        }
+
+        page
+    }
+
+    /// Get the number of distinct entities that have retired a credit
+    pub fn get_entity_count(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::EntityCount)
+            .unwrap_or(0)
+    }
+
+    /// Extend the TTL of a token's retirement record and its entity index
+    ///
+    /// Anyone may call this to keep a retirement record from being archived.
+    ///
+    /// # Errors
+    /// * `ContractError::InvalidTokenId` - No retirement record exists for the token
+    pub fn bump_ttl(env: Env, token_id: u32) -> Result<(), ContractError> {
+        let ledger_key = DataKey::RetirementLedger(token_id);
+        let record: RetirementRecord = env
+            .storage()
+            .persistent()
+            .get(&ledger_key)
+            .ok_or(ContractError::InvalidTokenId)?;
+
+        extend_persistent_ttl(&env, &ledger_key);
+        let entity_key = DataKey::EntityIndex(record.retiring_entity);
+        if env.storage().persistent().has(&entity_key) {
+            extend_persistent_ttl(&env, &entity_key);
+        }
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    // ========================================================================
+    // Admin Functions
+    // ========================================================================
+
+    /// Update the linked CarbonAsset contract address
+    ///
+    /// # Arguments
+    /// * `new_contract` - The new CarbonAsset contract address
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn update_carbon_asset_contract(
+        env: Env,
+        caller: Address,
+        new_contract: Address,
+    ) -> Result<(), ContractError> {
+        // Require auth for admin function
+        caller.require_auth();
+
+        let admin: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .ok_or(ContractError::ContractNotInitialized)?;
+
+        if caller != admin {
+            return Err(ContractError::NotAuthorized);
+        }
+
+        let old_contract: Address = env
+            .storage()
+            .instance()
+            .get(&DataKey::CarbonAssetContract)
+            .ok_or(ContractError::ContractNotInitialized)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::CarbonAssetContract, &new_contract);
+        extend_instance_ttl(&env);
+
+        // Emit event
+        ContractUpdatedEvent {
+            old_contract,
+            new_contract,
+            updated_by: caller,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Set or clear the contract notified after each successful retirement
+    ///
+    /// The listener's `on_retirement(token_id, retiring_entity)` is invoked in
+    /// the same transaction. Listener failures do not revert the retirement.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn set_notify_contract(
+        env: Env,
+        caller: Address,
+        notify_contract: Option<Address>,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        match notify_contract {
+            Some(listener) => env
+                .storage()
+                .instance()
+                .set(&DataKey::NotifyContract, &listener),
+            None => env.storage().instance().remove(&DataKey::NotifyContract),
+        }
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set or clear the TimeLock consulted by `get_token_status`
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn set_time_lock_contract(
+        env: Env,
+        caller: Address,
+        time_lock: Option<Address>,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        match time_lock {
+            Some(time_lock) => env
+                .storage()
+                .instance()
+                .set(&DataKey::TimeLockContract, &time_lock),
+            None => env.storage().instance().remove(&DataKey::TimeLockContract),
+        }
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Set or clear the shared controller whose `is_paused` halts retirements
+    ///
+    /// While the controller reports paused, every retirement path fails with
+    /// `ContractError::Paused` and `can_retire` reports `false`. Without a
+    /// controller the tracker is never paused.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn set_controller(
+        env: Env,
+        caller: Address,
+        controller: Option<Address>,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        match controller {
+            Some(controller) => env
+                .storage()
+                .instance()
+                .set(&DataKey::Controller, &controller),
+            None => env.storage().instance().remove(&DataKey::Controller),
+        }
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Get the shared controller, if any
+    pub fn get_controller(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Controller)
+    }
+
+    /// Enable or disable atomic precheck mode for retirements
+    ///
+    /// In this mode every recorded retirement first reserves the token with a
+    /// `PendingRetirement` that doubles as a reentrancy guard, then burns it,
+    /// then checks that the asset no longer reports the entity as owner. The
+    /// record is only committed once that check passes; otherwise the
+    /// reservation is released and the call fails with
+    /// `ContractError::BurnNotFinalized`, so the host reverts the burn and
+    /// fee with it. The batch paths fail as a whole on this error rather
+    /// than skip the token.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn set_atomic_retire(
+        env: Env,
+        caller: Address,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::AtomicRetire, &enabled);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Check whether atomic precheck mode is enabled
+    pub fn is_atomic_retire(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::AtomicRetire)
+            .unwrap_or(false)
+    }
+
+    /// Enable or disable maintenance of the per-entity retirement index
+    ///
+    /// Enabled by default. Deployments that never query by entity can turn
+    /// it off to save the `EntityIndex` read-modify-write on every
+    /// retirement. While disabled, retirements are not added to
+    /// `EntityIndex`, the entity list or the largest-index ranking,
+    /// `get_retirements_by_entity` returns empty and `verify_index_consistency`
+    /// skips the entity check. `has_retired` keeps answering from a per-entity
+    /// flag written on every retirement. Re-enabling does not backfill the retirements
+    /// recorded in between.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn set_index_by_entity(
+        env: Env,
+        caller: Address,
+        enabled: bool,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        env.storage()
+            .instance()
+            .set(&DataKey::IndexByEntity, &enabled);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Check whether the per-entity retirement index is maintained
+    pub fn is_index_by_entity(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&DataKey::IndexByEntity)
+            .unwrap_or(true)
+    }
+
+    /// Get the reservation of a token whose atomic-mode retirement is in
+    /// progress, if any
+    ///
+    /// Reservations only exist while the burn runs, so this is `Some` only
+    /// for calls made during it, e.g. by the CarbonAsset contract.
+    pub fn get_pending_retirement(env: Env, token_id: u32) -> Option<PendingRetirement> {
+        env.storage()
+            .temporary()
+            .get(&DataKey::PendingRetirement(token_id))
+    }
+
+    /// Set or clear the retirement quota of an entity
+    ///
+    /// Limits the credits `retiring_entity` may retire to `amount` within any
+    /// rolling window of `period_seconds`: every retirement, whole or
+    /// partial and through any entry point, counts against the quota until
+    /// `period_seconds` after it happened. Tokens the asset burns itself
+    /// (`record_asset_retirement`) have no known amount and are not counted.
+    /// An `amount` of 0 removes the quota; entities without one are
+    /// unrestricted. Replacing a quota clears its window.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    /// * `ContractError::InvalidAmount` - `amount` is negative or `period_seconds` is 0
+    pub fn set_quota(
+        env: Env,
+        caller: Address,
+        retiring_entity: Address,
+        amount: i128,
+        period_seconds: u64,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        let key = DataKey::Quota(retiring_entity);
+        if amount == 0 {
+            env.storage().persistent().remove(&key);
+            return Ok(());
+        }
+        if amount < 0 || period_seconds == 0 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        let quota = RetirementQuota {
+            amount,
+            period_seconds,
+            used: 0,
+            charges: Vec::new(&env),
+        };
+        env.storage().persistent().set(&key, &quota);
+        extend_persistent_ttl(&env, &key);
+        Ok(())
+    }
+
+    /// Get the retirement quota of an entity, if any, with the window as of now
+    pub fn get_quota(env: Env, retiring_entity: Address) -> Option<RetirementQuota> {
+        let quota: Option<RetirementQuota> = env
+            .storage()
+            .persistent()
+            .get(&DataKey::Quota(retiring_entity));
+        quota.map(|quota| Self::roll_quota_window(&env, quota))
+    }
+
+    /// Set the fee every retirement of a whole token charges, in basis points
+    /// of the token's amount
+    ///
+    /// 0 (the default) disables the fee. The fee is only charged while a
+    /// buffer pool is configured via `set_buffer_pool`. Tokens the asset
+    /// burns itself (`record_asset_retirement`) are already gone and pay no
+    /// fee, nor do contracts retiring their own holdings. Partial burns pay
+    /// the fee on top of the burned amount.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    /// * `ContractError::InvalidAmount` - `fee_bps` exceeds 10000
+    pub fn set_retirement_fee(
+        env: Env,
+        caller: Address,
+        fee_bps: u32,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        if fee_bps > 10_000 {
+            return Err(ContractError::InvalidAmount);
+        }
+
+        env.storage()
+            .instance()
+            .set(&DataKey::RetirementFeeBps, &fee_bps);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Get the retirement fee in basis points
+    pub fn get_retirement_fee(env: Env) -> u32 {
+        env.storage()
+            .instance()
+            .get(&DataKey::RetirementFeeBps)
+            .unwrap_or(0)
+    }
+
+    /// Set or clear the buffer pool receiving retirement fees
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn set_buffer_pool(
+        env: Env,
+        caller: Address,
+        buffer_pool: Option<Address>,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        match buffer_pool {
+            Some(buffer_pool) => env
+                .storage()
+                .instance()
+                .set(&DataKey::BufferPool, &buffer_pool),
+            None => env.storage().instance().remove(&DataKey::BufferPool),
+        }
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Get the buffer pool receiving retirement fees, if any
+    pub fn get_buffer_pool(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::BufferPool)
+    }
+
+    /// Allow or disallow a contract to retire tokens it holds itself
+    ///
+    /// Contracts such as the buffer pool retire their holdings by calling the
+    /// tracker with their own address as `retiring_entity`. Because they must
+    /// also authorize the nested burn on the CarbonAsset contract, they are
+    /// only accepted once whitelisted here.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn set_trusted_retirer(
+        env: Env,
+        caller: Address,
+        contract: Address,
+        trusted: bool,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        let key = DataKey::TrustedRetirer(contract);
+        if trusted {
+            env.storage().persistent().set(&key, &true);
+            extend_persistent_ttl(&env, &key);
+        } else {
+            env.storage().persistent().remove(&key);
+        }
+        Ok(())
+    }
+
+    /// Check whether a contract may retire tokens it holds itself
+    pub fn is_trusted_retirer(env: Env, contract: Address) -> bool {
+        env.storage()
+            .persistent()
+            .has(&DataKey::TrustedRetirer(contract))
+    }
+
+    /// Set the Merkle root of registry-approved serials checked by `retire_with_proof`
+    ///
+    /// Leaves are built with `compute_proof_leaf` from each token and its serial.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn set_registry_root(
+        env: Env,
+        caller: Address,
+        root: BytesN<32>,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        env.storage().instance().set(&DataKey::RegistryRoot, &root);
+        extend_instance_ttl(&env);
+        Ok(())
+    }
+
+    /// Get the registry Merkle root, if one is set
+    pub fn get_registry_root(env: Env) -> Option<BytesN<32>> {
+        env.storage().instance().get(&DataKey::RegistryRoot)
+    }
+
+    /// Get the TimeLock consulted by `get_token_status`, if any
+    pub fn get_time_lock_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::TimeLockContract)
+    }
+
+    /// Get the contract notified after each retirement, if any
+    pub fn get_notify_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::NotifyContract)
+    }
+
+    /// Re-publish the RetirementEvent for an existing record
+    ///
+    /// Lets a recovering indexer backfill missed events from on-chain state.
+    /// The replayed event carries `reemitted: true`.
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    /// * `ContractError::InvalidTokenId` - No retirement record exists for the token
+    pub fn reemit_retirement_event(
+        env: Env,
+        caller: Address,
+        token_id: u32,
+    ) -> Result<(), ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        let record: RetirementRecord = env
+            .storage()
+            .persistent()
+            .get(&DataKey::RetirementLedger(token_id))
+            .ok_or(ContractError::InvalidTokenId)?;
+
+        RetirementEvent {
+            token_id: record.token_id,
+            retiring_entity: record.retiring_entity,
+            timestamp: record.timestamp,
+            tx_hash: record.tx_hash,
+            initiated_by: record.initiated_by,
+            reemitted: true,
+            fee: record.fee,
+            reason: record.reason,
+        }
+        .publish(&env);
+        Ok(())
+    }
+
+    /// Rebuild an entity's retirement index from verified ledger records
+    ///
+    /// Repair tool for a corrupted `EntityIndex`. Every supplied token must
+    /// have a retirement record naming `retiring_entity`; the index is then
+    /// replaced with the verified IDs in input order, duplicates dropped. The
+    /// entity is added to the entity list if its index had been lost.
     ///
     /// # Returns
-    /// Vector of token IDs retired by the entity
-    pub fn get_retirements_by_entity(env: Env, retiring_entity: Address) -> Vec<u32> {
-        let entity_key = DataKey::EntityIndex(retiring_entity);
+    /// The number of token IDs in the rebuilt index
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    /// * `ContractError::InvalidTokenId` - No retirement record exists for a token
+    /// * `ContractError::TokenNotOwned` - A token's record names a different entity
+    pub fn rebuild_entity_index(
+        env: Env,
+        caller: Address,
+        retiring_entity: Address,
+        token_ids: Vec<u32>,
+    ) -> Result<u32, ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        let mut verified: Vec<u32> = Vec::new(&env);
+        for token_id in token_ids.iter() {
+            if verified.contains(token_id) {
+                continue;
+            }
+
+            let record: RetirementRecord = env
+                .storage()
+                .persistent()
+                .get(&DataKey::RetirementLedger(token_id))
+                .ok_or(ContractError::InvalidTokenId)?;
+            if record.retiring_entity != retiring_entity {
+                return Err(ContractError::TokenNotOwned);
+            }
+            verified.push_back(token_id);
+        }
+
+        let entity_key = DataKey::EntityIndex(retiring_entity.clone());
+        if !env.storage().persistent().has(&entity_key) && !verified.is_empty() {
+            Self::append_entity(&env, &retiring_entity);
+        }
+        env.storage().persistent().set(&entity_key, &verified);
+        extend_persistent_ttl(&env, &entity_key);
+        Self::record_index_size(&env, &retiring_entity, verified.len());
+        extend_instance_ttl(&env);
+
+        Ok(verified.len())
+    }
+
+    /// Void a retirement recorded in error (admin only)
+    ///
+    /// The burn cannot be undone, but the token's record is removed and the
+    /// token is purged from every index a report reads: its entity's
+    /// `EntityIndex`, the ledger (time) bucket, the retired-id and project
+    /// indexes, the standard, project, vintage, daily and total counters, and
+    /// the certificate and compliance details naming its beneficiary. Its memo
+    /// hash and claim are dropped too. Totals of amount-aware calls
+    /// (`get_total_amount_retired`, `get_amount_by_category`) aggregate whole
+    /// calls and are left unchanged, the entity stays in `get_entities`, and
+    /// `get_max_retired_id` remains an upper bound.
+    ///
+    /// # Returns
+    /// The voided retirement record
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    /// * `ContractError::TokenNotRetired` - No retirement record exists for the token
+    pub fn void_retirement(
+        env: Env,
+        caller: Address,
+        token_id: u32,
+    ) -> Result<RetirementRecord, ContractError> {
+        Self::require_admin(&env, &caller)?;
+        let storage = env.storage().persistent();
+        let record: RetirementRecord = storage
+            .get(&DataKey::RetirementLedger(token_id))
+            .ok_or(ContractError::TokenNotRetired)?;
+        storage.remove(&DataKey::RetirementLedger(token_id));
+
+        let entity = record.retiring_entity.clone();
+        if let Some(len) =
+            Self::remove_from_index(&env, &DataKey::EntityIndex(entity.clone()), token_id)
+        {
+            Self::record_index_size(&env, &entity, len);
+        }
+        Self::remove_from_index(
+            &env,
+            &DataKey::LedgerBucket(record.ledger_seq / LEDGER_BUCKET_SIZE),
+            token_id,
+        );
+        Self::remove_from_index(
+            &env,
+            &DataKey::RetiredIds(token_id / ID_BUCKET_SIZE),
+            token_id,
+        );
+        if let Some(project_id) = record.project_id {
+            Self::remove_from_index(&env, &DataKey::ProjectRetirements(project_id), token_id);
+        }
+
+        let credits_key = DataKey::RetiredCredits(token_id);
+        if let Some((vintage, credits)) = storage.get::<_, (Option<u32>, i128)>(&credits_key) {
+            if let Some(project_id) = record.project_id {
+                Self::record_project_amount(&env, project_id, -credits);
+            }
+            if let Some(vintage) = vintage {
+                Self::record_vintage_amount(&env, vintage, -credits);
+            }
+            storage.remove(&credits_key);
+        }
+        if let Some(standard) = record.standard.clone() {
+            let standard_key = DataKey::StandardCount(standard);
+            let count: u64 = storage.get(&standard_key).unwrap_or(0);
+            storage.set(&standard_key, &count.saturating_sub(1));
+        }
+        let day_key = DataKey::DayRetirements(record.timestamp / SECONDS_PER_DAY);
+        if let Some(mut day) = storage.get::<_, DayRetirements>(&day_key) {
+            day.count = day.count.saturating_sub(1);
+            storage.set(&day_key, &day);
+        }
+        let entity_day_key = DataKey::DayEntity(
+            record.timestamp / SECONDS_PER_DAY,
+            record.retiring_entity.clone(),
+        );
+        if let Some(count) = storage.get::<_, u64>(&entity_day_key) {
+            storage.set(&entity_day_key, &count.saturating_sub(1));
+        }
+        let total_retired = Self::get_total_retired(env.clone());
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalRetired, &total_retired.saturating_sub(1));
+
+        if let Some(certificate) =
+            storage.get::<_, RetirementCertificate>(&DataKey::Certificate(token_id))
+        {
+            storage.remove(&DataKey::CertificateIndex(certificate.certificate_id));
+            storage.remove(&DataKey::Certificate(token_id));
+        }
+        storage.remove(&DataKey::Compliance(token_id));
+        storage.remove(&DataKey::MemoHash(token_id));
+        storage.remove(&DataKey::Claim(token_id));
+        extend_instance_ttl(&env);
+
+        RetirementVoidedEvent {
+            token_id,
+            retiring_entity: entity,
+            voided_by: caller,
+        }
+        .publish(&env);
+
+        Ok(record)
+    }
+
+    /// Audit that a token's retirement record and secondary indexes agree
+    ///
+    /// A retired token must appear in its entity's `EntityIndex`, the ledger
+    /// bucket of its retirement ledger, the retired-id index and its
+    /// project's retirements, and be counted in the project and vintage
+    /// totals, its standard's count and the daily index of its retirement
+    /// day. Any certificate must resolve back to it. A token without a
+    /// record, such as one voided by `void_retirement`, must have no
+    /// retired-id entry, certificate, compliance details, memo hash or claim,
+    /// nor credits kept for the project and vintage totals.
+    ///
+    /// # Returns
+    /// `true` if every index is consistent with the record
+    pub fn verify_index_consistency(env: Env, token_id: u32) -> bool {
+        let storage = env.storage().persistent();
+        let certificate: Option<RetirementCertificate> =
+            storage.get(&DataKey::Certificate(token_id));
+        let retired_ids: Vec<u32> = storage
+            .get(&DataKey::RetiredIds(token_id / ID_BUCKET_SIZE))
+            .unwrap_or(Vec::new(&env));
+        let record: RetirementRecord = match storage.get(&DataKey::RetirementLedger(token_id)) {
+            Some(record) => record,
+            None => {
+                return certificate.is_none()
+                    && !retired_ids.contains(token_id)
+                    && !storage.has(&DataKey::Compliance(token_id))
+                    && !storage.has(&DataKey::MemoHash(token_id))
+                    && !storage.has(&DataKey::Claim(token_id))
+                    && !storage.has(&DataKey::RetiredCredits(token_id))
+            }
+        };
+
+        let entity_indexed = !Self::is_index_by_entity(env.clone())
+            || storage
+                .get::<_, Vec<u32>>(&DataKey::EntityIndex(record.retiring_entity.clone()))
+                .is_some_and(|entity_index| entity_index.contains(token_id));
+        let bucket: Vec<u32> = storage
+            .get(&DataKey::LedgerBucket(
+                record.ledger_seq / LEDGER_BUCKET_SIZE,
+            ))
+            .unwrap_or(Vec::new(&env));
+        let certificate_resolves = match certificate {
+            Some(certificate) => {
+                storage.get(&DataKey::CertificateIndex(certificate.certificate_id))
+                    == Some(token_id)
+            }
+            None => true,
+        };
+
+        // The credits kept by record_retirement must still be in the totals
+        let credits: Option<(Option<u32>, i128)> = storage.get(&DataKey::RetiredCredits(token_id));
+        let project_indexed = match record.project_id {
+            Some(project_id) => {
+                let credits_counted = credits.as_ref().is_some_and(|(_, credits)| {
+                    Self::get_amount_by_project(env.clone(), project_id) >= *credits
+                });
+                credits_counted
+                    && Self::get_retirements_by_project(env.clone(), project_id).contains(token_id)
+            }
+            None => true,
+        };
+        let vintage_counted = match credits {
+            Some((Some(vintage), credits)) => {
+                Self::get_retired_amount_by_vintage(env.clone(), vintage) >= credits
+            }
+            _ => true,
+        };
+        let standard_counted = record
+            .standard
+            .is_none_or(|standard| Self::get_count_by_standard(env.clone(), standard) > 0);
+        let day = record.timestamp / SECONDS_PER_DAY;
+        let day_counted = storage
+            .get::<_, DayRetirements>(&DataKey::DayRetirements(day))
+            .is_some_and(|retirements| retirements.count > 0)
+            && Self::get_entity_day_count(env.clone(), record.retiring_entity, record.timestamp)
+                > 0;
+
+        entity_indexed
+            && bucket.contains(token_id)
+            && retired_ids.contains(token_id)
+            && project_indexed
+            && vintage_counted
+            && standard_counted
+            && day_counted
+            && certificate_resolves
+    }
+
+    /// Find the token IDs in an entity's `EntityIndex` that have no matching
+    /// retirement record
+    ///
+    /// An ID is an orphan when it has no `RetirementLedger` record or its
+    /// record names a different retiring entity. Only the first
+    /// `MAX_PAGE_SIZE` entries of the index are scanned.
+    ///
+    /// # Returns
+    /// The orphaned token IDs in index order; empty when the index is consistent
+    pub fn audit_entity(env: Env, retiring_entity: Address) -> Vec<u32> {
+        let storage = env.storage().persistent();
+        let entity_index: Vec<u32> = storage
+            .get(&DataKey::EntityIndex(retiring_entity.clone()))
+            .unwrap_or(Vec::new(&env));
+
+        let mut orphans = Vec::new(&env);
+        for token_id in entity_index.iter().take(MAX_PAGE_SIZE as usize) {
+            let record: Option<RetirementRecord> =
+                storage.get(&DataKey::RetirementLedger(token_id));
+            if record.is_none_or(|record| record.retiring_entity != retiring_entity) {
+                orphans.push_back(token_id);
+            }
+        }
+        orphans
+    }
+
+    /// Get the entities with the largest retirement indexes (admin only)
+    ///
+    /// The ranking is maintained as indexes change, so only the top
+    /// `MAX_LARGEST_INDEXES` entities are tracked.
+    ///
+    /// # Arguments
+    /// * `n` - Maximum number of entries to return (capped at `MAX_LARGEST_INDEXES`)
+    ///
+    /// # Returns
+    /// `(entity, index length)` pairs, largest first
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn get_largest_indexes(
+        env: Env,
+        caller: Address,
+        n: u32,
+    ) -> Result<Vec<(Address, u32)>, ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        let largest: Vec<(Address, u32)> = env
+            .storage()
+            .instance()
+            .get(&DataKey::LargestIndexes)
+            .unwrap_or(Vec::new(&env));
+        Ok(largest.slice(0..n.min(MAX_LARGEST_INDEXES).min(largest.len())))
+    }
+
+    /// Record the current aggregate retirement totals as an immutable snapshot
+    ///
+    /// # Returns
+    /// The id of the new snapshot
+    ///
+    /// # Errors
+    /// * `ContractError::NotAuthorized` - Caller is not the admin
+    pub fn take_snapshot(env: Env, caller: Address) -> Result<u32, ContractError> {
+        Self::require_admin(&env, &caller)?;
+
+        let snapshot_id: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::SnapshotCount)
+            .unwrap_or(0);
+
+        let snapshot = Snapshot {
+            snapshot_id,
+            total_retired: Self::get_total_retired(env.clone()),
+            total_amount: Self::get_total_amount_retired(env.clone()),
+            timestamp: env.ledger().timestamp(),
+            ledger_seq: env.ledger().sequence(),
+        };
+
+        let snapshot_key = DataKey::Snapshot(snapshot_id);
+        env.storage().persistent().set(&snapshot_key, &snapshot);
+        extend_persistent_ttl(&env, &snapshot_key);
+        env.storage()
+            .instance()
+            .set(&DataKey::SnapshotCount, &(snapshot_id + 1));
+        extend_instance_ttl(&env);
+
+        Ok(snapshot_id)
+    }
+
+    /// Get a snapshot by id
+    pub fn get_snapshot(env: Env, snapshot_id: u32) -> Option<Snapshot> {
+        env.storage()
+            .persistent()
+            .get(&DataKey::Snapshot(snapshot_id))
+    }
+
+    /// Get the number of tokens retired
+    pub fn get_total_retired(env: Env) -> u64 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalRetired)
+            .unwrap_or(0)
+    }
+
+    /// Get the credits retired through amount-aware calls such as `retire_target_amount`
+    pub fn get_total_amount_retired(env: Env) -> i128 {
+        env.storage()
+            .instance()
+            .get(&DataKey::TotalAmountRetired)
+            .unwrap_or(0)
+    }
+
+    /// Get the credits retired in a category through amount-aware calls
+    pub fn get_amount_by_category(env: Env, category: RetirementReason) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&DataKey::CategoryAmount(category))
+            .unwrap_or(0)
+    }
+
+    /// Get the unit and decimal places of retired amounts
+    ///
+    /// Defaults to `DEFAULT_UNITS` with 0 decimals when not set at initialization.
+    pub fn get_units(env: Env) -> (Symbol, u32) {
+        let units = env
+            .storage()
+            .instance()
+            .get(&DataKey::Units)
+            .unwrap_or(Symbol::new(&env, DEFAULT_UNITS));
+        let decimals = env
+            .storage()
+            .instance()
+            .get(&DataKey::Decimals)
+            .unwrap_or(0);
+        (units, decimals)
+    }
+
+    /// Get aggregate retirement figures together with their units
+    pub fn get_stats(env: Env) -> RetirementStats {
+        let (units, decimals) = Self::get_units(env.clone());
+        RetirementStats {
+            total_retired: Self::get_total_retired(env.clone()),
+            total_amount: Self::get_total_amount_retired(env.clone()),
+            entity_count: Self::get_entity_count(env),
+            units,
+            decimals,
+        }
+    }
+
+    /// Get the current admin address
+    pub fn get_admin(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::Admin)
+    }
+
+    /// Ledger timestamp and sequence as the tracker observes them, for
+    /// replay tests of time-bucketed logic
+    pub fn get_ledger_context(env: Env) -> (u64, u32) {
+        (env.ledger().timestamp(), env.ledger().sequence())
+    }
+
+    /// Whether `who` is the admin; false before initialization
+    pub fn can_admin(env: Env, who: Address) -> bool {
+        Self::get_admin(env) == Some(who)
+    }
+
+    /// Get the current CarbonAsset contract address
+    pub fn get_carbon_asset_contract(env: Env) -> Option<Address> {
+        env.storage().instance().get(&DataKey::CarbonAssetContract)
+    }
+
+    /// List every external contract the tracker is configured to call
+    ///
+    /// Labelled `carbon_asset`, `time_lock`, `buffer_pool`, `notify` and
+    /// `controller`; only configured contracts are included. Contracts
+    /// whitelisted through `set_trusted_retirer` are not enumerable and are
+    /// checked with `is_trusted_retirer`.
+    pub fn get_trusted_contracts(env: Env) -> Vec<(Symbol, Address)> {
+        let mut contracts = Vec::new(&env);
+        let configured = [
+            ("carbon_asset", Self::get_carbon_asset_contract(env.clone())),
+            ("time_lock", Self::get_time_lock_contract(env.clone())),
+            ("buffer_pool", Self::get_buffer_pool(env.clone())),
+            ("notify", Self::get_notify_contract(env.clone())),
+            ("controller", Self::get_controller(env.clone())),
+        ];
+        for (label, address) in configured {
+            if let Some(address) = address {
+                contracts.push_back((Symbol::new(&env, label), address));
+            }
+        }
+        contracts
+    }
+
+    /// Get every configuration value in one call
+    ///
+    /// # Errors
+    /// * `ContractError::ContractNotInitialized` - Contract has not been initialized
+    pub fn get_config(env: Env) -> Result<TrackerConfig, ContractError> {
+        let admin = Self::get_admin(env.clone()).ok_or(ContractError::ContractNotInitialized)?;
+        let (units, decimals) = Self::get_units(env.clone());
+        Ok(TrackerConfig {
+            admin,
+            carbon_asset_contract: Self::carbon_asset_contract(&env)?,
+            fee_bps: Self::get_retirement_fee(env.clone()),
+            buffer_pool: Self::get_buffer_pool(env.clone()),
+            time_lock_contract: Self::get_time_lock_contract(env.clone()),
+            notify_contract: Self::get_notify_contract(env.clone()),
+            registry_root: Self::get_registry_root(env.clone()),
+            units,
+            decimals,
+            controller: Self::get_controller(env.clone()),
+            atomic_retire: Self::is_atomic_retire(env.clone()),
+            index_by_entity: Self::is_index_by_entity(env),
+        })
+    }
+
+    // ========================================================================
+    // Internal Functions
+    // ========================================================================
+
+    /// Store a certificate for a retired token and index it by its id,
+    /// `sha256(tx_hash || certificate_hash)`
+    fn anchor_certificate(
+        env: &Env,
+        record: &RetirementRecord,
+        beneficiary: Address,
+        certificate_hash: BytesN<32>,
+    ) -> BytesN<32> {
+        let token_id = record.token_id;
+        let mut id_input = Bytes::from_array(env, &record.tx_hash.to_array());
+        id_input.append(&Bytes::from_array(env, &certificate_hash.to_array()));
+        let certificate_id = BytesN::from_array(env, &env.crypto().sha256(&id_input).to_array());
+
+        let certificate = RetirementCertificate {
+            certificate_id: certificate_id.clone(),
+            token_id,
+            beneficiary,
+            certificate_hash,
+        };
+
+        let certificate_key = DataKey::Certificate(token_id);
+        env.storage()
+            .persistent()
+            .set(&certificate_key, &certificate);
+        extend_persistent_ttl(env, &certificate_key);
+
+        let index_key = DataKey::CertificateIndex(certificate_id.clone());
+        env.storage().persistent().set(&index_key, &token_id);
+        extend_persistent_ttl(env, &index_key);
+
+        certificate_id
+    }
+
+    /// Issuing standard, project and vintage the asset reports for
+    /// `token_id`, for assets that expose them, and the token's amount
+    ///
+    /// The amount is read with the token's ownership through
+    /// `owned_balance`, a single `retire_info` call where the asset has it,
+    /// and only when it is needed: for the project and vintage counters, or
+    /// when `charged` because a fee or quota applies.
+    fn issuance_info(
+        carbon_asset: &CarbonAssetClient,
+        token_id: u32,
+        retiring_entity: &Address,
+        charged: bool,
+    ) -> Result<IssuanceInfo, ContractError> {
+        let standard = match carbon_asset.try_standard_of(&token_id) {
+            Ok(Ok(standard)) => Some(standard),
+            _ => None,
+        };
+        let project_id = Self::project_of(carbon_asset, token_id);
+        let vintage = Self::vintage_of(carbon_asset, token_id);
+        let amount = if charged || project_id.is_some() || vintage.is_some() {
+            Self::owned_balance(carbon_asset, token_id, retiring_entity)?
+        } else {
+            0
+        };
+        Ok(IssuanceInfo {
+            standard,
+            project_id,
+            vintage,
+            amount,
+        })
+    }
+
+    /// Project the asset reports for `token_id`, if it exposes one
+    fn project_of(carbon_asset: &CarbonAssetClient, token_id: u32) -> Option<u32> {
+        match carbon_asset.try_project_of(&token_id) {
+            Ok(Ok(project_id)) => Some(project_id),
+            _ => None,
+        }
+    }
+
+    /// Vintage the asset reports for `token_id`, if it exposes one
+    fn vintage_of(carbon_asset: &CarbonAssetClient, token_id: u32) -> Option<u32> {
+        match carbon_asset.try_vintage_of(&token_id) {
+            Ok(Ok(vintage)) => Some(vintage),
+            _ => None,
+        }
+    }
+
+    /// Remove `token_id` from the `Vec<u32>` index stored under `key`
+    ///
+    /// # Returns
+    /// The index length after removal, or `None` if the token was not in it
+    fn remove_from_index(env: &Env, key: &DataKey, token_id: u32) -> Option<u32> {
+        let mut index: Vec<u32> = env.storage().persistent().get(key)?;
+        let position = index.first_index_of(token_id)?;
+        index.remove(position);
+        env.storage().persistent().set(key, &index);
+        extend_persistent_ttl(env, key);
+        Some(index.len())
+    }
+
+    /// Add retired credits to a vintage's running total
+    fn record_vintage_amount(env: &Env, vintage: u32, amount: i128) {
+        let key = DataKey::VintageAmount(vintage);
+        let total: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(total + amount));
+        extend_persistent_ttl(env, &key);
+    }
+
+    /// Add retired credits to a project's running total
+    fn record_project_amount(env: &Env, project_id: u32, amount: i128) {
+        let key = DataKey::ProjectAmount(project_id);
+        let total: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(total + amount));
+        extend_persistent_ttl(env, &key);
+    }
+
+    /// Burn `token_id` held by `retiring_entity` on the CarbonAsset contract
+    fn burn_token(
+        env: &Env,
+        carbon_asset_contract: &Address,
+        token_id: u32,
+        retiring_entity: &Address,
+    ) -> Result<(), ContractError> {
+        // The contract must be pre-authorized as a burner on the CarbonAsset contract
+        // We assume CarbonAsset has a burn function that accepts (token_id: u32, from: Address)
+        // The CarbonAsset contract should verify ownership before allowing burn
+        let burn_symbol = Symbol::new(env, "burn");
+        let mut burn_args = Vec::new(env);
+        burn_args.push_back(token_id.into_val(env));
+        burn_args.push_back(retiring_entity.clone().into_val(env));
+        if env
+            .try_invoke_contract::<(), soroban_sdk::Error>(
+                carbon_asset_contract,
+                &burn_symbol,
+                burn_args,
+            )
+            .is_err()
+        {
+            return Err(ContractError::BurnFailed);
+        }
+
+        Ok(())
+    }
+
+    fn retire_internal(
+        env: &Env,
+        token_id: u32,
+        retiring_entity: Address,
+        reason: Option<String>,
+        initiated_by: Option<Address>,
+    ) -> Result<RetirementRecord, ContractError> {
+        Self::check_retirable(env, token_id, &retiring_entity)?;
+        let carbon_asset_contract = Self::carbon_asset_contract(env)?;
+        let carbon_asset = CarbonAssetClient::new(env, &carbon_asset_contract);
+        // Read before burning, while the asset still knows the token
+        let charged = Self::is_retirement_charged(env, &retiring_entity);
+        let issuance = Self::issuance_info(&carbon_asset, token_id, &retiring_entity, charged)?;
+        let charges = Self::retirement_charges(env, &retiring_entity, issuance.amount)?;
+        // The fee is split off the token, so it has to move before the burn.
+        // A failed burn fails the call, which reverts the donation with it.
+        Self::pay_retirement_fee(env, token_id, &retiring_entity, &charges);
+        let fee = charges.fee;
+
+        if !Self::is_atomic_retire(env.clone()) {
+            Self::burn_token(env, &carbon_asset_contract, token_id, &retiring_entity)?;
+            Self::store_quota(env, &retiring_entity, charges.quota);
+            return Ok(Self::record_retirement(
+                env,
+                token_id,
+                retiring_entity,
+                reason,
+                initiated_by,
+                fee,
+                issuance,
+            ));
+        }
+
+        let pending_key = DataKey::PendingRetirement(token_id);
+        let pending = PendingRetirement {
+            token_id,
+            retiring_entity: retiring_entity.clone(),
+            reserved_at: env.ledger().timestamp(),
+        };
+        env.storage().temporary().set(&pending_key, &pending);
+
+        let burned = Self::burn_token(env, &carbon_asset_contract, token_id, &retiring_entity)
+            .and_then(|_| match carbon_asset.try_owner_of(&token_id) {
+                Ok(Ok(Some(owner))) if owner == retiring_entity => {
+                    Err(ContractError::BurnNotFinalized)
+                }
+                _ => Ok(()),
+            });
+        env.storage().temporary().remove(&pending_key);
+        burned?;
+        Self::store_quota(env, &retiring_entity, charges.quota);
+
+        Ok(Self::record_retirement(
+            env,
+            token_id,
+            retiring_entity,
+            reason,
+            initiated_by,
+            fee,
+            issuance,
+        ))
+    }
+
+    /// Retire one token of a batch, or `None` to skip a token whose
+    /// retirement failed before anything was committed
+    ///
+    /// A burn that fails once the fee has gone to the buffer pool, or that
+    /// atomic mode finds unfinalized, fails the whole call instead, so the
+    /// host reverts the fee and burn with it.
+    fn retire_batch_entry(
+        env: &Env,
+        token_id: u32,
+        retiring_entity: &Address,
+        reason: &Option<String>,
+    ) -> Result<Option<RetirementRecord>, ContractError> {
+        let charged = Self::fee_pool(env, retiring_entity).is_some();
+        match Self::retire_internal(env, token_id, retiring_entity.clone(), reason.clone(), None) {
+            Ok(record) => Ok(Some(record)),
+            Err(ContractError::BurnFailed) if charged => Err(ContractError::BurnFailed),
+            Err(ContractError::BurnNotFinalized) => Err(ContractError::BurnNotFinalized),
+            Err(_) => Ok(None),
+        }
+    }
+
+    /// Store the record and indexes of a token that has already been burned,
+    /// then emit the event and notify the listener
+    fn record_retirement(
+        env: &Env,
+        token_id: u32,
+        retiring_entity: Address,
+        reason: Option<String>,
+        initiated_by: Option<Address>,
+        fee: i128,
+        issuance: IssuanceInfo,
+    ) -> RetirementRecord {
+        let ledger_key = DataKey::RetirementLedger(token_id);
+
+        // Get current timestamp
+        let timestamp = env.ledger().timestamp();
+
+        // Deterministic hash of the retirement, reproducible off-chain via
+        // compute_retire_hash
+        let ledger_seq = env.ledger().sequence();
+        let tx_hash = Self::compute_retire_hash(
+            env.clone(),
+            token_id,
+            retiring_entity.clone(),
+            timestamp,
+            ledger_seq,
+        );
+
+        // Create retirement record
+        let record = RetirementRecord {
+            token_id,
+            retiring_entity: retiring_entity.clone(),
+            timestamp,
+            tx_hash: tx_hash.clone(),
+            reason: reason.clone(),
+            initiated_by: initiated_by.clone(),
+            ledger_seq,
+            fee,
+            standard: issuance.standard.clone(),
+            project_id: issuance.project_id,
+        };
+
+        // Store in retirement ledger
+        env.storage().persistent().set(&ledger_key, &record);
+        extend_persistent_ttl(env, &ledger_key);
+
+        let retired_key = DataKey::EntityRetired(retiring_entity.clone());
+        if !env.storage().persistent().has(&retired_key) {
+            env.storage().persistent().set(&retired_key, &true);
+        }
+        extend_persistent_ttl(env, &retired_key);
+
+        // Update entity index, listing the entity the first time it retires
+        if Self::is_index_by_entity(env.clone()) {
+            let entity_key = DataKey::EntityIndex(retiring_entity.clone());
+            let mut entity_retirements: Vec<u32> = match env.storage().persistent().get(&entity_key)
+            {
+                Some(retirements) => retirements,
+                None => {
+                    Self::append_entity(env, &retiring_entity);
+                    Vec::new(env)
+                }
+            };
+            entity_retirements.push_back(token_id);
+            env.storage()
+                .persistent()
+                .set(&entity_key, &entity_retirements);
+            extend_persistent_ttl(env, &entity_key);
+            Self::record_index_size(env, &retiring_entity, entity_retirements.len());
+        }
+
+        // Update ledger sequence index
+        let bucket_key = DataKey::LedgerBucket(ledger_seq / LEDGER_BUCKET_SIZE);
+        let mut bucket: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&bucket_key)
+            .unwrap_or(Vec::new(env));
+        bucket.push_back(token_id);
+        env.storage().persistent().set(&bucket_key, &bucket);
+        extend_persistent_ttl(env, &bucket_key);
+
+        // Update the sorted retired-id index
+        let ids_key = DataKey::RetiredIds(token_id / ID_BUCKET_SIZE);
+        let mut ids: Vec<u32> = env
+            .storage()
+            .persistent()
+            .get(&ids_key)
+            .unwrap_or(Vec::new(env));
+        if let Err(position) = ids.binary_search(token_id) {
+            ids.insert(position, token_id);
+        }
+        env.storage().persistent().set(&ids_key, &ids);
+        extend_persistent_ttl(env, &ids_key);
+        if Self::get_max_retired_id(env.clone()).is_none_or(|max_id| token_id > max_id) {
+            env.storage()
+                .instance()
+                .set(&DataKey::MaxRetiredId, &token_id);
+        }
+
+        let total_retired = Self::get_total_retired(env.clone());
         env.storage()
+            .instance()
+            .set(&DataKey::TotalRetired, &(total_retired + 1));
+        Self::record_day_retirements(env, &retiring_entity, 1, 0);
+        if let Some(standard) = issuance.standard {
+            let standard_key = DataKey::StandardCount(standard);
+            let count: u64 = env.storage().persistent().get(&standard_key).unwrap_or(0);
+            env.storage().persistent().set(&standard_key, &(count + 1));
+            extend_persistent_ttl(env, &standard_key);
+        }
+        if let Some(project_id) = issuance.project_id {
+            let project_key = DataKey::ProjectRetirements(project_id);
+            let mut project_retirements = Self::get_retirements_by_project(env.clone(), project_id);
+            project_retirements.push_back(token_id);
+            env.storage()
+                .persistent()
+                .set(&project_key, &project_retirements);
+            extend_persistent_ttl(env, &project_key);
+            Self::record_project_amount(env, project_id, issuance.amount - fee);
+        }
+        if let Some(vintage) = issuance.vintage {
+            Self::record_vintage_amount(env, vintage, issuance.amount - fee);
+        }
+        if issuance.project_id.is_some() || issuance.vintage.is_some() {
+            // Kept so void_retirement can reverse the project and vintage totals
+            let credits_key = DataKey::RetiredCredits(token_id);
+            env.storage()
+                .persistent()
+                .set(&credits_key, &(issuance.vintage, issuance.amount - fee));
+            extend_persistent_ttl(env, &credits_key);
+        }
+        extend_instance_ttl(env);
+
+        // Emit event
+        RetirementEvent {
+            token_id,
+            retiring_entity: retiring_entity.clone(),
+            timestamp,
+            tx_hash,
+            initiated_by,
+            reemitted: false,
+            fee,
+            reason,
+        }
+        .publish(env);
+
+        Self::notify_listener(env, token_id, &retiring_entity);
+        record
+    }
+
+    /// Ledger-side checks shared by every retirement path and `can_retire`
+    fn check_retirable(
+        env: &Env,
+        token_id: u32,
+        retiring_entity: &Address,
+    ) -> Result<(), ContractError> {
+        Self::require_not_paused(env)?;
+
+        if env
+            .storage()
             .persistent()
-            .get(&entity_key)
-            .unwrap_or(Vec::new(&env))
+            .has(&DataKey::RetirementLedger(token_id))
+        {
+            return Err(ContractError::TokenAlreadyRetired);
+        }
+        if env
+            .storage()
+            .temporary()
+            .has(&DataKey::PendingRetirement(token_id))
+        {
+            return Err(ContractError::RetirementPending);
+        }
+
+        // A contract retiring its own holdings (e.g. the buffer pool) must be
+        // whitelisted. Its direct invocation already satisfies require_auth.
+        if matches!(retiring_entity.executable(), Some(Executable::Wasm(_)))
+            && !Self::is_trusted_retirer(env.clone(), retiring_entity.clone())
+        {
+            return Err(ContractError::NotAuthorized);
+        }
+
+        Ok(())
     }
 
-    // ========================================================================
-    // Admin Functions
-    // ========================================================================
+    /// Add an amount-aware retirement to the running and per-category totals
+    fn record_amount_retired(
+        env: &Env,
+        retiring_entity: &Address,
+        amount: i128,
+        category: Option<RetirementReason>,
+    ) {
+        let total_amount: i128 = env
+            .storage()
+            .instance()
+            .get(&DataKey::TotalAmountRetired)
+            .unwrap_or(0);
+        env.storage()
+            .instance()
+            .set(&DataKey::TotalAmountRetired, &(total_amount + amount));
 
-    /// Update the linked CarbonAsset contract address
+        let category_key = DataKey::CategoryAmount(category.unwrap_or(RetirementReason::Other));
+        let category_amount: i128 = env.storage().persistent().get(&category_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&category_key, &(category_amount + amount));
+        extend_persistent_ttl(env, &category_key);
+
+        Self::record_day_retirements(env, retiring_entity, 0, amount);
+    }
+
+    /// Add to the current day's bucket of the daily retirement index
+    fn record_day_retirements(env: &Env, retiring_entity: &Address, count: u64, amount: i128) {
+        let today = env.ledger().timestamp() / SECONDS_PER_DAY;
+        let key = DataKey::DayRetirements(today);
+        let mut day: DayRetirements =
+            env.storage()
+                .persistent()
+                .get(&key)
+                .unwrap_or(DayRetirements {
+                    count: 0,
+                    total_amount: 0,
+                    entities_by_gap: Map::new(env),
+                });
+        day.count += count;
+        day.total_amount += amount;
+
+        let entity_key = DataKey::DayEntity(today, retiring_entity.clone());
+        let entity_count: Option<u64> = env.storage().persistent().get(&entity_key);
+        if entity_count.is_none() {
+            // First activity of the entity today
+            let last_key = DataKey::EntityLastDay(retiring_entity.clone());
+            let gap = match env.storage().persistent().get::<_, u64>(&last_key) {
+                Some(last_day) => today.saturating_sub(last_day).clamp(1, MAX_STATS_DAYS),
+                None => MAX_STATS_DAYS,
+            } as u32;
+            let entities = day.entities_by_gap.get(gap).unwrap_or(0);
+            day.entities_by_gap.set(gap, entities + 1);
+            env.storage().persistent().set(&last_key, &today);
+            extend_persistent_ttl(env, &last_key);
+        }
+        env.storage()
+            .persistent()
+            .set(&entity_key, &(entity_count.unwrap_or(0) + count));
+        extend_persistent_ttl(env, &entity_key);
+
+        env.storage().persistent().set(&key, &day);
+        extend_persistent_ttl(env, &key);
+    }
+
+    /// Whether retiring a whole token costs `retiring_entity` a fee or quota,
+    /// so its amount has to be read
+    fn is_retirement_charged(env: &Env, retiring_entity: &Address) -> bool {
+        Self::fee_pool(env, retiring_entity).is_some()
+            || env
+                .storage()
+                .persistent()
+                .has(&DataKey::Quota(retiring_entity.clone()))
+    }
+
+    /// Buffer pool receiving the retirement fees of `retiring_entity`, while
+    /// a fee is configured
     ///
-    /// # Arguments
-    /// * `new_contract` - The new CarbonAsset contract address
+    /// Contracts retiring their own holdings (e.g. the TimeLock or the pool
+    /// itself) are not charged: the fee is donated from the entity's balance,
+    /// which needs an authorization they would have to grant up front.
+    fn fee_pool(env: &Env, retiring_entity: &Address) -> Option<Address> {
+        if matches!(retiring_entity.executable(), Some(Executable::Wasm(_))) {
+            return None;
+        }
+        Self::get_buffer_pool(env.clone()).filter(|_| Self::get_retirement_fee(env.clone()) > 0)
+    }
+
+    /// Work out the fee and quota charges of retiring a whole token of
+    /// `amount` credits, failing before anything moves when the quota would
+    /// be exceeded
+    fn retirement_charges(
+        env: &Env,
+        retiring_entity: &Address,
+        amount: i128,
+    ) -> Result<RetirementCharges, ContractError> {
+        let buffer_pool = Self::fee_pool(env, retiring_entity);
+        let fee = Self::fee_for(env, &buffer_pool, amount);
+        let quota = Self::charge_quota(env, retiring_entity, amount - fee)?;
+
+        Ok(RetirementCharges {
+            fee,
+            buffer_pool,
+            quota,
+        })
+    }
+
+    /// The retirement fee on `amount` credits, while `buffer_pool` collects one
+    fn fee_for(env: &Env, buffer_pool: &Option<Address>, amount: i128) -> i128 {
+        if buffer_pool.is_some() {
+            amount * Self::get_retirement_fee(env.clone()) as i128 / 10_000
+        } else {
+            0
+        }
+    }
+
+    /// Donate the retirement fee to the buffer pool through its
+    /// `donate_amount`, so the pool accounts for it
+    fn pay_retirement_fee(
+        env: &Env,
+        token_id: u32,
+        retiring_entity: &Address,
+        charges: &RetirementCharges,
+    ) {
+        if let Some(buffer_pool) = charges.buffer_pool.as_ref().filter(|_| charges.fee > 0) {
+            BufferPoolClient::new(env, buffer_pool).donate_amount(
+                retiring_entity,
+                &token_id,
+                &charges.fee,
+            );
+        }
+    }
+
+    /// Amount of a token held by `from`, verifying ownership
     ///
-    /// # Errors
-    /// * `ContractError::NotAuthorized` - Caller is not the admin
-    pub fn update_carbon_asset_contract(
-        env: Env,
-        caller: Address,
-        new_contract: Address,
-    ) -> Result<(), ContractError> {
-        // Require auth for admin function
+    /// Uses the asset's combined `retire_info` when available to save a
+    /// cross-contract call, falling back to `owner_of` and `amount_of`.
+    fn owned_balance(
+        carbon_asset: &CarbonAssetClient,
+        token_id: u32,
+        from: &Address,
+    ) -> Result<i128, ContractError> {
+        let (owner, balance) = match carbon_asset.try_retire_info(&token_id, from) {
+            Ok(Ok(info)) => info,
+            _ => (
+                carbon_asset
+                    .owner_of(&token_id)
+                    .ok_or(ContractError::TokenNotOwned)?,
+                carbon_asset.amount_of(&token_id),
+            ),
+        };
+
+        if owner != *from {
+            return Err(ContractError::TokenNotOwned);
+        }
+
+        Ok(balance)
+    }
+
+    /// Burn `amount` from a token without retiring it, tracking the total burned
+    ///
+    /// Applies the same `check_retirable` checks as a whole-token retirement.
+    /// The retirement fee is charged on top of `amount` and donated from the
+    /// token once the burn has gone through.
+    ///
+    /// # Returns
+    /// The fee donated to the buffer pool
+    fn retire_partial(
+        env: &Env,
+        carbon_asset: &CarbonAssetClient,
+        token_id: u32,
+        retiring_entity: &Address,
+        amount: i128,
+    ) -> Result<i128, ContractError> {
+        Self::check_retirable(env, token_id, retiring_entity)?;
+        let buffer_pool = Self::fee_pool(env, retiring_entity);
+        let charges = RetirementCharges {
+            fee: Self::fee_for(env, &buffer_pool, amount),
+            buffer_pool,
+            quota: Self::charge_quota(env, retiring_entity, amount)?,
+        };
+
+        if carbon_asset
+            .try_burn_amount(&token_id, retiring_entity, &amount)
+            .is_err()
+        {
+            return Err(ContractError::BurnFailed);
+        }
+        Self::pay_retirement_fee(env, token_id, retiring_entity, &charges);
+        let fee = charges.fee;
+        Self::store_quota(env, retiring_entity, charges.quota);
+
+        if let Some(project_id) = Self::project_of(carbon_asset, token_id) {
+            Self::record_project_amount(env, project_id, amount);
+        }
+        if let Some(vintage) = Self::vintage_of(carbon_asset, token_id) {
+            Self::record_vintage_amount(env, vintage, amount);
+        }
+
+        let partial_key = DataKey::PartialRetired(token_id);
+        let total: i128 = env.storage().persistent().get(&partial_key).unwrap_or(0);
+        env.storage()
+            .persistent()
+            .set(&partial_key, &(total + amount));
+        extend_persistent_ttl(env, &partial_key);
+
+        let timestamp = env.ledger().timestamp();
+        let history_key = DataKey::RetirementHistory(token_id);
+        let mut history = Self::get_retirement_history(env.clone(), token_id);
+        history.push_back(PartialRetirement {
+            amount,
+            timestamp,
+            retiring_entity: retiring_entity.clone(),
+        });
+        env.storage().persistent().set(&history_key, &history);
+        extend_persistent_ttl(env, &history_key);
+        extend_instance_ttl(env);
+
+        PartialRetirementEvent {
+            token_id,
+            retiring_entity: retiring_entity.clone(),
+            amount,
+            timestamp,
+            fee,
+        }
+        .publish(env);
+        Ok(fee)
+    }
+
+    /// The entity's quota with `amount` charged to it, if it has one, for
+    /// `store_quota` to save once the credits are burned
+    fn charge_quota(
+        env: &Env,
+        retiring_entity: &Address,
+        amount: i128,
+    ) -> Result<Option<RetirementQuota>, ContractError> {
+        let mut quota: RetirementQuota = match env
+            .storage()
+            .persistent()
+            .get(&DataKey::Quota(retiring_entity.clone()))
+        {
+            Some(quota) => Self::roll_quota_window(env, quota),
+            None => return Ok(None),
+        };
+
+        let used = quota.used.saturating_add(amount);
+        if used > quota.amount {
+            return Err(ContractError::QuotaExceeded);
+        }
+        quota.used = used;
+
+        let now = env.ledger().timestamp();
+        match quota.charges.last() {
+            Some((timestamp, charged)) if timestamp == now => {
+                quota
+                    .charges
+                    .set(quota.charges.len() - 1, (now, charged + amount));
+            }
+            _ => quota.charges.push_back((now, amount)),
+        }
+        Ok(Some(quota))
+    }
+
+    /// Drop the charges that have left the quota's rolling window
+    fn roll_quota_window(env: &Env, mut quota: RetirementQuota) -> RetirementQuota {
+        let now = env.ledger().timestamp();
+        while let Some((timestamp, charged)) = quota.charges.first() {
+            if timestamp.saturating_add(quota.period_seconds) > now {
+                break;
+            }
+            quota.charges.pop_front();
+            quota.used -= charged;
+        }
+        quota
+    }
+
+    /// Save a quota returned by `charge_quota`
+    fn store_quota(env: &Env, retiring_entity: &Address, quota: Option<RetirementQuota>) {
+        if let Some(quota) = quota {
+            let key = DataKey::Quota(retiring_entity.clone());
+            env.storage().persistent().set(&key, &quota);
+            extend_persistent_ttl(env, &key);
+        }
+    }
+
+    fn time_lock_client(env: &Env) -> Option<TimeLockClient<'_>> {
+        let time_lock: Option<Address> = env.storage().instance().get(&DataKey::TimeLockContract);
+        time_lock.map(|time_lock| TimeLockClient::new(env, &time_lock))
+    }
+
+    /// Resolve a token's status, consulting `time_lock` when one is configured
+    fn resolve_token_status(
+        env: &Env,
+        time_lock: Option<&TimeLockClient>,
+        token_id: u32,
+    ) -> TokenStatus {
+        if let Some(record) = Self::get_retirement_record(env.clone(), token_id) {
+            return TokenStatus::Retired(record.timestamp);
+        }
+
+        if let Some(unlock) = time_lock.and_then(|client| client.get_unlock_timestamp(&token_id)) {
+            return TokenStatus::Locked(unlock);
+        }
+
+        TokenStatus::Free
+    }
+
+    fn carbon_asset_contract(env: &Env) -> Result<Address, ContractError> {
+        env.storage()
+            .instance()
+            .get(&DataKey::CarbonAssetContract)
+            .ok_or(ContractError::ContractNotInitialized)
+    }
+
+    /// Fail with `Paused` while the shared controller, if any, is paused
+    fn require_not_paused(env: &Env) -> Result<(), ContractError> {
+        let controller: Option<Address> = env.storage().instance().get(&DataKey::Controller);
+        match controller {
+            Some(controller) if ControllerClient::new(env, &controller).is_paused() => {
+                Err(ContractError::Paused)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Fail with `ContractNotInitialized` when `initialize` has not been called
+    fn require_initialized(env: &Env) -> Result<(), ContractError> {
+        if !env.storage().instance().has(&DataKey::Admin) {
+            return Err(ContractError::ContractNotInitialized);
+        }
+
+        Ok(())
+    }
+
+    /// Require `caller` to be the authenticated admin
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), ContractError> {
         caller.require_auth();
 
         let admin: Address = env
@@ -296,37 +3558,72 @@ impl RetirementTracker {
             .get(&DataKey::Admin)
             .ok_or(ContractError::ContractNotInitialized)?;
 
-        if caller != admin {
+        if *caller != admin {
             return Err(ContractError::NotAuthorized);
         }
 
-        let old_contract: Address = env
+        Ok(())
+    }
+
+    /// Re-rank `retiring_entity` in the largest-index table after its
+    /// index changed to `len` entries
+    fn record_index_size(env: &Env, retiring_entity: &Address, len: u32) {
+        let mut largest: Vec<(Address, u32)> = env
             .storage()
             .instance()
-            .get(&DataKey::CarbonAssetContract)
-            .ok_or(ContractError::ContractNotInitialized)?;
+            .get(&DataKey::LargestIndexes)
+            .unwrap_or(Vec::new(env));
+
+        if let Some(pos) = largest
+            .iter()
+            .position(|(entity, _)| entity == *retiring_entity)
+        {
+            largest.remove(pos as u32);
+        }
+        if len > 0 {
+            let pos = largest
+                .iter()
+                .position(|(_, size)| size < len)
+                .map_or(largest.len(), |pos| pos as u32);
+            largest.insert(pos, (retiring_entity.clone(), len));
+        }
+        while largest.len() > MAX_LARGEST_INDEXES {
+            largest.pop_back();
+        }
 
         env.storage()
             .instance()
-            .set(&DataKey::CarbonAssetContract, &new_contract);
-
-        // Emit event
-        ContractUpdatedEvent {
-            old_contract,
-            new_contract,
-            updated_by: caller,
-        }
-        .publish(&env);
-        Ok(())
+            .set(&DataKey::LargestIndexes, &largest);
     }
 
-    /// Get the current admin address
-    pub fn get_admin(env: Env) -> Option<Address> {
-        env.storage().instance().get(&DataKey::Admin)
+    /// Append a newly seen retiring entity to the entity list
+    fn append_entity(env: &Env, retiring_entity: &Address) {
+        let count: u32 = env
+            .storage()
+            .instance()
+            .get(&DataKey::EntityCount)
+            .unwrap_or(0);
+
+        let list_key = DataKey::EntityList(count);
+        env.storage().persistent().set(&list_key, retiring_entity);
+        extend_persistent_ttl(env, &list_key);
+        env.storage()
+            .instance()
+            .set(&DataKey::EntityCount, &(count + 1));
     }
 
-    /// Get the current CarbonAsset contract address
-    pub fn get_carbon_asset_contract(env: Env) -> Option<Address> {
-        env.storage().instance().get(&DataKey::CarbonAssetContract)
+    /// Notify the configured listener, recording failures as an event
+    fn notify_listener(env: &Env, token_id: u32, retiring_entity: &Address) {
+        let listener: Option<Address> = env.storage().instance().get(&DataKey::NotifyContract);
+        if let Some(listener) = listener {
+            let result = RetirementListenerClient::new(env, &listener)
+                .try_on_retirement(&token_id, retiring_entity);
+            if result.is_err() {
+                NotificationFailedEvent { token_id, listener }.publish(env);
+            }
+        }
     }
 }
+
+#[cfg(test)]
+mod test;
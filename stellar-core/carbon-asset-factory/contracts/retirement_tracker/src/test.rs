@@ -0,0 +1,2520 @@
+#![cfg(test)]
+
+use crate::{
+    error_code, ClaimStatus, ComplianceDetails, ComplianceRetirementEvent, ContractError, DataKey,
+    ExpiryAction, PartialRetirement, PartialRetirementEvent, PendingRetirement, RangeStats,
+    ReportRow, RetirementEvent, RetirementReason, RetirementRecord, RetirementStats,
+    RetirementTracker, RetirementTrackerClient, RetirementVoidedEvent, TokenStatus, TrackerConfig,
+    DEFAULT_UNITS, ID_BUCKET_SIZE, LEDGER_BUCKET_SIZE, MAX_EXPORT_BUCKETS, MAX_HELD_BATCH_SIZE,
+    MAX_LARGEST_INDEXES, MAX_LEDGER_BUCKETS, MAX_PAGE_SIZE, MAX_RANGE_SIZE, MAX_STATS_DAYS,
+    PERSISTENT_BUMP_AMOUNT, SECONDS_PER_DAY,
+};
+use soroban_sdk::{
+    contract, contractimpl, symbol_short,
+    testutils::{storage::Persistent as _, Address as _, Events as _, Ledger},
+    vec, Address, Bytes, BytesN, Env, Event, String, Symbol, Val, Vec,
+};
+
+#[contract]
+pub struct MockCarbonAsset;
+
+#[contractimpl]
+impl MockCarbonAsset {
+    pub fn mint(env: Env, to: Address, token_id: u32) {
+        env.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Option<Address> {
+        Self::count_call(&env, symbol_short!("owner_of"));
+        env.storage().persistent().get(&token_id)
+    }
+
+    pub fn retire_info(env: Env, token_id: u32, _from: Address) -> (Address, i128) {
+        assert!(!env.storage().instance().has(&symbol_short!("legacy")));
+        Self::count_call(&env, symbol_short!("info"));
+        (
+            env.storage().persistent().get(&token_id).unwrap(),
+            Self::stored_amount(&env, token_id),
+        )
+    }
+
+    /// Make `retire_info` fail, as on assets that predate it
+    pub fn disable_retire_info(env: Env) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("legacy"), &true);
+    }
+
+    pub fn calls(env: Env, name: Symbol) -> u32 {
+        env.storage().instance().get(&name).unwrap_or(0)
+    }
+
+    fn count_call(env: &Env, name: Symbol) {
+        let calls = Self::calls(env.clone(), name.clone());
+        env.storage().instance().set(&name, &(calls + 1));
+    }
+
+    fn stored_amount(env: &Env, token_id: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("amount"), token_id))
+            .unwrap()
+    }
+
+    pub fn burn(env: Env, token_id: u32, from: Address) {
+        let owner: Address = env.storage().persistent().get(&token_id).unwrap();
+        assert_eq!(owner, from);
+        assert!(!env.storage().instance().has(&symbol_short!("fail_burn")));
+        if !env.storage().instance().has(&symbol_short!("noop_burn")) {
+            env.storage().persistent().remove(&token_id);
+        }
+    }
+
+    /// Make `burn` succeed without removing the token
+    pub fn set_noop_burn(env: Env, enabled: bool) {
+        if enabled {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("noop_burn"), &true);
+        } else {
+            env.storage().instance().remove(&symbol_short!("noop_burn"));
+        }
+    }
+
+    /// Make `burn` fail even for the token's owner
+    pub fn set_failing_burn(env: Env, enabled: bool) {
+        if enabled {
+            env.storage()
+                .instance()
+                .set(&symbol_short!("fail_burn"), &true);
+        } else {
+            env.storage().instance().remove(&symbol_short!("fail_burn"));
+        }
+    }
+
+    pub fn set_amount(env: Env, token_id: u32, amount: i128) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("amount"), token_id), &amount);
+    }
+
+    pub fn amount_of(env: Env, token_id: u32) -> i128 {
+        Self::count_call(&env, symbol_short!("amount_of"));
+        Self::stored_amount(&env, token_id)
+    }
+
+    pub fn transfer_amount(env: Env, from: Address, to: Address, token_id: u32, amount: i128) {
+        let owner: Address = env.storage().persistent().get(&token_id).unwrap();
+        assert_eq!(owner, from);
+        let key = (symbol_short!("received"), to);
+        let received: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(received + amount));
+        let remaining = Self::stored_amount(&env, token_id) - amount;
+        Self::set_amount(env, token_id, remaining);
+    }
+
+    pub fn set_standard(env: Env, token_id: u32, standard: Symbol) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("standard"), token_id), &standard);
+    }
+
+    pub fn standard_of(env: Env, token_id: u32) -> Symbol {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("standard"), token_id))
+            .unwrap()
+    }
+
+    pub fn set_project(env: Env, token_id: u32, project_id: u32) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("project"), token_id), &project_id);
+    }
+
+    pub fn project_of(env: Env, token_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("project"), token_id))
+            .unwrap()
+    }
+
+    pub fn set_vintage(env: Env, token_id: u32, vintage: u32) {
+        env.storage()
+            .persistent()
+            .set(&(symbol_short!("vintage"), token_id), &vintage);
+    }
+
+    pub fn vintage_of(env: Env, token_id: u32) -> u32 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("vintage"), token_id))
+            .unwrap()
+    }
+
+    pub fn received(env: Env, to: Address) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(symbol_short!("received"), to))
+            .unwrap_or(0)
+    }
+
+    pub fn burn_amount(env: Env, token_id: u32, from: Address, amount: i128) {
+        let owner: Address = env.storage().persistent().get(&token_id).unwrap();
+        assert_eq!(owner, from);
+        let remaining = Self::stored_amount(&env, token_id) - amount;
+        assert!(remaining > 0);
+        Self::set_amount(env, token_id, remaining);
+    }
+}
+
+fn setup_test_env<'a>() -> (
+    Env,
+    Address,
+    RetirementTrackerClient<'a>,
+    MockCarbonAssetClient<'a>,
+) {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let client = RetirementTrackerClient::new(&env, &env.register(RetirementTracker, ()));
+
+    client.initialize(&admin, &asset.address);
+
+    (env, admin, client, asset)
+}
+
+#[test]
+fn test_can_admin() {
+    let env = Env::default();
+    let client = RetirementTrackerClient::new(&env, &env.register(RetirementTracker, ()));
+    let admin = Address::generate(&env);
+    assert!(!client.can_admin(&admin));
+
+    let (env, admin, client, _) = setup_test_env();
+    assert!(client.can_admin(&admin));
+    assert!(!client.can_admin(&Address::generate(&env)));
+}
+
+#[test]
+fn test_get_ledger_context() {
+    let (env, _, client, _) = setup_test_env();
+    env.ledger().set_timestamp(12_345);
+    env.ledger().set_sequence_number(678);
+    assert_eq!(client.get_ledger_context(), (12_345, 678));
+}
+
+#[test]
+fn test_retire() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    let reason = Some(String::from_str(&env, "Annual offset"));
+    let record = client.retire(&1, &entity, &reason);
+
+    assert_eq!(record.token_id, 1);
+    assert_eq!(record.retiring_entity, entity);
+    assert_eq!(record.reason, reason);
+    assert!(client.is_retired(&1));
+    assert!(asset.owner_of(&1).is_none());
+    assert_eq!(client.get_retirements_by_entity(&entity).len(), 1);
+}
+
+#[test]
+fn test_retire_twice_fails() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    client.retire(&1, &entity, &None);
+    let result = client.try_retire(&1, &entity, &None);
+
+    assert_eq!(result, Err(Ok(ContractError::TokenAlreadyRetired)));
+}
+
+#[test]
+fn test_retire_extends_record_ttl() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    client.retire(&1, &entity, &None);
+
+    env.as_contract(&client.address, || {
+        let ledger_ttl = env
+            .storage()
+            .persistent()
+            .get_ttl(&DataKey::RetirementLedger(1));
+        let index_ttl = env
+            .storage()
+            .persistent()
+            .get_ttl(&DataKey::EntityIndex(entity.clone()));
+        assert!(ledger_ttl >= PERSISTENT_BUMP_AMOUNT - 1);
+        assert!(index_ttl >= PERSISTENT_BUMP_AMOUNT - 1);
+    });
+}
+
+#[test]
+fn test_bump_ttl() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    client.retire(&1, &entity, &None);
+    client.bump_ttl(&1);
+
+    let result = client.try_bump_ttl(&2);
+    assert_eq!(result, Err(Ok(ContractError::InvalidTokenId)));
+}
+
+#[contract]
+pub struct EnumerableAsset;
+
+#[contractimpl]
+impl EnumerableAsset {
+    pub fn mint(env: Env, to: Address, token_id: u32) {
+        let mut held = Self::tokens_of(env.clone(), to.clone());
+        held.push_back(token_id);
+        env.storage().persistent().set(&to, &held);
+    }
+
+    pub fn tokens_of(env: Env, owner: Address) -> Vec<u32> {
+        env.storage()
+            .persistent()
+            .get(&owner)
+            .unwrap_or(Vec::new(&env))
+    }
+
+    pub fn burn(env: Env, token_id: u32, from: Address) {
+        let mut held = Self::tokens_of(env.clone(), from.clone());
+        let index = held.first_index_of(token_id).unwrap();
+        held.remove(index);
+        env.storage().persistent().set(&from, &held);
+    }
+}
+
+#[contract]
+pub struct MockTimeLock;
+
+#[contractimpl]
+impl MockTimeLock {
+    pub fn lock(env: Env, token_id: u32, unlock_timestamp: u64) {
+        env.storage().persistent().set(&token_id, &unlock_timestamp);
+    }
+
+    pub fn get_unlock_timestamp(env: Env, token_id: u32) -> Option<u64> {
+        env.storage().persistent().get(&token_id)
+    }
+
+    pub fn lock_credit(
+        env: Env,
+        caller: Address,
+        _owner: Address,
+        token_id: u32,
+        unlock_timestamp: u64,
+        _release_to: Option<Address>,
+        _on_expiry: Option<ExpiryAction>,
+    ) {
+        caller.require_auth();
+        Self::lock(env, token_id, unlock_timestamp);
+    }
+}
+
+#[contract]
+pub struct MockBufferPool;
+
+#[contractimpl]
+impl MockBufferPool {
+    pub fn init(env: Env, asset: Address) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("asset"), &asset);
+    }
+
+    pub fn donate_amount(env: Env, from: Address, token_id: u32, amount: i128) {
+        from.require_auth();
+        let asset: Address = env
+            .storage()
+            .instance()
+            .get(&symbol_short!("asset"))
+            .unwrap();
+        let pool = env.current_contract_address();
+        MockCarbonAssetClient::new(&env, &asset).transfer_amount(&from, &pool, &token_id, &amount);
+        let donated = Self::donated(env.clone(), token_id);
+        env.storage()
+            .persistent()
+            .set(&token_id, &(donated + amount));
+    }
+
+    pub fn donated(env: Env, token_id: u32) -> i128 {
+        env.storage().persistent().get(&token_id).unwrap_or(0)
+    }
+
+    pub fn retire_held(env: Env, tracker: Address, token_id: u32) -> RetirementRecord {
+        RetirementTrackerClient::new(&env, &tracker).retire(
+            &token_id,
+            &env.current_contract_address(),
+            &None,
+        )
+    }
+}
+
+#[contract]
+pub struct MockMarketplace;
+
+#[contractimpl]
+impl MockMarketplace {
+    pub fn retire_for(
+        env: Env,
+        tracker: Address,
+        token_id: u32,
+        retiring_entity: Address,
+    ) -> RetirementRecord {
+        RetirementTrackerClient::new(&env, &tracker).retire_via(
+            &token_id,
+            &retiring_entity,
+            &env.current_contract_address(),
+            &None,
+        )
+    }
+}
+
+/// Asset contract that burns a token itself and then records it with the tracker
+#[contract]
+pub struct SelfRetiringAsset;
+
+#[contractimpl]
+impl SelfRetiringAsset {
+    pub fn mint(env: Env, to: Address, token_id: u32) {
+        env.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Option<Address> {
+        env.storage().persistent().get(&token_id)
+    }
+
+    pub fn retire(env: Env, tracker: Address, token_id: u32, from: Address) -> RetirementRecord {
+        from.require_auth();
+        assert_eq!(Self::owner_of(env.clone(), token_id), Some(from.clone()));
+        env.storage().persistent().remove(&token_id);
+        RetirementTrackerClient::new(&env, &tracker).record_asset_retirement(
+            &token_id,
+            &from,
+            &None,
+            &Some(symbol_short!("GOLD")),
+            &None,
+        )
+    }
+}
+
+#[test]
+fn test_retire_records_no_intermediary() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    let record = client.retire(&1, &entity, &None);
+    assert_eq!(record.initiated_by, None);
+}
+
+#[test]
+fn test_retire_via_proxy_contract() {
+    let (env, _, client, asset) = setup_test_env();
+    env.mock_all_auths_allowing_non_root_auth();
+
+    let entity = Address::generate(&env);
+    let marketplace = MockMarketplaceClient::new(&env, &env.register(MockMarketplace, ()));
+    asset.mint(&entity, &1);
+
+    let record = marketplace.retire_for(&client.address, &1, &entity);
+    assert_eq!(record.initiated_by, Some(marketplace.address.clone()));
+
+    let stored = client.get_retirement_record(&1).unwrap();
+    assert_eq!(stored.initiated_by, Some(marketplace.address));
+}
+
+#[test]
+fn test_trusted_contract_retires_its_holdings() {
+    let (env, admin, client, asset) = setup_test_env();
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    asset.mint(&pool.address, &1);
+
+    let result = pool.try_retire_held(&client.address, &1);
+    assert!(result.is_err());
+    assert!(!client.is_retired(&1));
+
+    client.set_trusted_retirer(&admin, &pool.address, &true);
+    assert!(client.is_trusted_retirer(&pool.address));
+    let record = pool.retire_held(&client.address, &1);
+    assert_eq!(record.retiring_entity, pool.address);
+    assert!(client.is_retired(&1));
+
+    client.set_trusted_retirer(&admin, &pool.address, &false);
+    assert!(!client.is_trusted_retirer(&pool.address));
+}
+
+#[test]
+fn test_has_retired() {
+    let (env, _, client, asset) = setup_test_env();
+    let retirer = Address::generate(&env);
+    let newcomer = Address::generate(&env);
+    asset.mint(&retirer, &1);
+
+    client.retire(&1, &retirer, &None);
+
+    assert!(client.has_retired(&retirer));
+    assert!(!client.has_retired(&newcomer));
+}
+
+#[contract]
+pub struct MockListener;
+
+#[contractimpl]
+impl MockListener {
+    pub fn on_retirement(env: Env, token_id: u32, retiring_entity: Address) {
+        env.storage().persistent().set(&token_id, &retiring_entity);
+    }
+
+    pub fn notified(env: Env, token_id: u32) -> Option<Address> {
+        env.storage().persistent().get(&token_id)
+    }
+}
+
+#[contract]
+pub struct FailingListener;
+
+#[contractimpl]
+impl FailingListener {
+    pub fn on_retirement(_env: Env, _token_id: u32, _retiring_entity: Address) {
+        panic!("listener failure");
+    }
+}
+
+#[test]
+fn test_retire_notifies_listener() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let listener = MockListenerClient::new(&env, &env.register(MockListener, ()));
+    asset.mint(&entity, &1);
+
+    client.set_notify_contract(&admin, &Some(listener.address.clone()));
+    assert_eq!(client.get_notify_contract(), Some(listener.address.clone()));
+
+    client.retire(&1, &entity, &None);
+    assert_eq!(listener.notified(&1), Some(entity));
+}
+
+#[test]
+fn test_failing_listener_does_not_block_retirement() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let listener = env.register(FailingListener, ());
+    asset.mint(&entity, &1);
+
+    client.set_notify_contract(&admin, &Some(listener));
+    client.retire(&1, &entity, &None);
+
+    assert!(client.is_retired(&1));
+}
+
+#[test]
+fn test_set_notify_contract_not_admin() {
+    let (env, _, client, _) = setup_test_env();
+    let stranger = Address::generate(&env);
+
+    let result = client.try_set_notify_contract(&stranger, &None);
+    assert_eq!(result, Err(Ok(ContractError::NotAuthorized)));
+}
+
+#[test]
+fn test_retire_range_skips_retired_and_unowned() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let other = Address::generate(&env);
+
+    for token_id in 100..=105 {
+        asset.mint(&entity, &token_id);
+    }
+    asset.mint(&other, &106);
+    client.retire(&102, &entity, &None);
+
+    let records = client.retire_range(&100, &106, &entity, &None);
+
+    assert_eq!(records.len(), 5);
+    let mut retired = Vec::new(&env);
+    for record in records.iter() {
+        retired.push_back(record.token_id);
+    }
+    assert_eq!(retired, vec![&env, 100, 101, 103, 104, 105]);
+    assert!(!client.is_retired(&106));
+    assert_eq!(asset.owner_of(&106), Some(other));
+}
+
+#[test]
+fn test_retire_range_bounds() {
+    let (env, _, client, _) = setup_test_env();
+    let entity = Address::generate(&env);
+
+    let result = client.try_retire_range(&10, &5, &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::InvalidRange)));
+
+    let result = client.try_retire_range(&0, &MAX_RANGE_SIZE, &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::InvalidRange)));
+}
+
+#[test]
+fn test_batch_retire() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.mint(&entity, &2);
+
+    let records = client.batch_retire(&vec![&env, 1, 2, 3], &entity, &None);
+
+    assert_eq!(records.len(), 2);
+    assert!(client.is_retired(&1));
+    assert!(client.is_retired(&2));
+    assert!(!client.is_retired(&3));
+}
+
+#[test]
+fn test_reemit_retirement_event() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    let record = client.retire(&1, &entity, &None);
+    env.ledger().set_timestamp(record.timestamp + 1_000);
+
+    client.reemit_retirement_event(&admin, &1);
+
+    let expected = RetirementEvent {
+        token_id: 1,
+        retiring_entity: entity,
+        timestamp: record.timestamp,
+        tx_hash: record.tx_hash,
+        initiated_by: None,
+        reemitted: true,
+        fee: 0,
+        reason: None,
+    };
+    let last = env.events().all().last().unwrap();
+    assert_eq!(
+        vec![&env, last],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                expected.topics(&env),
+                expected.data(&env)
+            )
+        ]
+    );
+}
+
+#[test]
+fn test_reemit_missing_record_fails() {
+    let (_, admin, client, _) = setup_test_env();
+
+    let result = client.try_reemit_retirement_event(&admin, &42);
+    assert_eq!(result, Err(Ok(ContractError::InvalidTokenId)));
+}
+
+#[test]
+fn test_retire_certified() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let certificate_hash = BytesN::from_array(&env, &[7u8; 32]);
+    asset.mint(&entity, &1);
+
+    let certificate_id =
+        client.retire_certified(&1, &entity, &beneficiary, &None, &certificate_hash);
+
+    let record = client
+        .get_retirement_by_certificate(&certificate_id)
+        .unwrap();
+    assert_eq!(record, client.get_retirement_record(&1).unwrap());
+
+    let certificate = client.get_certificate(&1).unwrap();
+    assert_eq!(certificate.certificate_id, certificate_id);
+    assert_eq!(certificate.beneficiary, beneficiary);
+    assert_eq!(certificate.certificate_hash, certificate_hash);
+
+    let unknown = BytesN::from_array(&env, &[0u8; 32]);
+    assert_eq!(client.get_retirement_by_certificate(&unknown), None);
+}
+
+#[test]
+fn test_atomic_retire_rolls_back_unfinalized_burn() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    assert!(!client.is_atomic_retire());
+
+    let stranger = Address::generate(&env);
+    let result = client.try_set_atomic_retire(&stranger, &true);
+    assert_eq!(result, Err(Ok(ContractError::NotAuthorized)));
+    client.set_atomic_retire(&admin, &true);
+    assert!(client.get_config().atomic_retire);
+
+    // The burn call succeeds but the token stays with the entity
+    asset.set_noop_burn(&true);
+    let result = client.try_retire(&1, &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::BurnNotFinalized)));
+    assert_eq!(client.get_retirement_record(&1), None);
+    assert_eq!(client.get_pending_retirement(&1), None);
+    assert_eq!(client.get_total_retired(), 0);
+    assert!(client.get_retirements_by_entity(&entity).is_empty());
+
+    // The batch paths fail as a whole, reverting the fee paid before the burn
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    pool.init(&asset.address);
+    client.set_retirement_fee(&admin, &100);
+    client.set_buffer_pool(&admin, &Some(pool.address.clone()));
+    asset.set_amount(&1, &1_000);
+    let result = client.try_batch_retire(&vec![&env, 1], &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::BurnNotFinalized)));
+    let result = client.try_retire_range(&1, &1, &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::BurnNotFinalized)));
+    assert_eq!(pool.donated(&1), 0);
+    assert_eq!(client.get_retirement_record(&1), None);
+
+    asset.set_noop_burn(&false);
+    let record = client.retire(&1, &entity, &None);
+    assert_eq!(client.get_retirement_record(&1), Some(record));
+    assert_eq!(client.get_pending_retirement(&1), None);
+    assert!(asset.owner_of(&1).is_none());
+}
+
+#[test]
+fn test_retire_compliance_emits_bundled_event() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    let serial = String::from_str(&env, "VCS-1234-2021-001");
+    let reason = Some(String::from_str(&env, "CORSIA filing"));
+    let record = client.retire_compliance(
+        &1,
+        &entity,
+        &beneficiary,
+        &symbol_short!("VERRA"),
+        &2021,
+        &serial,
+        &reason,
+    );
+
+    let expected = ComplianceRetirementEvent {
+        token_id: 1,
+        retiring_entity: entity.clone(),
+        beneficiary: beneficiary.clone(),
+        standard: symbol_short!("VERRA"),
+        vintage: 2021,
+        serial: serial.clone(),
+        reason: reason.clone(),
+        timestamp: record.timestamp,
+        tx_hash: record.tx_hash.clone(),
+    };
+    assert_eq!(
+        vec![&env, env.events().all().last().unwrap()],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                expected.topics(&env),
+                expected.data(&env)
+            )
+        ]
+    );
+
+    assert_eq!(
+        client.get_compliance_details(&1),
+        Some(ComplianceDetails {
+            beneficiary,
+            standard: symbol_short!("VERRA"),
+            vintage: 2021,
+            serial,
+        })
+    );
+    assert_eq!(client.get_retirement_record(&1).unwrap(), record);
+}
+
+#[test]
+fn test_retire_with_memo_hash() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    assert!(client.get_memo_hash(&1).is_none());
+
+    let memo = Bytes::from_slice(&env, b"Scope 3 offset for FY2024 supplier flights");
+    let memo_hash: BytesN<32> = env.crypto().sha256(&memo).into();
+    let record = client.retire_with_memo_hash(&1, &entity, &memo_hash);
+
+    assert!(record.reason.is_none());
+    assert!(client.get_retirement_record(&1).unwrap().reason.is_none());
+    assert_eq!(client.get_memo_hash(&1), Some(memo_hash));
+
+    // A revealed preimage other than the original does not match
+    let wrong = Bytes::from_slice(&env, b"Scope 1 offset");
+    let wrong_hash: BytesN<32> = env.crypto().sha256(&wrong).into();
+    assert_ne!(client.get_memo_hash(&1), Some(wrong_hash));
+
+    let result = client.try_retire_with_memo_hash(&1, &entity, &BytesN::from_array(&env, &[1; 32]));
+    assert_eq!(result, Err(Ok(ContractError::TokenAlreadyRetired)));
+}
+
+#[test]
+fn test_retire_compliance_rejects_missing_fields() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    let empty = String::from_str(&env, "");
+    let result = client.try_retire_compliance(
+        &1,
+        &entity,
+        &beneficiary,
+        &symbol_short!("VERRA"),
+        &2021,
+        &empty,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(ContractError::InvalidComplianceData)));
+
+    let serial = String::from_str(&env, "VCS-1234-2021-001");
+    let result = client.try_retire_compliance(
+        &1,
+        &entity,
+        &beneficiary,
+        &symbol_short!("VERRA"),
+        &0,
+        &serial,
+        &None,
+    );
+    assert_eq!(result, Err(Ok(ContractError::InvalidComplianceData)));
+    assert!(!client.is_retired(&1));
+    assert_eq!(client.get_compliance_details(&1), None);
+}
+
+#[test]
+fn test_get_retirement_report_row() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.set_standard(&1, &symbol_short!("VERRA"));
+    assert_eq!(client.get_retirement_report_row(&1), None);
+
+    let reason = Some(String::from_str(&env, "Scope 1 offset"));
+    client.retire_with_claim(&1, &entity, &reason, &(env.ledger().timestamp() + 100));
+    let certificate_hash = BytesN::from_array(&env, &[7u8; 32]);
+    client.batch_anchor_certificates(&admin, &vec![&env, (1, certificate_hash.clone())]);
+    client.confirm_claim(&admin, &1);
+
+    let record = client.get_retirement_record(&1).unwrap();
+    let certificate = client.get_certificate(&1).unwrap();
+    assert_eq!(
+        client.get_retirement_report_row(&1),
+        Some(ReportRow {
+            token_id: 1,
+            retiring_entity: entity.clone(),
+            timestamp: record.timestamp,
+            ledger_seq: record.ledger_seq,
+            tx_hash: record.tx_hash,
+            reason,
+            initiated_by: None,
+            fee: 0,
+            standard: Some(symbol_short!("VERRA")),
+            project_id: None,
+            partial_retired: 0,
+            beneficiary: Some(entity),
+            certificate_id: Some(certificate.certificate_id),
+            certificate_hash: Some(certificate_hash),
+            claim_status: ClaimStatus::Confirmed,
+            units: Symbol::new(&env, DEFAULT_UNITS),
+            decimals: 0,
+        })
+    );
+}
+
+#[test]
+fn test_get_entities_paginates() {
+    let (env, _, client, asset) = setup_test_env();
+    let mut entities = Vec::new(&env);
+    for token_id in 1..=5 {
+        let entity = Address::generate(&env);
+        asset.mint(&entity, &token_id);
+        client.retire(&token_id, &entity, &None);
+        entities.push_back(entity);
+    }
+
+    // A repeat retirement does not list the entity twice
+    let first = entities.get_unchecked(0);
+    asset.mint(&first, &6);
+    client.retire(&6, &first, &None);
+
+    assert_eq!(client.get_entity_count(), 5);
+    assert_eq!(client.get_entities(&0, &2), entities.slice(0..2));
+    assert_eq!(client.get_entities(&2, &2), entities.slice(2..4));
+    assert_eq!(client.get_entities(&4, &2), entities.slice(4..5));
+    assert_eq!(client.get_entities(&5, &2).len(), 0);
+    assert_eq!(client.get_entities(&0, &(MAX_PAGE_SIZE + 1)), entities);
+}
+
+#[test]
+fn test_checked_getters_before_initialize() {
+    let env = Env::default();
+    let client = RetirementTrackerClient::new(&env, &env.register(RetirementTracker, ()));
+    let entity = Address::generate(&env);
+
+    assert_eq!(
+        client.try_is_retired_strict(&1),
+        Err(Ok(ContractError::ContractNotInitialized))
+    );
+    assert_eq!(
+        client.try_get_retirement_record_strict(&1),
+        Err(Ok(ContractError::ContractNotInitialized))
+    );
+    assert_eq!(
+        client.try_get_retirements_by_entity_strict(&entity),
+        Err(Ok(ContractError::ContractNotInitialized))
+    );
+
+    // Lenient variants keep reporting an empty state
+    assert!(!client.is_retired(&1));
+    assert_eq!(client.get_retirement_record(&1), None);
+    assert_eq!(client.get_retirements_by_entity(&entity).len(), 0);
+}
+
+#[test]
+fn test_checked_getters_after_initialize() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    assert!(!client.is_retired_strict(&1));
+    client.retire(&1, &entity, &None);
+
+    assert!(client.is_retired_strict(&1));
+    assert!(client.get_retirement_record_strict(&1).is_some());
+    assert_eq!(
+        client.get_retirements_by_entity_strict(&entity),
+        vec![&env, 1]
+    );
+}
+
+#[test]
+fn test_retire_target_amount_spans_tokens() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    for (token_id, amount) in [(1, 200), (2, 250), (3, 300)] {
+        asset.mint(&entity, &token_id);
+        asset.set_amount(&token_id, &amount);
+    }
+
+    let retired = client.retire_target_amount(&entity, &vec![&env, 1, 2, 2, 3], &500, &None, &None);
+
+    assert_eq!(retired, vec![&env, (1, 200), (2, 250), (3, 50)]);
+    assert!(client.is_retired(&1));
+    assert!(client.is_retired(&2));
+    assert!(!client.is_retired(&3));
+    assert_eq!(asset.amount_of(&3), 250);
+    assert_eq!(client.get_partial_retired_amount(&3), 50);
+}
+
+#[test]
+fn test_retirement_history_keeps_partial_retirements() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.set_amount(&1, &1_000);
+
+    env.ledger().set_timestamp(100);
+    client.retire_target_amount(&entity, &vec![&env, 1], &300, &None, &None);
+    env.ledger().set_timestamp(200);
+    client.retire_target_amount(&entity, &vec![&env, 1], &200, &None, &None);
+
+    assert_eq!(
+        client.get_retirement_history(&1),
+        vec![
+            &env,
+            PartialRetirement {
+                amount: 300,
+                timestamp: 100,
+                retiring_entity: entity.clone(),
+            },
+            PartialRetirement {
+                amount: 200,
+                timestamp: 200,
+                retiring_entity: entity,
+            }
+        ]
+    );
+    assert_eq!(client.get_partial_retired_amount(&1), 500);
+    assert_eq!(client.get_retirement_history(&2).len(), 0);
+}
+
+#[test]
+fn test_retire_target_amount_insufficient_balance() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.set_amount(&1, &100);
+
+    let result = client.try_retire_target_amount(&entity, &vec![&env, 1], &101, &None, &None);
+    assert_eq!(result, Err(Ok(ContractError::InsufficientBalance)));
+    assert!(!client.is_retired(&1));
+    assert_eq!(asset.amount_of(&1), 100);
+}
+
+#[test]
+fn test_simulate_batch_retire() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let other = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.mint(&entity, &2);
+    asset.mint(&other, &3);
+    client.retire(&2, &entity, &None);
+
+    let outcomes = client.simulate_batch_retire(&vec![&env, 1, 2, 3, 4], &entity);
+
+    assert_eq!(
+        outcomes,
+        vec![&env, (1, true), (2, false), (3, false), (4, false)]
+    );
+    assert!(!client.is_retired(&1));
+    assert_eq!(
+        client
+            .batch_retire(&vec![&env, 1, 2, 3, 4], &entity, &None)
+            .len(),
+        1
+    );
+}
+
+#[test]
+fn test_batch_retire_deduplicates_ids() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.mint(&entity, &2);
+
+    let records = client.batch_retire(&vec![&env, 2, 1, 2, 1], &entity, &None);
+
+    let mut retired = Vec::new(&env);
+    for record in records.iter() {
+        retired.push_back(record.token_id);
+    }
+    assert_eq!(retired, vec![&env, 2, 1]);
+    assert_eq!(client.get_retirements_by_entity(&entity), vec![&env, 2, 1]);
+}
+
+#[test]
+fn test_retire_all_held() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let entity = Address::generate(&env);
+    let asset = EnumerableAssetClient::new(&env, &env.register(EnumerableAsset, ()));
+    let client = RetirementTrackerClient::new(&env, &env.register(RetirementTracker, ()));
+    client.initialize(&admin, &asset.address);
+
+    let held = MAX_HELD_BATCH_SIZE + 3;
+    for token_id in 0..held {
+        asset.mint(&entity, &token_id);
+    }
+
+    let (records, cursor) = client.retire_all_held(&entity, &None, &0);
+    assert_eq!(records.len(), MAX_HELD_BATCH_SIZE);
+    assert_eq!(cursor, Some(0));
+
+    let (records, cursor) = client.retire_all_held(&entity, &None, &0);
+    assert_eq!(records.len(), 3);
+    assert_eq!(cursor, None);
+
+    assert_eq!(asset.tokens_of(&entity).len(), 0);
+    assert_eq!(client.get_retirements_by_entity(&entity).len(), held);
+}
+
+#[test]
+fn test_retire_all_held_without_enumeration_fails() {
+    let (env, _, client, _) = setup_test_env();
+    let entity = Address::generate(&env);
+
+    let result = client.try_retire_all_held(&entity, &None, &0);
+    assert_eq!(result, Err(Ok(ContractError::EnumerationUnsupported)));
+}
+
+#[test]
+fn test_take_snapshot() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    for token_id in 1..=3 {
+        asset.mint(&entity, &token_id);
+        asset.set_amount(&token_id, &100);
+    }
+
+    client.retire(&1, &entity, &None);
+    let first = client.take_snapshot(&admin);
+
+    env.ledger().set_timestamp(5_000);
+    client.retire_target_amount(&entity, &vec![&env, 2, 3], &150, &None, &None);
+    let second = client.take_snapshot(&admin);
+
+    let first = client.get_snapshot(&first).unwrap();
+    assert_eq!(first.snapshot_id, 0);
+    assert_eq!(first.total_retired, 1);
+    assert_eq!(first.total_amount, 0);
+
+    let second = client.get_snapshot(&second).unwrap();
+    assert_eq!(second.snapshot_id, 1);
+    assert_eq!(second.total_retired, 2);
+    assert_eq!(second.total_amount, 150);
+    assert_eq!(second.timestamp, 5_000);
+
+    assert_eq!(client.get_snapshot(&2), None);
+    let stranger = Address::generate(&env);
+    assert_eq!(
+        client.try_take_snapshot(&stranger),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_get_token_status() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let time_lock = MockTimeLockClient::new(&env, &env.register(MockTimeLock, ()));
+    time_lock.lock(&2, &9_000);
+    asset.mint(&entity, &1);
+    env.ledger().set_timestamp(4_000);
+    client.retire(&1, &entity, &None);
+
+    // Without a TimeLock configured, locked tokens read as free
+    assert_eq!(client.get_token_status(&2), TokenStatus::Free);
+
+    client.set_time_lock_contract(&admin, &Some(time_lock.address.clone()));
+    assert_eq!(client.get_token_status(&1), TokenStatus::Retired(4_000));
+    assert_eq!(client.get_token_status(&2), TokenStatus::Locked(9_000));
+    assert_eq!(client.get_token_status(&3), TokenStatus::Free);
+}
+
+#[test]
+fn test_get_token_statuses() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let time_lock = MockTimeLockClient::new(&env, &env.register(MockTimeLock, ()));
+    client.set_time_lock_contract(&admin, &Some(time_lock.address.clone()));
+    time_lock.lock(&2, &9_000);
+    asset.mint(&entity, &1);
+    env.ledger().set_timestamp(4_000);
+    client.retire(&1, &entity, &None);
+
+    assert_eq!(
+        client.get_token_statuses(&vec![&env, 3, 2, 1]),
+        vec![
+            &env,
+            TokenStatus::Free,
+            TokenStatus::Locked(9_000),
+            TokenStatus::Retired(4_000)
+        ]
+    );
+
+    let mut too_many = Vec::new(&env);
+    for token_id in 0..=MAX_PAGE_SIZE {
+        too_many.push_back(token_id);
+    }
+    let result = client.try_get_token_statuses(&too_many);
+    assert_eq!(result, Err(Ok(ContractError::InvalidRange)));
+}
+
+#[test]
+fn test_amount_by_category() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    for token_id in 1..=3 {
+        asset.mint(&entity, &token_id);
+        asset.set_amount(&token_id, &100);
+    }
+
+    let compliance = Some(RetirementReason::Compliance);
+    client.retire_target_amount(&entity, &vec![&env, 1], &40, &None, &compliance);
+    client.retire_target_amount(&entity, &vec![&env, 1], &60, &None, &compliance);
+    client.retire_target_amount(
+        &entity,
+        &vec![&env, 2],
+        &30,
+        &None,
+        &Some(RetirementReason::Voluntary),
+    );
+    client.retire_target_amount(&entity, &vec![&env, 3], &10, &None, &None);
+
+    assert_eq!(
+        client.get_amount_by_category(&RetirementReason::Compliance),
+        100
+    );
+    assert_eq!(
+        client.get_amount_by_category(&RetirementReason::Voluntary),
+        30
+    );
+    assert_eq!(client.get_amount_by_category(&RetirementReason::Other), 10);
+    assert_eq!(client.get_total_amount_retired(), 140);
+}
+
+#[test]
+fn test_disabled_entity_index_is_not_written() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    assert!(client.is_index_by_entity());
+
+    let result = client.try_set_index_by_entity(&entity, &false);
+    assert_eq!(result, Err(Ok(ContractError::NotAuthorized)));
+    client.set_index_by_entity(&admin, &false);
+    assert!(!client.get_config().index_by_entity);
+
+    client.retire(&1, &entity, &None);
+    env.as_contract(&client.address, || {
+        assert!(!env
+            .storage()
+            .persistent()
+            .has(&DataKey::EntityIndex(entity.clone())));
+    });
+    assert!(client.get_retirement_record(&1).is_some());
+    assert!(client.get_retirements_by_entity(&entity).is_empty());
+    // has_retired does not depend on the entity index
+    assert!(client.has_retired(&entity));
+    assert!(!client.has_retired(&Address::generate(&env)));
+    assert_eq!(client.get_entity_count(), 0);
+    assert!(client.verify_index_consistency(&1));
+    client.bump_ttl(&1);
+
+    client.set_index_by_entity(&admin, &true);
+    asset.mint(&entity, &2);
+    client.retire(&2, &entity, &None);
+    assert_eq!(client.get_retirements_by_entity(&entity), vec![&env, 2]);
+}
+
+#[test]
+fn test_audit_entity_reports_orphans() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let other = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.mint(&other, &2);
+    client.retire(&1, &entity, &None);
+    client.retire(&2, &other, &None);
+    assert!(client.audit_entity(&entity).is_empty());
+
+    // Point the index at a token with no record and at another entity's token
+    env.as_contract(&client.address, || {
+        env.storage().persistent().set(
+            &DataKey::EntityIndex(entity.clone()),
+            &vec![&env, 1u32, 7, 2],
+        );
+    });
+    assert_eq!(client.audit_entity(&entity), vec![&env, 7, 2]);
+    assert!(client.audit_entity(&other).is_empty());
+}
+
+#[test]
+fn test_get_retirement_with_position() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let other = Address::generate(&env);
+    assert!(client.get_retirement_with_position(&1).is_none());
+
+    for token_id in [5, 2, 9] {
+        asset.mint(&entity, &token_id);
+        client.retire(&token_id, &entity, &None);
+    }
+    asset.mint(&other, &4);
+    client.retire(&4, &other, &None);
+
+    let (record, position, total) = client.get_retirement_with_position(&2).unwrap();
+    assert_eq!(record, client.get_retirement_record(&2).unwrap());
+    assert_eq!((position, total), (2, 3));
+    let (_, position, total) = client.get_retirement_with_position(&9).unwrap();
+    assert_eq!((position, total), (3, 3));
+    let (_, position, total) = client.get_retirement_with_position(&4).unwrap();
+    assert_eq!((position, total), (1, 1));
+}
+
+#[test]
+fn test_export_retirements_pages_through_all_records() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    assert_eq!(client.export_retirements(&0, &10), (Vec::new(&env), None));
+
+    // Out of order and spread over buckets, including one past the scan window
+    let token_ids = [120, 7, 3, 250, 101, 9_000];
+    for token_id in token_ids {
+        asset.mint(&entity, &token_id);
+        client.retire(&token_id, &entity, &None);
+    }
+    assert_eq!(client.get_max_retired_id(), Some(9_000));
+
+    // The gap before 9_000 spans more than one scan window
+    let (page, next_start) = client.export_retirements(&251, &2);
+    assert!(page.is_empty());
+    assert_eq!(
+        next_start,
+        Some((251 / ID_BUCKET_SIZE + MAX_EXPORT_BUCKETS) * ID_BUCKET_SIZE)
+    );
+
+    let mut exported = Vec::new(&env);
+    let mut start = Some(0);
+    while let Some(from) = start {
+        let (page, next_start) = client.export_retirements(&from, &2);
+        assert!(page.len() <= 2);
+        exported.append(&page);
+        start = next_start;
+    }
+
+    let mut expected = Vec::new(&env);
+    for token_id in [3, 7, 101, 120, 250, 9_000] {
+        expected.push_back(client.get_retirement_record(&token_id).unwrap());
+    }
+    assert_eq!(exported, expected);
+}
+
+#[test]
+fn test_get_retirements_by_ledger_range() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+
+    let sequences = [100, 900, 1_500, 2_200, 2_999];
+    for (token_id, sequence) in (1u32..).zip(sequences) {
+        env.ledger().set_sequence_number(sequence);
+        asset.mint(&entity, &token_id);
+        let record = client.retire(&token_id, &entity, &None);
+        assert_eq!(record.ledger_seq, sequence);
+    }
+
+    assert_eq!(
+        client.get_retirements_by_ledger_range(&900, &2_200),
+        vec![&env, 2, 3, 4]
+    );
+    assert_eq!(
+        client.get_retirements_by_ledger_range(&2_201, &2_999),
+        vec![&env, 5]
+    );
+    assert_eq!(
+        client.get_retirements_by_ledger_range(&3_000, &4_000).len(),
+        0
+    );
+
+    let result = client.try_get_retirements_by_ledger_range(&10, &5);
+    assert_eq!(result, Err(Ok(ContractError::InvalidRange)));
+    let result =
+        client.try_get_retirements_by_ledger_range(&0, &(LEDGER_BUCKET_SIZE * MAX_LEDGER_BUCKETS));
+    assert_eq!(result, Err(Ok(ContractError::InvalidRange)));
+}
+
+#[test]
+fn test_retirement_quota() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    for token_id in 1..=3 {
+        asset.mint(&entity, &token_id);
+        asset.set_amount(&token_id, &100);
+    }
+    env.ledger().set_timestamp(1_000);
+    client.set_quota(&admin, &entity, &150, &3_600);
+
+    // Within quota
+    client.retire_target_amount(&entity, &vec![&env, 1], &100, &None, &None);
+    env.ledger().set_timestamp(2_000);
+    client.retire_target_amount(&entity, &vec![&env, 2], &40, &None, &None);
+    assert_eq!(client.get_quota(&entity).unwrap().used, 140);
+
+    // Over quota
+    let result = client.try_retire_target_amount(&entity, &vec![&env, 2], &20, &None, &None);
+    assert_eq!(result, Err(Ok(ContractError::QuotaExceeded)));
+    assert_eq!(asset.amount_of(&2), 60);
+
+    // The window rolls: only the retirement from 1_000 has left it
+    env.ledger().set_timestamp(1_000 + 3_600);
+    let quota = client.get_quota(&entity).unwrap();
+    assert_eq!(quota.used, 40);
+    assert_eq!(quota.charges, vec![&env, (2_000, 40)]);
+    let result = client.try_retire_target_amount(&entity, &vec![&env, 2, 3], &111, &None, &None);
+    assert_eq!(result, Err(Ok(ContractError::QuotaExceeded)));
+    client.retire_target_amount(&entity, &vec![&env, 2, 3], &110, &None, &None);
+    let quota = client.get_quota(&entity).unwrap();
+    assert_eq!(quota.used, 150);
+    assert_eq!(quota.charges, vec![&env, (2_000, 40), (4_600, 110)]);
+
+    // Other entities are unrestricted, and a zero amount clears the quota
+    client.set_quota(&admin, &entity, &0, &3_600);
+    assert_eq!(client.get_quota(&entity), None);
+    let result = client.try_set_quota(&admin, &entity, &10, &0);
+    assert_eq!(result, Err(Ok(ContractError::InvalidAmount)));
+}
+
+#[test]
+fn test_retirement_quota_applies_to_plain_retire() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    for token_id in 1..=3 {
+        asset.mint(&entity, &token_id);
+        asset.set_amount(&token_id, &100);
+    }
+    env.ledger().set_timestamp(1_000);
+    client.set_quota(&admin, &entity, &150, &3_600);
+
+    client.retire(&1, &entity, &None);
+    assert_eq!(client.get_quota(&entity).unwrap().used, 100);
+
+    // Every whole-token path is charged, and nothing burns when over quota
+    let result = client.try_retire(&2, &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::QuotaExceeded)));
+    assert!(client
+        .batch_retire(&vec![&env, 2], &entity, &None)
+        .is_empty());
+    let result = client.try_retire_event_only(&2, &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::QuotaExceeded)));
+    assert!(!client.is_retired(&2));
+    assert_eq!(asset.owner_of(&2), Some(entity.clone()));
+
+    env.ledger().set_timestamp(1_000 + 3_600);
+    client.retire(&2, &entity, &None);
+    assert!(client.is_retired(&2));
+    assert_eq!(client.get_quota(&entity).unwrap().used, 100);
+}
+
+#[test]
+fn test_compute_retire_hash_matches_record() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    env.ledger().set_timestamp(1_700_000_000);
+    env.ledger().set_sequence_number(4_242);
+    asset.mint(&entity, &7);
+
+    let record = client.retire(&7, &entity, &None);
+
+    assert_eq!(
+        client.compute_retire_hash(&7, &entity, &record.timestamp, &record.ledger_seq),
+        record.tx_hash
+    );
+
+    // The preimage is the big-endian token id, timestamp and ledger sequence
+    let mut preimage = Bytes::from_array(&env, &7u32.to_be_bytes());
+    preimage.append(&Bytes::from_array(&env, &1_700_000_000u64.to_be_bytes()));
+    preimage.append(&Bytes::from_array(&env, &4_242u32.to_be_bytes()));
+    let expected: BytesN<32> = env.crypto().sha256(&preimage).into();
+    assert_eq!(record.tx_hash, expected);
+}
+
+#[test]
+fn test_rebuild_entity_index() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let other = Address::generate(&env);
+    for token_id in 1..=3 {
+        asset.mint(&entity, &token_id);
+        client.retire(&token_id, &entity, &None);
+    }
+    asset.mint(&other, &4);
+    client.retire(&4, &other, &None);
+
+    // Simulate an index that lost entries
+    env.as_contract(&client.address, || {
+        env.storage()
+            .persistent()
+            .set(&DataKey::EntityIndex(entity.clone()), &vec![&env, 2u32]);
+    });
+    assert_eq!(client.get_retirements_by_entity(&entity), vec![&env, 2]);
+
+    let result = client.try_rebuild_entity_index(&admin, &entity, &vec![&env, 1, 4]);
+    assert_eq!(result, Err(Ok(ContractError::TokenNotOwned)));
+    let result = client.try_rebuild_entity_index(&admin, &entity, &vec![&env, 1, 9]);
+    assert_eq!(result, Err(Ok(ContractError::InvalidTokenId)));
+    let result = client.try_rebuild_entity_index(&entity, &entity, &vec![&env, 1]);
+    assert_eq!(result, Err(Ok(ContractError::NotAuthorized)));
+
+    assert_eq!(
+        client.rebuild_entity_index(&admin, &entity, &vec![&env, 1, 2, 2, 3]),
+        3
+    );
+    assert_eq!(
+        client.get_retirements_by_entity(&entity),
+        vec![&env, 1, 2, 3]
+    );
+    assert_eq!(client.get_entity_count(), 2);
+}
+
+#[test]
+fn test_retire_and_lock_residue() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let time_lock = MockTimeLockClient::new(&env, &env.register(MockTimeLock, ()));
+    asset.mint(&entity, &1);
+    asset.set_amount(&1, &1_000);
+
+    let result = client.try_retire_and_lock_residue(&1, &entity, &400, &5_000);
+    assert_eq!(result, Err(Ok(ContractError::TimeLockNotConfigured)));
+
+    client.set_time_lock_contract(&admin, &Some(time_lock.address.clone()));
+    let result = client.try_retire_and_lock_residue(&1, &entity, &1_000, &5_000);
+    assert_eq!(result, Err(Ok(ContractError::InsufficientBalance)));
+
+    let (retired, locked) = client.retire_and_lock_residue(&1, &entity, &400, &5_000);
+    assert_eq!(retired + locked, 1_000);
+    assert_eq!(locked, 600);
+    assert_eq!(asset.amount_of(&1), 600);
+    assert_eq!(client.get_partial_retired_amount(&1), 400);
+    assert_eq!(client.get_total_amount_retired(), 400);
+    assert_eq!(time_lock.get_unlock_timestamp(&1), Some(5_000));
+    assert_eq!(client.get_token_status(&1), TokenStatus::Locked(5_000));
+}
+
+#[test]
+fn test_partial_retirement_checks_and_fee() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let time_lock = MockTimeLockClient::new(&env, &env.register(MockTimeLock, ()));
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    pool.init(&asset.address);
+    client.set_time_lock_contract(&admin, &Some(time_lock.address.clone()));
+    client.set_retirement_fee(&admin, &100);
+    client.set_buffer_pool(&admin, &Some(pool.address.clone()));
+    for token_id in 1..=3 {
+        asset.mint(&entity, &token_id);
+        asset.set_amount(&token_id, &1_000);
+    }
+
+    // Partial burns go through the same guards as whole-token retirements
+    env.as_contract(&client.address, || {
+        let pending = PendingRetirement {
+            token_id: 1,
+            retiring_entity: entity.clone(),
+            reserved_at: 0,
+        };
+        env.storage()
+            .temporary()
+            .set(&DataKey::PendingRetirement(1), &pending);
+    });
+    let result = client.try_retire_target_amount(&entity, &vec![&env, 1], &400, &None, &None);
+    assert_eq!(result, Err(Ok(ContractError::RetirementPending)));
+    let result = client.try_retire_and_lock_residue(&1, &entity, &400, &5_000);
+    assert_eq!(result, Err(Ok(ContractError::RetirementPending)));
+    assert_eq!(asset.amount_of(&1), 1_000);
+
+    // The fee is charged on top of the burned amount
+    let result = client.try_retire_and_lock_residue(&2, &entity, &995, &5_000);
+    assert_eq!(result, Err(Ok(ContractError::InsufficientBalance)));
+    let (retired, locked) = client.retire_and_lock_residue(&2, &entity, &400, &5_000);
+    assert_eq!((retired, locked), (400, 596));
+    assert_eq!(asset.amount_of(&2), 596);
+    assert_eq!(pool.donated(&2), 4);
+
+    client.retire_target_amount(&entity, &vec![&env, 3], &500, &None, &None);
+    let expected = PartialRetirementEvent {
+        token_id: 3,
+        retiring_entity: entity,
+        amount: 500,
+        timestamp: env.ledger().timestamp(),
+        fee: 5,
+    };
+    assert_eq!(
+        vec![&env, env.events().all().last().unwrap()],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                expected.topics(&env),
+                expected.data(&env)
+            )
+        ]
+    );
+    assert_eq!(asset.amount_of(&3), 495);
+    assert_eq!(pool.donated(&3), 5);
+    assert_eq!(client.get_partial_retired_amount(&3), 500);
+}
+
+#[test]
+fn test_owned_balance_uses_single_asset_call() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    for token_id in 1..=4 {
+        asset.mint(&entity, &token_id);
+        asset.set_amount(&token_id, &1_000);
+        asset.set_vintage(&token_id, &2020);
+    }
+    let reads = || {
+        (
+            asset.calls(&symbol_short!("info")),
+            asset.calls(&symbol_short!("owner_of")),
+            asset.calls(&symbol_short!("amount_of")),
+        )
+    };
+
+    // A plain retire reads the amount for the vintage total in one call
+    client.retire(&1, &entity, &None);
+    assert_eq!(reads(), (1, 0, 0));
+
+    // The fee shares that single read
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    pool.init(&asset.address);
+    client.set_retirement_fee(&admin, &100);
+    client.set_buffer_pool(&admin, &Some(pool.address));
+    client.retire(&2, &entity, &None);
+    assert_eq!(reads(), (2, 0, 0));
+    client.batch_retire(&vec![&env, 3], &entity, &None);
+    assert_eq!(reads(), (3, 0, 0));
+
+    // Assets without retire_info fall back to separate calls
+    asset.disable_retire_info();
+    client.retire(&4, &entity, &None);
+    assert_eq!(reads(), (3, 1, 1));
+
+    // Without a fee, quota, project or vintage the amount is not read
+    client.set_retirement_fee(&admin, &0);
+    asset.mint(&entity, &6);
+    client.retire(&6, &entity, &None);
+    assert_eq!(reads(), (3, 1, 1));
+
+    let other = Address::generate(&env);
+    asset.mint(&other, &7);
+    asset.set_amount(&7, &1_000);
+    asset.set_vintage(&7, &2020);
+    let result = client.try_retire(&7, &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::TokenNotOwned)));
+}
+
+/// Hash a sorted pair of nodes, as the tracker and merkle bridge do
+fn hash_pair(env: &Env, a: &BytesN<32>, b: &BytesN<32>) -> BytesN<32> {
+    let (left, right) = if a <= b { (a, b) } else { (b, a) };
+    let mut combined = Bytes::from_array(env, &left.to_array());
+    combined.append(&Bytes::from_array(env, &right.to_array()));
+    env.crypto().sha256(&combined).into()
+}
+
+#[test]
+fn test_retire_with_proof() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.mint(&entity, &2);
+
+    // Four-leaf tree pairing tokens with their serials
+    let serial =
+        |i: u32| String::from_str(&env, ["VCS-1", "VCS-2", "VCS-3", "VCS-4"][i as usize - 1]);
+    let leaves = [1, 2, 3, 4].map(|i| client.compute_proof_leaf(&i, &serial(i)));
+    let mut preimage = Bytes::from_array(&env, &1u32.to_be_bytes());
+    preimage.append(&Bytes::from_slice(&env, b"VCS-1"));
+    assert_eq!(
+        leaves[0].to_array(),
+        env.crypto().sha256(&preimage).to_array()
+    );
+    let left = hash_pair(&env, &leaves[0], &leaves[1]);
+    let right = hash_pair(&env, &leaves[2], &leaves[3]);
+    let root = hash_pair(&env, &left, &right);
+    let proof = vec![&env, leaves[1].clone(), right.clone()];
+
+    let result = client.try_retire_with_proof(&1, &entity, &serial(1), &proof, &None);
+    assert_eq!(result, Err(Ok(ContractError::InvalidProof)));
+
+    client.set_registry_root(&admin, &root);
+    let tampered = vec![&env, leaves[2].clone(), right.clone()];
+    let result = client.try_retire_with_proof(&1, &entity, &serial(1), &tampered, &None);
+    assert_eq!(result, Err(Ok(ContractError::InvalidProof)));
+    let result = client.try_retire_with_proof(&1, &entity, &serial(4), &proof, &None);
+    assert_eq!(result, Err(Ok(ContractError::InvalidProof)));
+    // A valid proof for token 1 does not retire another token
+    let result = client.try_retire_with_proof(&2, &entity, &serial(1), &proof, &None);
+    assert_eq!(result, Err(Ok(ContractError::InvalidProof)));
+    assert!(!client.is_retired(&1));
+    assert!(!client.is_retired(&2));
+
+    let record = client.retire_with_proof(&1, &entity, &serial(1), &proof, &None);
+    assert_eq!(record.retiring_entity, entity);
+    assert!(client.is_retired(&1));
+}
+
+#[test]
+fn test_retire_without_fee() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    pool.init(&asset.address);
+    asset.mint(&entity, &1);
+    asset.set_amount(&1, &1_000);
+
+    // A pool alone charges nothing while the fee stays at 0
+    client.set_buffer_pool(&admin, &Some(pool.address.clone()));
+    let record = client.retire(&1, &entity, &None);
+
+    assert_eq!(record.fee, 0);
+    assert_eq!(asset.received(&pool.address), 0);
+    assert_eq!(pool.donated(&1), 0);
+}
+
+#[test]
+fn test_retire_with_fee() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    pool.init(&asset.address);
+    asset.mint(&entity, &1);
+    asset.set_amount(&1, &1_000);
+
+    client.set_retirement_fee(&admin, &250);
+    // No pool configured yet, so no fee is taken
+    asset.mint(&entity, &2);
+    asset.set_amount(&2, &1_000);
+    assert_eq!(client.retire(&2, &entity, &None).fee, 0);
+
+    client.set_buffer_pool(&admin, &Some(pool.address.clone()));
+    let record = client.retire(&1, &entity, &None);
+
+    assert_eq!(record.fee, 25);
+    let expected = RetirementEvent {
+        token_id: 1,
+        retiring_entity: entity,
+        timestamp: record.timestamp,
+        tx_hash: record.tx_hash,
+        initiated_by: None,
+        reemitted: false,
+        fee: 25,
+        reason: None,
+    };
+    assert_eq!(
+        vec![&env, env.events().all().last().unwrap()],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                expected.topics(&env),
+                expected.data(&env)
+            )
+        ]
+    );
+
+    // The fee reaches the pool through its donate_amount
+    assert_eq!(asset.received(&pool.address), 25);
+    assert_eq!(pool.donated(&1), 25);
+    assert_eq!(client.get_retirement_record(&1).unwrap().fee, 25);
+
+    let result = client.try_set_retirement_fee(&admin, &10_001);
+    assert_eq!(result, Err(Ok(ContractError::InvalidAmount)));
+}
+
+#[test]
+fn test_fee_charged_on_every_retire_path() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let marketplace = Address::generate(&env);
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    pool.init(&asset.address);
+    client.set_retirement_fee(&admin, &100);
+    client.set_buffer_pool(&admin, &Some(pool.address.clone()));
+    for token_id in 1..=6 {
+        asset.mint(&entity, &token_id);
+        asset.set_amount(&token_id, &1_000);
+    }
+
+    client.batch_retire(&vec![&env, 1], &entity, &None);
+    client.retire_range(&2, &2, &entity, &None);
+    client.retire_via(&3, &entity, &marketplace, &None);
+    client.retire_event_only(&4, &entity, &None);
+    client.retire_with_memo_hash(&5, &entity, &BytesN::from_array(&env, &[7; 32]));
+    assert_eq!(
+        client.retire_target_amount(&entity, &vec![&env, 6], &990, &None, &None),
+        vec![&env, (6, 990)]
+    );
+
+    for token_id in 1..=6 {
+        assert_eq!(pool.donated(&token_id), 10);
+    }
+    assert_eq!(client.get_retirement_record(&3).unwrap().fee, 10);
+    assert_eq!(client.get_total_amount_retired(), 990);
+}
+
+#[test]
+fn test_retire_target_amount_with_fee_reaches_target() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    pool.init(&asset.address);
+    client.set_retirement_fee(&admin, &200);
+    client.set_buffer_pool(&admin, &Some(pool.address.clone()));
+    asset.mint(&entity, &1);
+    asset.set_amount(&1, &500);
+    asset.mint(&entity, &2);
+    asset.set_amount(&2, &500);
+    asset.mint(&entity, &3);
+    asset.set_amount(&3, &1_000);
+
+    // 1_000 credits net of fees is more than the first two tokens hold
+    let result = client.try_retire_target_amount(&entity, &vec![&env, 1, 2], &1_000, &None, &None);
+    assert_eq!(result, Err(Ok(ContractError::InsufficientBalance)));
+
+    let retired = client.retire_target_amount(&entity, &vec![&env, 1, 2, 3], &1_200, &None, &None);
+
+    // Whole tokens give up their fee, the partial burn pays it on top
+    assert_eq!(retired, vec![&env, (1, 490), (2, 490), (3, 220)]);
+    assert_eq!(client.get_total_amount_retired(), 1_200);
+    assert_eq!(pool.donated(&1), 10);
+    assert_eq!(pool.donated(&2), 10);
+    assert_eq!(pool.donated(&3), 4);
+    assert_eq!(asset.amount_of(&3), 776);
+    assert!(client.is_retired(&2));
+    assert!(!client.is_retired(&3));
+}
+
+#[test]
+fn test_failed_burn_in_batch_reverts_fee() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    pool.init(&asset.address);
+    client.set_retirement_fee(&admin, &100);
+    client.set_buffer_pool(&admin, &Some(pool.address.clone()));
+    for token_id in 1..=2 {
+        asset.mint(&entity, &token_id);
+        asset.set_amount(&token_id, &1_000);
+    }
+
+    // The fee is paid before the burn, so the batch fails instead of skipping
+    asset.set_failing_burn(&true);
+    let result = client.try_batch_retire(&vec![&env, 1, 2], &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::BurnFailed)));
+    let result = client.try_retire_range(&1, &2, &entity, &None);
+    assert_eq!(result, Err(Ok(ContractError::BurnFailed)));
+    assert_eq!(pool.donated(&1), 0);
+    assert_eq!(asset.received(&pool.address), 0);
+    assert_eq!(asset.amount_of(&1), 1_000);
+    assert!(!client.is_retired(&1));
+
+    asset.set_failing_burn(&false);
+    let records = client.batch_retire(&vec![&env, 1, 2], &entity, &None);
+    assert_eq!(records.len(), 2);
+    assert_eq!(pool.donated(&1), 10);
+    assert_eq!(pool.donated(&2), 10);
+}
+
+#[test]
+fn test_contract_retirer_pays_no_fee() {
+    let (env, admin, client, asset) = setup_test_env();
+    let pool = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    pool.init(&asset.address);
+    let holder = MockBufferPoolClient::new(&env, &env.register(MockBufferPool, ()));
+    client.set_retirement_fee(&admin, &100);
+    client.set_buffer_pool(&admin, &Some(pool.address.clone()));
+    client.set_trusted_retirer(&admin, &holder.address, &true);
+    asset.mint(&holder.address, &1);
+    asset.set_amount(&1, &1_000);
+
+    // The holder grants no authorization for a donation from its balance
+    env.set_auths(&[]);
+    let record = holder.retire_held(&client.address, &1);
+
+    assert_eq!(record.fee, 0);
+    assert_eq!(pool.donated(&1), 0);
+    assert!(client.is_retired(&1));
+}
+
+#[test]
+fn test_units_metadata() {
+    let env = Env::default();
+    env.mock_all_auths();
+    let admin = Address::generate(&env);
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let client = RetirementTrackerClient::new(&env, &env.register(RetirementTracker, ()));
+    let units = Symbol::new(&env, "tCO2e");
+
+    client.initialize_with_units(&admin, &asset.address, &units, &3);
+
+    assert_eq!(client.get_units(), (units.clone(), 3));
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.set_amount(&1, &2_500);
+    client.retire_target_amount(&entity, &vec![&env, 1], &1_500, &None, &None);
+    assert_eq!(
+        client.get_stats(),
+        RetirementStats {
+            total_retired: 0,
+            total_amount: 1_500,
+            entity_count: 0,
+            units,
+            decimals: 3,
+        }
+    );
+}
+
+#[test]
+fn test_units_default() {
+    let (env, _, client, _) = setup_test_env();
+    assert_eq!(client.get_units(), (Symbol::new(&env, "tCO2e"), 0));
+}
+
+#[test]
+fn test_error_codes_are_stable() {
+    assert_eq!(error_code(ContractError::NotAuthorized), 1);
+    assert_eq!(error_code(ContractError::TokenNotOwned), 2);
+    assert_eq!(error_code(ContractError::TokenAlreadyRetired), 3);
+    assert_eq!(error_code(ContractError::InvalidTokenId), 4);
+    assert_eq!(error_code(ContractError::BurnFailed), 5);
+    assert_eq!(error_code(ContractError::ContractNotInitialized), 6);
+    assert_eq!(error_code(ContractError::InvalidRange), 7);
+    assert_eq!(error_code(ContractError::InsufficientBalance), 8);
+    assert_eq!(error_code(ContractError::InvalidAmount), 9);
+    assert_eq!(error_code(ContractError::EnumerationUnsupported), 10);
+    assert_eq!(error_code(ContractError::QuotaExceeded), 11);
+    assert_eq!(error_code(ContractError::TimeLockNotConfigured), 12);
+    assert_eq!(error_code(ContractError::InvalidProof), 13);
+    assert_eq!(error_code(ContractError::ClaimExpired), 14);
+    assert_eq!(error_code(ContractError::InvalidDeadline), 15);
+    assert_eq!(error_code(ContractError::Paused), 16);
+    assert_eq!(error_code(ContractError::InvalidComplianceData), 17);
+    assert_eq!(error_code(ContractError::RetirementPending), 18);
+    assert_eq!(error_code(ContractError::BurnNotFinalized), 19);
+    assert_eq!(error_code(ContractError::TokenNotRetired), 20);
+}
+
+#[test]
+fn test_get_largest_indexes_ranks_entities_by_index_size() {
+    let (env, admin, client, asset) = setup_test_env();
+    let small = Address::generate(&env);
+    let large = Address::generate(&env);
+    let medium = Address::generate(&env);
+
+    let mut token_id = 1;
+    for (entity, count) in [(&small, 1), (&large, 4), (&medium, 2)] {
+        for _ in 0..count {
+            asset.mint(entity, &token_id);
+            client.retire(&token_id, entity, &None);
+            token_id += 1;
+        }
+    }
+
+    assert_eq!(
+        client.get_largest_indexes(&admin, &10),
+        vec![
+            &env,
+            (large.clone(), 4),
+            (medium.clone(), 2),
+            (small.clone(), 1)
+        ]
+    );
+    assert_eq!(
+        client.get_largest_indexes(&admin, &2),
+        vec![&env, (large.clone(), 4), (medium.clone(), 2)]
+    );
+
+    // Growing an index moves the entity up the ranking
+    for _ in 0..2 {
+        asset.mint(&small, &token_id);
+        client.retire(&token_id, &small, &None);
+        token_id += 1;
+    }
+    assert_eq!(
+        client.get_largest_indexes(&admin, &MAX_LARGEST_INDEXES),
+        vec![
+            &env,
+            (large.clone(), 4),
+            (small.clone(), 3),
+            (medium.clone(), 2)
+        ]
+    );
+
+    let outsider = Address::generate(&env);
+    assert_eq!(
+        client.try_get_largest_indexes(&outsider, &10),
+        Err(Ok(ContractError::NotAuthorized))
+    );
+}
+
+#[test]
+fn test_verify_index_consistency() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+
+    // Nothing retired yet, so there is nothing to disagree
+    assert!(client.verify_index_consistency(&1));
+
+    asset.mint(&entity, &1);
+    client.retire(&1, &entity, &None);
+    assert!(client.verify_index_consistency(&1));
+
+    // Dropping the token from the entity index is detected
+    client.rebuild_entity_index(&admin, &entity, &Vec::new(&env));
+    assert!(!client.verify_index_consistency(&1));
+
+    client.rebuild_entity_index(&admin, &entity, &vec![&env, 1]);
+    assert!(client.verify_index_consistency(&1));
+
+    // So is a token missing from any other index or total
+    let verra = symbol_short!("VERRA");
+    asset.mint(&entity, &2);
+    asset.set_project(&2, &10);
+    asset.set_vintage(&2, &2021);
+    asset.set_standard(&2, &verra);
+    asset.set_amount(&2, &100);
+    client.retire(&2, &entity, &None);
+    assert!(client.verify_index_consistency(&2));
+    let keys = [
+        DataKey::RetiredIds(2 / ID_BUCKET_SIZE),
+        DataKey::ProjectRetirements(10),
+        DataKey::ProjectAmount(10),
+        DataKey::VintageAmount(2021),
+        DataKey::RetiredCredits(2),
+        DataKey::StandardCount(verra),
+        DataKey::DayRetirements(env.ledger().timestamp() / SECONDS_PER_DAY),
+    ];
+    for key in keys {
+        let value: Val = env.as_contract(&client.address, || {
+            let value = env.storage().persistent().get(&key).unwrap();
+            env.storage().persistent().remove(&key);
+            value
+        });
+        assert!(!client.verify_index_consistency(&2));
+        env.as_contract(&client.address, || {
+            env.storage().persistent().set(&key, &value);
+        });
+        assert!(client.verify_index_consistency(&2));
+    }
+
+    // A voided token left in the retired-id index is a failed purge
+    client.void_retirement(&admin, &2);
+    assert!(client.verify_index_consistency(&2));
+    env.as_contract(&client.address, || {
+        let key = DataKey::RetiredIds(2 / ID_BUCKET_SIZE);
+        let mut ids: Vec<u32> = env.storage().persistent().get(&key).unwrap();
+        ids.push_back(2);
+        env.storage().persistent().set(&key, &ids);
+    });
+    assert!(!client.verify_index_consistency(&2));
+}
+
+#[test]
+fn test_void_retirement_purges_every_index() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    let verra = symbol_short!("VERRA");
+    for token_id in 1..=3 {
+        asset.mint(&entity, &token_id);
+        asset.set_project(&token_id, &10);
+        asset.set_vintage(&token_id, &2021);
+        asset.set_standard(&token_id, &verra);
+        asset.set_amount(&token_id, &100);
+    }
+
+    let serial = String::from_str(&env, "VCS-1234-2021-001");
+    let record = client.retire_compliance(&1, &entity, &beneficiary, &verra, &2021, &serial, &None);
+    let certificate_hash = BytesN::from_array(&env, &[7u8; 32]);
+    let certificate_id =
+        client.retire_certified(&2, &entity, &beneficiary, &None, &certificate_hash);
+    client.retire_with_memo_hash(&3, &entity, &BytesN::from_array(&env, &[9u8; 32]));
+
+    // Only the admin may void
+    let result = client.try_void_retirement(&entity, &1);
+    assert_eq!(result, Err(Ok(ContractError::NotAuthorized)));
+
+    assert_eq!(client.void_retirement(&admin, &1), record);
+    let expected = RetirementVoidedEvent {
+        token_id: 1,
+        retiring_entity: entity.clone(),
+        voided_by: admin.clone(),
+    };
+    assert_eq!(
+        vec![&env, env.events().all().last().unwrap()],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                expected.topics(&env),
+                expected.data(&env)
+            )
+        ]
+    );
+    client.void_retirement(&admin, &2);
+
+    for token_id in 1..=2 {
+        assert!(!client.is_retired(&token_id));
+        assert_eq!(client.get_retirement_record(&token_id), None);
+        assert!(client.verify_index_consistency(&token_id));
+    }
+    assert_eq!(client.get_compliance_details(&1), None);
+    assert_eq!(client.get_certificate(&2), None);
+    assert_eq!(client.get_retirement_by_certificate(&certificate_id), None);
+    assert_eq!(client.get_retirements_by_entity(&entity), vec![&env, 3]);
+    assert_eq!(
+        client.get_retirements_by_ledger_range(&0, &env.ledger().sequence()),
+        vec![&env, 3]
+    );
+    assert_eq!(client.export_retirements(&0, &10).0.len(), 1);
+    assert_eq!(client.get_retirements_by_project(&10), vec![&env, 3]);
+    assert_eq!(client.get_amount_by_project(&10), 100);
+    assert_eq!(client.get_retired_amount_by_vintage(&2021), 100);
+    assert_eq!(client.get_count_by_standard(&verra), 1);
+    assert_eq!(client.get_total_retired(), 1);
+    assert_eq!(
+        client.get_range_stats(&0, &env.ledger().timestamp()).count,
+        1
+    );
+
+    // Token 3 keeps its retirement and memo hash
+    assert!(client.is_retired(&3));
+    assert!(client.get_memo_hash(&3).is_some());
+    client.void_retirement(&admin, &3);
+    assert!(client.get_memo_hash(&3).is_none());
+    assert_eq!(client.get_total_retired(), 0);
+
+    let result = client.try_void_retirement(&admin, &3);
+    assert_eq!(result, Err(Ok(ContractError::TokenNotRetired)));
+}
+
+#[test]
+fn test_retire_event_only_emits_without_storing() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    let reason = Some(String::from_str(&env, "Scope 3 offset"));
+    let tx_hash = client.retire_event_only(&1, &entity, &reason);
+
+    let expected = RetirementEvent {
+        token_id: 1,
+        retiring_entity: entity.clone(),
+        timestamp: env.ledger().timestamp(),
+        tx_hash: tx_hash.clone(),
+        initiated_by: None,
+        reemitted: false,
+        fee: 0,
+        reason,
+    };
+    assert_eq!(
+        vec![&env, env.events().all().last().unwrap()],
+        vec![
+            &env,
+            (
+                client.address.clone(),
+                expected.topics(&env),
+                expected.data(&env)
+            )
+        ]
+    );
+    assert_eq!(
+        tx_hash,
+        client.compute_retire_hash(
+            &1,
+            &entity,
+            &env.ledger().timestamp(),
+            &env.ledger().sequence()
+        )
+    );
+
+    assert_eq!(asset.owner_of(&1), None);
+    assert!(!client.is_retired(&1));
+    assert_eq!(client.get_retirement_record(&1), None);
+    assert!(client.get_retirements_by_entity(&entity).is_empty());
+    assert_eq!(client.get_total_retired(), 0);
+
+    // The burned token cannot be retired again
+    assert_eq!(
+        client.try_retire_event_only(&1, &entity, &None),
+        Err(Ok(ContractError::BurnFailed))
+    );
+}
+
+#[test]
+fn test_get_config_reflects_setters() {
+    let (env, admin, client, asset) = setup_test_env();
+
+    assert_eq!(
+        client.get_config(),
+        TrackerConfig {
+            admin: admin.clone(),
+            carbon_asset_contract: asset.address.clone(),
+            fee_bps: 0,
+            buffer_pool: None,
+            time_lock_contract: None,
+            notify_contract: None,
+            registry_root: None,
+            units: Symbol::new(&env, DEFAULT_UNITS),
+            decimals: 0,
+            controller: None,
+            atomic_retire: false,
+            index_by_entity: true,
+        }
+    );
+
+    let buffer_pool = Address::generate(&env);
+    let time_lock = Address::generate(&env);
+    let listener = Address::generate(&env);
+    let root = BytesN::from_array(&env, &[7; 32]);
+    client.set_retirement_fee(&admin, &250);
+    client.set_buffer_pool(&admin, &Some(buffer_pool.clone()));
+    client.set_time_lock_contract(&admin, &Some(time_lock.clone()));
+    client.set_notify_contract(&admin, &Some(listener.clone()));
+    client.set_registry_root(&admin, &root);
+
+    let config = client.get_config();
+    assert_eq!(config.fee_bps, 250);
+    assert_eq!(config.buffer_pool, Some(buffer_pool));
+    assert_eq!(config.time_lock_contract, Some(time_lock));
+    assert_eq!(config.notify_contract, Some(listener));
+    assert_eq!(config.registry_root, Some(root));
+
+    let uninitialized = RetirementTrackerClient::new(&env, &env.register(RetirementTracker, ()));
+    assert_eq!(
+        uninitialized.try_get_config(),
+        Err(Ok(ContractError::ContractNotInitialized))
+    );
+}
+
+#[test]
+fn test_asset_contract_records_its_own_retirement() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let entity = Address::generate(&env);
+    let asset = SelfRetiringAssetClient::new(&env, &env.register(SelfRetiringAsset, ()));
+    let client = RetirementTrackerClient::new(&env, &env.register(RetirementTracker, ()));
+    client.initialize(&admin, &asset.address);
+    asset.mint(&entity, &1);
+    asset.mint(&entity, &2);
+
+    let record = asset.retire(&client.address, &1, &entity);
+    assert_eq!(record.retiring_entity, entity);
+    assert_eq!(record.initiated_by, Some(asset.address.clone()));
+    assert_eq!(record.standard, Some(symbol_short!("GOLD")));
+    assert_eq!(asset.owner_of(&1), None);
+    assert!(client.is_retired(&1));
+    assert_eq!(client.get_retirements_by_entity(&entity), vec![&env, 1]);
+
+    // Nobody but the configured asset contract can take the no-burn path
+    env.set_auths(&[]);
+    assert!(client
+        .try_record_asset_retirement(&2, &entity, &None, &None, &None)
+        .is_err());
+    assert!(!client.is_retired(&2));
+}
+
+#[test]
+fn test_get_range_stats() {
+    let (env, _, client, asset) = setup_test_env();
+    let alice = Address::generate(&env);
+    let bob = Address::generate(&env);
+    let carol = Address::generate(&env);
+    for (token_id, owner) in [(1, &alice), (2, &bob), (3, &alice), (4, &bob), (5, &carol)] {
+        asset.mint(owner, &token_id);
+    }
+    asset.set_amount(&3, &300);
+
+    // Day 0, before the window
+    env.ledger().set_timestamp(1_000);
+    client.retire(&1, &alice, &None);
+
+    // Day 1
+    env.ledger().set_timestamp(SECONDS_PER_DAY + 10);
+    client.retire(&2, &bob, &None);
+    client.retire_target_amount(&alice, &vec![&env, 3], &300, &None, &None);
+
+    // Day 3
+    env.ledger().set_timestamp(3 * SECONDS_PER_DAY + 5);
+    client.retire(&4, &bob, &None);
+
+    // Day 10, after the window
+    env.ledger().set_timestamp(10 * SECONDS_PER_DAY);
+    client.retire(&5, &carol, &None);
+
+    assert_eq!(
+        client.get_range_stats(&(SECONDS_PER_DAY + 1), &(3 * SECONDS_PER_DAY + 100)),
+        RangeStats {
+            count: 3,
+            total_amount: 300,
+            distinct_entities: 2,
+        }
+    );
+    assert_eq!(
+        client.get_range_stats(&0, &(10 * SECONDS_PER_DAY)),
+        RangeStats {
+            count: 5,
+            total_amount: 300,
+            distinct_entities: 3,
+        }
+    );
+
+    // Bob was last active on day 1, so he is new to a window starting on day 3
+    assert_eq!(
+        client.get_range_stats(&(3 * SECONDS_PER_DAY), &(3 * SECONDS_PER_DAY)),
+        RangeStats {
+            count: 1,
+            total_amount: 0,
+            distinct_entities: 1,
+        }
+    );
+    assert_eq!(
+        client.get_entity_day_count(&alice, &(SECONDS_PER_DAY + 10)),
+        1
+    );
+    assert_eq!(client.get_entity_day_count(&bob, &(2 * SECONDS_PER_DAY)), 0);
+
+    assert_eq!(
+        client.try_get_range_stats(&SECONDS_PER_DAY, &0),
+        Err(Ok(ContractError::InvalidRange))
+    );
+    assert_eq!(
+        client.try_get_range_stats(&0, &(MAX_STATS_DAYS * SECONDS_PER_DAY)),
+        Err(Ok(ContractError::InvalidRange))
+    );
+}
+
+#[test]
+fn test_count_by_standard() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let verra = symbol_short!("VERRA");
+    let gold = symbol_short!("GOLD");
+    for token_id in 1..=4 {
+        asset.mint(&entity, &token_id);
+    }
+    asset.set_standard(&1, &verra);
+    asset.set_standard(&2, &verra);
+    asset.set_standard(&3, &gold);
+
+    let record = client.retire(&1, &entity, &None);
+    assert_eq!(record.standard, Some(verra.clone()));
+    client.retire(&2, &entity, &None);
+    client.retire(&3, &entity, &None);
+
+    // Token 4 has no standard, so it is recorded without one
+    let record = client.retire(&4, &entity, &None);
+    assert_eq!(record.standard, None);
+
+    assert_eq!(client.get_count_by_standard(&verra), 2);
+    assert_eq!(client.get_count_by_standard(&gold), 1);
+    assert_eq!(client.get_count_by_standard(&symbol_short!("OTHER")), 0);
+}
+
+#[test]
+fn test_claim_confirmed_in_time() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    asset.mint(&entity, &2);
+    env.ledger().set_timestamp(1_000);
+
+    client.retire_with_claim(&1, &entity, &None, &2_000);
+    assert_eq!(client.get_claim_status(&1), ClaimStatus::Pending(2_000));
+
+    env.ledger().set_timestamp(2_000);
+    client.confirm_claim(&admin, &1);
+    assert_eq!(client.get_claim_status(&1), ClaimStatus::Confirmed);
+
+    // Confirmed claims stay confirmed after the deadline
+    env.ledger().set_timestamp(5_000);
+    assert_eq!(client.get_claim_status(&1), ClaimStatus::Confirmed);
+
+    // Plain retirements carry no claim
+    client.retire(&2, &entity, &None);
+    assert_eq!(client.get_claim_status(&2), ClaimStatus::NotRequired);
+    assert_eq!(
+        client.try_confirm_claim(&admin, &2),
+        Err(Ok(ContractError::InvalidTokenId))
+    );
+}
+
+#[test]
+fn test_claim_expires_without_confirmation() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+    env.ledger().set_timestamp(1_000);
+
+    assert_eq!(
+        client.try_retire_with_claim(&1, &entity, &None, &1_000),
+        Err(Ok(ContractError::InvalidDeadline))
+    );
+
+    client.retire_with_claim(&1, &entity, &None, &2_000);
+    env.ledger().set_timestamp(2_001);
+    assert_eq!(client.get_claim_status(&1), ClaimStatus::Expired);
+    assert_eq!(
+        client.try_confirm_claim(&admin, &1),
+        Err(Ok(ContractError::ClaimExpired))
+    );
+    assert!(client.is_retired(&1));
+}
+
+#[test]
+fn test_get_trusted_contracts() {
+    let (env, admin, client, asset) = setup_test_env();
+    assert_eq!(
+        client.get_trusted_contracts(),
+        vec![
+            &env,
+            (Symbol::new(&env, "carbon_asset"), asset.address.clone())
+        ]
+    );
+
+    let time_lock = Address::generate(&env);
+    let buffer_pool = Address::generate(&env);
+    let listener = Address::generate(&env);
+    client.set_time_lock_contract(&admin, &Some(time_lock.clone()));
+    client.set_buffer_pool(&admin, &Some(buffer_pool.clone()));
+    client.set_notify_contract(&admin, &Some(listener.clone()));
+
+    assert_eq!(
+        client.get_trusted_contracts(),
+        vec![
+            &env,
+            (Symbol::new(&env, "carbon_asset"), asset.address.clone()),
+            (Symbol::new(&env, "time_lock"), time_lock),
+            (Symbol::new(&env, "buffer_pool"), buffer_pool),
+            (Symbol::new(&env, "notify"), listener),
+        ]
+    );
+}
+
+#[test]
+fn test_batch_anchor_certificates_skips_existing() {
+    let (env, admin, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    let beneficiary = Address::generate(&env);
+    for token_id in 1..=3 {
+        asset.mint(&entity, &token_id);
+    }
+    client.retire(&1, &entity, &None);
+    let original = BytesN::from_array(&env, &[1; 32]);
+    client.retire_certified(&2, &entity, &beneficiary, &None, &original);
+    client.retire(&3, &entity, &None);
+
+    let hash = BytesN::from_array(&env, &[9; 32]);
+    let skipped = client.batch_anchor_certificates(
+        &admin,
+        &vec![
+            &env,
+            (1, hash.clone()),
+            (2, hash.clone()),
+            (3, hash.clone()),
+        ],
+    );
+    assert_eq!(skipped, vec![&env, 2]);
+
+    let certificate = client.get_certificate(&1).unwrap();
+    assert_eq!(certificate.certificate_hash, hash);
+    assert_eq!(certificate.beneficiary, entity);
+    assert_eq!(
+        client.get_retirement_by_certificate(&certificate.certificate_id),
+        client.get_retirement_record(&1)
+    );
+    assert!(client.get_certificate(&3).is_some());
+
+    // The existing certificate is write-once
+    let certificate = client.get_certificate(&2).unwrap();
+    assert_eq!(certificate.certificate_hash, original);
+    assert_eq!(certificate.beneficiary, beneficiary);
+
+    assert_eq!(
+        client.try_batch_anchor_certificates(&admin, &vec![&env, (4, hash)]),
+        Err(Ok(ContractError::InvalidTokenId))
+    );
+}
+
+#[contract]
+pub struct MockController;
+
+#[contractimpl]
+impl MockController {
+    pub fn set_paused(env: Env, paused: bool) {
+        env.storage()
+            .instance()
+            .set(&symbol_short!("paused"), &paused);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&symbol_short!("paused"))
+            .unwrap_or(false)
+    }
+}
+
+#[test]
+fn test_controller_pause_blocks_retire() {
+    let (env, admin, client, asset) = setup_test_env();
+    let controller = MockControllerClient::new(&env, &env.register(MockController, ()));
+    let entity = Address::generate(&env);
+    asset.mint(&entity, &1);
+
+    client.set_controller(&admin, &Some(controller.address.clone()));
+    assert_eq!(client.get_controller(), Some(controller.address.clone()));
+
+    controller.set_paused(&true);
+    assert_eq!(
+        client.try_retire(&1, &entity, &None),
+        Err(Ok(ContractError::Paused))
+    );
+    assert!(!client.can_retire(&1, &entity));
+
+    controller.set_paused(&false);
+    client.retire(&1, &entity, &None);
+    assert!(client.is_retired(&1));
+}
+
+#[test]
+fn test_retired_amount_by_vintage() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    for (token_id, vintage, amount) in
+        [(1, 2019, 100), (2, 2019, 250), (3, 2021, 40), (4, 2019, 80)]
+    {
+        asset.mint(&entity, &token_id);
+        asset.set_vintage(&token_id, &vintage);
+        asset.set_amount(&token_id, &amount);
+    }
+    assert_eq!(client.get_retired_amount_by_vintage(&2019), 0);
+
+    client.retire(&1, &entity, &None);
+    assert_eq!(client.get_retired_amount_by_vintage(&2019), 100);
+    client.retire(&2, &entity, &None);
+    assert_eq!(client.get_retired_amount_by_vintage(&2019), 350);
+    client.retire_target_amount(&entity, &vec![&env, 4], &30, &None, &None);
+    assert_eq!(client.get_retired_amount_by_vintage(&2019), 380);
+
+    client.retire(&3, &entity, &None);
+    assert_eq!(client.get_retired_amount_by_vintage(&2021), 40);
+    assert_eq!(client.get_retired_amount_by_vintage(&2019), 380);
+}
+
+#[test]
+fn test_retirements_by_project() {
+    let (env, _, client, asset) = setup_test_env();
+    let entity = Address::generate(&env);
+    for (token_id, project_id, amount) in [(1, 10, 100), (2, 20, 250), (3, 10, 50), (4, 20, 80)] {
+        asset.mint(&entity, &token_id);
+        asset.set_project(&token_id, &project_id);
+        asset.set_amount(&token_id, &amount);
+    }
+    asset.mint(&entity, &5);
+
+    for token_id in 1..=3 {
+        client.retire(&token_id, &entity, &None);
+    }
+    client.retire(&5, &entity, &None);
+    client.retire_target_amount(&entity, &vec![&env, 4], &30, &None, &None);
+
+    assert_eq!(
+        client.get_retirement_record(&1).unwrap().project_id,
+        Some(10)
+    );
+    assert_eq!(client.get_retirement_record(&5).unwrap().project_id, None);
+    assert_eq!(client.get_retirements_by_project(&10), vec![&env, 1, 3]);
+    assert_eq!(client.get_retirements_by_project(&20), vec![&env, 2]);
+    assert_eq!(client.get_amount_by_project(&10), 150);
+    // Token 4 is partially burned
+    assert_eq!(client.get_amount_by_project(&20), 280);
+    assert!(client.get_retirements_by_project(&30).is_empty());
+}
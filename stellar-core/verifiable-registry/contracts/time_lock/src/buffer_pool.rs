@@ -0,0 +1,12 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Subset of the buffer pool interface used for diverting locks
+#[allow(dead_code)]
+#[contractclient(name = "BufferPoolClient")]
+pub trait BufferPoolInterface {
+    /// Pull `token_id` from `from` into the pool's custody as a donation
+    fn donate(env: Env, from: Address, token_id: u32);
+
+    /// Pull `amount` of `token_id` from `from` into the pool as a donation
+    fn donate_amount(env: Env, from: Address, token_id: u32, amount: i128);
+}
@@ -0,0 +1,21 @@
+use soroban_sdk::{contractclient, Address, Env};
+
+/// Subset of the CarbonAsset interface used by the time lock
+#[allow(dead_code)]
+#[contractclient(name = "CarbonAssetClient")]
+pub trait CarbonAssetInterface {
+    /// Move `token_id` from `from` to `to` on behalf of `spender`
+    fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u32);
+
+    /// Move `token_id` held by `from` to `to`
+    fn transfer(env: Env, from: Address, to: Address, token_id: u32);
+
+    /// Current holder of `token_id`
+    fn owner_of(env: Env, token_id: u32) -> Address;
+
+    /// Quantity of credits (e.g. tonnes) represented by `token_id`
+    fn amount_of(env: Env, token_id: u32) -> i128;
+
+    /// Move `amount` of `token_id` held by `from` to `to`
+    fn transfer_amount(env: Env, from: Address, to: Address, token_id: u32, amount: i128);
+}
@@ -0,0 +1,9 @@
+use soroban_sdk::{contractclient, Env};
+
+/// Controller contract that pauses a whole deployment at once
+#[allow(dead_code)]
+#[contractclient(name = "ControllerClient")]
+pub trait ControllerInterface {
+    /// Whether the deployment is currently paused
+    fn is_paused(env: Env) -> bool;
+}
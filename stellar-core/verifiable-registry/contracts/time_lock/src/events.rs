@@ -0,0 +1,202 @@
+use crate::types::{ExpiryAction, PartialConfig};
+use soroban_sdk::{contractevent, Address, Env};
+
+/// Event emitted when a credit is locked
+#[contractevent]
+pub struct CreditLocked {
+    pub token_id: u32,
+    pub owner: Address,
+    pub unlock_timestamp: u64,
+    /// True when the CarbonAsset contract created the lock through
+    /// `lock_from_asset` rather than the owner through `lock_credit`
+    pub initiated_by_asset: bool,
+}
+
+/// Event emitted when a credit is released from its lock
+#[contractevent]
+pub struct CreditReleased {
+    pub token_id: u32,
+    pub owner: Address,
+    pub destination: Address,
+    pub forced: bool,
+    pub action: ExpiryAction,
+}
+
+/// Event emitted when the admin moves a lock to a new owner account
+#[contractevent]
+pub struct LockOwnerReassigned {
+    pub token_id: u32,
+    pub old_owner: Address,
+    pub new_owner: Address,
+}
+
+/// Event emitted when a keeper is paid for releasing credits
+#[contractevent]
+pub struct KeeperRewarded {
+    pub keeper: Address,
+    pub token: Address,
+    pub amount: i128,
+    /// Number of releases the reward was paid for
+    pub released: u32,
+}
+
+/// Event emitted when vested credits are claimed from a vesting lock
+#[contractevent]
+pub struct VestedClaimed {
+    pub token_id: u32,
+    pub owner: Address,
+    pub destination: Address,
+    pub amount: i128,
+}
+
+/// Event emitted when several configuration values are updated at once
+#[contractevent]
+pub struct ConfigUpdated {
+    pub admin: Address,
+    pub config: PartialConfig,
+}
+
+/// Event emitted when a lock is handed over to another TimeLock
+#[contractevent]
+pub struct LockMigrated {
+    pub token_id: u32,
+    pub owner: Address,
+    pub destination: Address,
+}
+
+/// Event emitted when a lock is received from the migration source
+#[contractevent]
+pub struct LockImported {
+    pub token_id: u32,
+    pub owner: Address,
+    pub source: Address,
+}
+
+/// Event emitted when the admin places or clears a dispute hold on a lock
+#[contractevent]
+pub struct DisputeHoldUpdated {
+    pub token_id: u32,
+    pub admin: Address,
+    pub on_hold: bool,
+}
+
+/// Event emitted when an expired credit is not released because it is held
+#[contractevent]
+pub struct ReleaseHeld {
+    pub token_id: u32,
+}
+
+pub fn emit_locked_event(
+    env: &Env,
+    token_id: u32,
+    owner: Address,
+    unlock_timestamp: u64,
+    initiated_by_asset: bool,
+) {
+    CreditLocked {
+        token_id,
+        owner,
+        unlock_timestamp,
+        initiated_by_asset,
+    }
+    .publish(env);
+}
+
+pub fn emit_released_event(
+    env: &Env,
+    token_id: u32,
+    owner: Address,
+    destination: Address,
+    forced: bool,
+    action: ExpiryAction,
+) {
+    CreditReleased {
+        token_id,
+        owner,
+        destination,
+        forced,
+        action,
+    }
+    .publish(env);
+}
+
+pub fn emit_owner_reassigned_event(
+    env: &Env,
+    token_id: u32,
+    old_owner: Address,
+    new_owner: Address,
+) {
+    LockOwnerReassigned {
+        token_id,
+        old_owner,
+        new_owner,
+    }
+    .publish(env);
+}
+
+pub fn emit_config_updated_event(env: &Env, admin: Address, config: PartialConfig) {
+    ConfigUpdated { admin, config }.publish(env);
+}
+
+pub fn emit_vested_claimed_event(
+    env: &Env,
+    token_id: u32,
+    owner: Address,
+    destination: Address,
+    amount: i128,
+) {
+    VestedClaimed {
+        token_id,
+        owner,
+        destination,
+        amount,
+    }
+    .publish(env);
+}
+
+pub fn emit_dispute_hold_event(env: &Env, token_id: u32, admin: Address, on_hold: bool) {
+    DisputeHoldUpdated {
+        token_id,
+        admin,
+        on_hold,
+    }
+    .publish(env);
+}
+
+pub fn emit_release_held_event(env: &Env, token_id: u32) {
+    ReleaseHeld { token_id }.publish(env);
+}
+
+pub fn emit_lock_migrated_event(env: &Env, token_id: u32, owner: Address, destination: Address) {
+    LockMigrated {
+        token_id,
+        owner,
+        destination,
+    }
+    .publish(env);
+}
+
+pub fn emit_lock_imported_event(env: &Env, token_id: u32, owner: Address, source: Address) {
+    LockImported {
+        token_id,
+        owner,
+        source,
+    }
+    .publish(env);
+}
+
+pub fn emit_keeper_rewarded_event(
+    env: &Env,
+    keeper: Address,
+    token: Address,
+    amount: i128,
+    released: u32,
+) {
+    KeeperRewarded {
+        keeper,
+        token,
+        amount,
+        released,
+    }
+    .publish(env);
+}
@@ -1,16 +1,1742 @@
 #![no_std]
 
-use soroban_sdk::{contract, contractimpl, Env};
+mod buffer_pool;
+mod carbon_asset;
+mod controller;
+mod events;
+mod retirement_tracker;
+mod storage;
+mod types;
+mod vintage_oracle;
+
+use buffer_pool::BufferPoolClient;
+use carbon_asset::CarbonAssetClient;
+use controller::ControllerClient;
+use events::{
+    emit_config_updated_event, emit_dispute_hold_event, emit_keeper_rewarded_event,
+    emit_lock_imported_event, emit_lock_migrated_event, emit_locked_event,
+    emit_owner_reassigned_event, emit_release_held_event, emit_released_event,
+    emit_vested_claimed_event,
+};
+use retirement_tracker::RetirementTrackerClient;
+use soroban_sdk::{
+    auth::{ContractContext, InvokerContractAuthEntry, SubContractInvocation},
+    contract, contractimpl,
+    token::TokenClient,
+    vec,
+    xdr::ToXdr,
+    Address, BytesN, Env, IntoVal, Symbol, Val, Vec,
+};
+pub use types::{
+    error_code, ApprovalAction, ExpiryAction, KeeperReward, LockRecord, PartialConfig,
+    TimeLockError, VestingLock,
+};
+use vintage_oracle::VintageOracleClient;
+
+/// Maximum number of records returned by a single paginated query
+pub const MAX_PAGE_SIZE: u32 = 100;
+
+/// Maximum number of entries a cursor-based batch call processes before
+/// returning a continuation cursor
+pub const BATCH_WORK_BUDGET: u32 = 25;
+
+/// Storage layout written by this version of the contract. Bump alongside a
+/// `migrate_storage` step whenever the layout changes.
+pub const STORAGE_VERSION: u32 = 1;
 
 /// Time lock contract for vintage locking mechanisms
-/// Implementation pending - see project roadmap
+///
+/// Credits are transferred into the contract's custody when locked and
+/// returned to their owner once the unlock timestamp has passed.
 #[contract]
 pub struct TimeLock;
 
 #[contractimpl]
 impl TimeLock {
-    /// Placeholder initialization function
+    /// Initialize the contract with an admin and the CarbonAsset contract address
+    pub fn initialize(
+        env: Env,
+        admin: Address,
+        carbon_asset_contract: Address,
+    ) -> Result<(), TimeLockError> {
+        if storage::has_admin(&env) {
+            return Err(TimeLockError::AlreadyInitialized);
+        }
+
+        admin.require_auth();
+        storage::set_admin(&env, &admin);
+        storage::set_carbon_asset_contract(&env, &carbon_asset_contract);
+        storage::set_storage_version(&env, STORAGE_VERSION);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Initialize the contract with an M-of-N approver set
+    ///
+    /// Once `threshold` is set, `force_release` and `force_release_by_owner`
+    /// only proceed after `threshold` approvers have called `approve_action`
+    /// for the matching action hash.
+    pub fn initialize_with_approvers(
+        env: Env,
+        admin: Address,
+        carbon_asset_contract: Address,
+        approvers: Vec<Address>,
+        threshold: u32,
+    ) -> Result<(), TimeLockError> {
+        if threshold == 0 || threshold > approvers.len() {
+            return Err(TimeLockError::InvalidThreshold);
+        }
+
+        Self::initialize(env.clone(), admin, carbon_asset_contract)?;
+        storage::set_approvers(&env, &approvers);
+        storage::set_approval_threshold(&env, threshold);
+
+        Ok(())
+    }
+
+    /// Record `approver`'s approval of the action identified by `action_hash`
+    ///
+    /// Repeated approvals from the same approver are counted once. Returns
+    /// the number of approvals now recorded for the action.
+    pub fn approve_action(
+        env: Env,
+        approver: Address,
+        action_hash: BytesN<32>,
+    ) -> Result<u32, TimeLockError> {
+        approver.require_auth();
+
+        if !storage::get_approvers(&env).contains(&approver) {
+            return Err(TimeLockError::NotApprover);
+        }
+
+        let mut approvals = storage::get_approvals(&env, &action_hash);
+        if !approvals.contains(&approver) {
+            approvals.push_back(approver);
+            storage::set_approvals(&env, &action_hash, &approvals);
+        }
+        storage::extend_instance_ttl(&env);
+
+        Ok(approvals.len())
+    }
+
+    /// Hash identifying `action` for `approve_action`
+    ///
+    /// Token actions are bound to the token's current lock: the hash covers
+    /// the lock's `locked_at`, so approvals gathered for one lock never carry
+    /// over to a later lock of the same token. Approvals for a lock are also
+    /// cleared when it is removed. Returns the hash for the current lock, or
+    /// for no lock when the token is not locked.
+    pub fn get_action_hash(env: Env, action: ApprovalAction) -> BytesN<32> {
+        let lock_instance = match &action {
+            ApprovalAction::ForceRelease(token_id)
+            | ApprovalAction::VintageGatedRelease(token_id) => {
+                storage::get_lock_record(&env, *token_id).map(|record| record.locked_at)
+            }
+            ApprovalAction::ForceReleaseByOwner(_) => None,
+        };
+        env.crypto()
+            .sha256(&(action, lock_instance).to_xdr(&env))
+            .into()
+    }
+
+    /// Get the number of approvals recorded for an action hash
+    pub fn get_approval_count(env: Env, action_hash: BytesN<32>) -> u32 {
+        storage::get_approvals(&env, &action_hash).len()
+    }
+
+    /// Get the approver set and threshold (0 when approvals are not required)
+    pub fn get_approval_config(env: Env) -> (Vec<Address>, u32) {
+        (
+            storage::get_approvers(&env),
+            storage::get_approval_threshold(&env),
+        )
+    }
+
+    /// Lock a credit until `unlock_timestamp`
+    ///
+    /// The token is moved from `owner` into the contract via `transfer_from`,
+    /// with `caller` acting as the spender. `caller` must be the token's
+    /// current owner; the CarbonAsset contract locks through
+    /// `lock_from_asset` instead. A token that does not exist or is not held
+    /// by `owner` fails with `TokenNotHeld`. On release the credit is sent to
+    /// `release_to` when set, otherwise back to `owner`.
+    ///
+    /// With vintage validation enabled, `unlock_timestamp` must not precede
+    /// the oracle's unlock for the token; the oracle value is stored on the
+    /// record as `vintage_unlock`. A zero oracle response is treated as an
+    /// unknown vintage and rejects the lock.
+    ///
+    /// `on_expiry` selects what happens once the lock expires and defaults to
+    /// `ExpiryAction::ReturnToOwner`. Forced releases always return the credit.
+    ///
+    /// Once `owner` holds `max_locks_per_owner` locks, new ones are rejected.
+    pub fn lock_credit(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        token_id: u32,
+        unlock_timestamp: u64,
+        release_to: Option<Address>,
+        on_expiry: Option<ExpiryAction>,
+    ) -> Result<LockRecord, TimeLockError> {
+        caller.require_auth();
+
+        Self::create_lock(
+            &env,
+            caller,
+            owner,
+            token_id,
+            unlock_timestamp,
+            release_to,
+            on_expiry,
+        )
+    }
+
+    /// Lock a credit the CarbonAsset contract has already transferred to
+    /// this contract, e.g. on an auto-locking mint (CarbonAsset only)
+    ///
+    /// Soroban does not let a contract call back into one that is still
+    /// executing, so the asset passes the token's `owner` and `amount` in and
+    /// this path never calls the asset. The asset must move the token into
+    /// this contract's custody before calling. Otherwise behaves like
+    /// `lock_credit`, and the `CreditLocked` event is flagged as
+    /// asset-initiated.
+    pub fn lock_from_asset(
+        env: Env,
+        owner: Address,
+        token_id: u32,
+        amount: i128,
+        unlock_timestamp: u64,
+        release_to: Option<Address>,
+        on_expiry: Option<ExpiryAction>,
+    ) -> Result<LockRecord, TimeLockError> {
+        storage::get_carbon_asset_contract(&env)?.require_auth();
+
+        let vintage_unlock = Self::check_new_lock(&env, token_id, unlock_timestamp)?;
+        let record = LockRecord {
+            token_id,
+            owner,
+            locked_at: env.ledger().timestamp(),
+            unlock_timestamp,
+            release_to,
+            vintage_unlock,
+            on_expiry: on_expiry.unwrap_or(ExpiryAction::ReturnToOwner),
+            amount,
+        };
+        Self::record_lock(&env, &record)?;
+
+        emit_locked_event(&env, token_id, record.owner.clone(), unlock_timestamp, true);
+        Ok(record)
+    }
+
+    /// Lock a credit that vests linearly over `duration` seconds
+    ///
+    /// Works like `lock_credit` with an unlock at `now + duration`, but the
+    /// owner can claim the vested share through `claim_vested` from
+    /// `cliff_timestamp` onwards. The cliff must fall within the vesting period.
+    /// With `ExpiryAction::DivertToBuffer` every claim goes to the buffer
+    /// pool as a donated amount, which the pool holds apart from its TVL
+    /// until governance withdraws it; the final release donates the token
+    /// itself, which enters custody and counts towards TVL.
+    pub fn lock_vesting(
+        env: Env,
+        caller: Address,
+        owner: Address,
+        token_id: u32,
+        cliff_timestamp: u64,
+        duration: u64,
+        on_expiry: Option<ExpiryAction>,
+    ) -> Result<LockRecord, TimeLockError> {
+        caller.require_auth();
+
+        let now = env.ledger().timestamp();
+        let unlock_timestamp = now.saturating_add(duration);
+        if cliff_timestamp < now || cliff_timestamp > unlock_timestamp {
+            return Err(TimeLockError::InvalidUnlockTime);
+        }
+
+        let record = Self::create_lock(
+            &env,
+            caller,
+            owner,
+            token_id,
+            unlock_timestamp,
+            None,
+            on_expiry,
+        )?;
+        storage::set_vesting(
+            &env,
+            token_id,
+            &VestingLock {
+                cliff_timestamp,
+                duration,
+                claimed: 0,
+            },
+        );
+
+        Ok(record)
+    }
+
+    /// Send the vested but unclaimed credits of a vesting lock to its owner,
+    /// or donate them to the buffer pool through its `donate_amount` for a
+    /// `DivertToBuffer` lock
+    ///
+    /// Anyone may trigger a claim. Once the vesting period has ended the lock
+    /// is released in full through its expiry action, sending the token with
+    /// its remaining credits.
+    ///
+    /// # Errors
+    /// * `TimeLockError::ExpiryTargetMissing` - A diverting lock has no buffer pool configured
+    ///
+    /// # Returns
+    /// The amount of credits sent; zero before the cliff, when nothing new
+    /// has vested or while the lock is under a dispute hold
+    pub fn claim_vested(env: Env, token_id: u32) -> Result<i128, TimeLockError> {
+        let mut record =
+            storage::get_lock_record(&env, token_id).ok_or(TimeLockError::NotLocked)?;
+        let mut vesting = storage::get_vesting(&env, token_id).ok_or(TimeLockError::NotVesting)?;
+        Self::require_not_paused(&env)?;
+
+        if storage::is_on_hold(&env, token_id) {
+            emit_release_held_event(&env, token_id);
+            return Ok(0);
+        }
+
+        if Self::is_expired(&env, &record) {
+            let remaining = record.amount;
+            Self::release(&env, &record, false)?;
+            return Ok(remaining);
+        }
+
+        let claimable = Self::claimable_vested(&env, &record, &vesting);
+        if claimable <= 0 {
+            return Ok(0);
+        }
+        // Resolve the target before touching state so a missing one is a clean error
+        let destination = Self::claim_target(&env, &record)?;
+
+        vesting.claimed += claimable;
+        storage::set_vesting(&env, token_id, &vesting);
+
+        record.amount -= claimable;
+        storage::set_lock_record(&env, &record);
+        storage::set_total_amount_locked(&env, storage::get_total_amount_locked(&env) - claimable);
+        storage::extend_instance_ttl(&env);
+
+        if record.on_expiry == ExpiryAction::DivertToBuffer {
+            Self::donate_amount_to_pool(&env, &destination, token_id, claimable)?;
+        } else {
+            let carbon_asset = storage::get_carbon_asset_contract(&env)?;
+            CarbonAssetClient::new(&env, &carbon_asset).transfer_amount(
+                &env.current_contract_address(),
+                &destination,
+                &token_id,
+                &claimable,
+            );
+        }
+
+        emit_vested_claimed_event(&env, token_id, record.owner, destination, claimable);
+
+        Ok(claimable)
+    }
+
+    /// Preview what releasing a lock would do, without changing state
+    ///
+    /// Returns the destination, the amount of credits that would be sent and
+    /// whether the release can happen now. For a vesting lock that has not
+    /// ended, this is the currently claimable share sent by `claim_vested`.
+    /// When the lock's expiry target is not configured the release would
+    /// fail, so it is reported as ineligible with the lock's own destination.
+    /// A lock under a dispute hold is always reported as ineligible.
+    pub fn preview_release(env: Env, token_id: u32) -> Option<(Address, i128, bool)> {
+        let record = storage::get_lock_record(&env, token_id)?;
+
+        if storage::is_on_hold(&env, token_id) {
+            return Some((record.release_destination(), record.amount, false));
+        }
+
+        if !Self::is_expired(&env, &record) {
+            return Some(match storage::get_vesting(&env, token_id) {
+                Some(vesting) => {
+                    let claimable = Self::claimable_vested(&env, &record, &vesting).max(0);
+                    match Self::claim_target(&env, &record) {
+                        Ok(destination) => (destination, claimable, claimable > 0),
+                        Err(_) => (record.release_destination(), claimable, false),
+                    }
+                }
+                None => (record.release_destination(), record.amount, false),
+            });
+        }
+
+        Some(
+            match Self::release_target(&env, &record, record.on_expiry) {
+                Ok(destination) => (destination, record.amount, true),
+                Err(_) => (record.release_destination(), record.amount, false),
+            },
+        )
+    }
+
+    /// Get the vesting schedule of a token's lock, if it has one
+    pub fn get_vesting(env: Env, token_id: u32) -> Option<VestingLock> {
+        storage::get_vesting(&env, token_id)
+    }
+
+    /// Release a credit if the lock has expired, applying its expiry action
+    ///
+    /// Anyone may call this. Returns `false` if the lock is still active or
+    /// under a dispute hold.
+    pub fn release_if_eligible(env: Env, token_id: u32) -> Result<bool, TimeLockError> {
+        let record = storage::get_lock_record(&env, token_id).ok_or(TimeLockError::NotLocked)?;
+
+        if !Self::is_expired(&env, &record) || Self::skip_held(&env, token_id) {
+            return Ok(false);
+        }
+
+        Self::release(&env, &record, false)?;
+        Ok(true)
+    }
+
+    /// Release every expired credit in `token_ids`, skipping the rest and
+    /// any under a dispute hold
+    ///
+    /// Processes at most `BATCH_WORK_BUDGET` entries per call, starting at
+    /// index `cursor` (0 starts fresh). Returns the cursor to resume from,
+    /// or 0 once the whole list has been processed, along with the token IDs
+    /// released by this call.
+    pub fn batch_release(
+        env: Env,
+        token_ids: Vec<u32>,
+        cursor: u32,
+    ) -> Result<(u32, Vec<u32>), TimeLockError> {
+        let (next_cursor, released) = Self::release_batch(&env, &token_ids, cursor)?;
+        Ok((next_cursor, Self::token_ids_of(&env, &released)))
+    }
+
+    /// `batch_release` on behalf of a keeper, who is paid the configured
+    /// keeper reward for every credit released
+    ///
+    /// The reward is paid from this contract's balance of the reward token
+    /// after all releases have completed; if the balance runs short the
+    /// keeper receives what is left. Releases of the keeper's own locks, and
+    /// of locks that ran for less than the reward's `min_lock_duration`, earn
+    /// nothing, so an owner cannot farm the reward by cycling short locks.
+    /// Without a keeper reward this behaves exactly like `batch_release`.
+    ///
+    /// # Errors
+    /// * `TimeLockError::KeeperPayoutActive` - Called while a payout is in progress
+    pub fn keeper_release(
+        env: Env,
+        keeper: Address,
+        token_ids: Vec<u32>,
+        cursor: u32,
+    ) -> Result<(u32, Vec<u32>), TimeLockError> {
+        keeper.require_auth();
+
+        if storage::is_keeper_payout_active(&env) {
+            return Err(TimeLockError::KeeperPayoutActive);
+        }
+
+        let (next_cursor, released) = Self::release_batch(&env, &token_ids, cursor)?;
+
+        if let Some(reward) = storage::get_keeper_reward(&env) {
+            let rewarded = released
+                .iter()
+                .filter(|record| {
+                    record.owner != keeper
+                        && record.unlock_timestamp - record.locked_at >= reward.min_lock_duration
+                })
+                .count() as u32;
+            if rewarded > 0 {
+                let token = TokenClient::new(&env, &reward.token);
+                let owed = reward.amount.saturating_mul(rewarded as i128);
+                let amount = owed.min(token.balance(&env.current_contract_address()));
+
+                if amount > 0 {
+                    storage::set_keeper_payout_active(&env, true);
+                    token.transfer(&env.current_contract_address(), &keeper, &amount);
+                    storage::set_keeper_payout_active(&env, false);
+
+                    emit_keeper_rewarded_event(&env, keeper, reward.token, amount, rewarded);
+                }
+            }
+        }
+
+        Ok((next_cursor, Self::token_ids_of(&env, &released)))
+    }
+
+    /// Count how many of `token_ids` `batch_release` would release now
+    ///
+    /// A read-only pre-flight for keepers: a token counts when it is locked,
+    /// expired, not under a dispute hold and its expiry target is configured.
+    /// Returns 0 while the shared controller is paused.
+    ///
+    /// # Errors
+    /// * `TimeLockError::InvalidRange` - More than `MAX_PAGE_SIZE` ids supplied
+    pub fn count_releasable(env: Env, token_ids: Vec<u32>) -> Result<u32, TimeLockError> {
+        if token_ids.len() > MAX_PAGE_SIZE {
+            return Err(TimeLockError::InvalidRange);
+        }
+        if Self::require_not_paused(&env).is_err() {
+            return Ok(0);
+        }
+
+        let mut count = 0;
+        for token_id in token_ids.iter() {
+            if let Some(record) = storage::get_lock_record(&env, token_id) {
+                if Self::is_expired(&env, &record)
+                    && !storage::is_on_hold(&env, token_id)
+                    && Self::release_target(&env, &record, record.on_expiry).is_ok()
+                {
+                    count += 1;
+                }
+            }
+        }
+
+        Ok(count)
+    }
+
+    /// Release the expired credits in `token_ids` that are locked for `owner`
+    ///
+    /// Tokens locked for other owners, still within their lock period or
+    /// under a dispute hold are skipped. Returns the token IDs that were
+    /// released.
+    pub fn release_owned(
+        env: Env,
+        owner: Address,
+        token_ids: Vec<u32>,
+    ) -> Result<Vec<u32>, TimeLockError> {
+        owner.require_auth();
+
+        let mut released = Vec::new(&env);
+
+        for token_id in token_ids.iter() {
+            if let Some(record) = storage::get_lock_record(&env, token_id) {
+                if record.owner == owner
+                    && Self::is_expired(&env, &record)
+                    && !Self::skip_held(&env, token_id)
+                {
+                    Self::release(&env, &record, false)?;
+                    released.push_back(token_id);
+                }
+            }
+        }
+
+        Ok(released)
+    }
+
+    /// Release a credit regardless of its unlock timestamp (admin only)
+    ///
+    /// Overrides a dispute hold, which is cleared with the lock.
+    ///
+    /// Requires approvals for `ApprovalAction::ForceRelease(token_id)` when
+    /// an approval threshold is configured; they are consumed with the lock.
+    pub fn force_release(env: Env, admin: Address, token_id: u32) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+        Self::require_approvals(&env, ApprovalAction::ForceRelease(token_id))?;
+
+        let record = storage::get_lock_record(&env, token_id).ok_or(TimeLockError::NotLocked)?;
+        Self::release(&env, &record, true)?;
+
+        Ok(())
+    }
+
+    /// Release a credit before its unlock timestamp, but not before its
+    /// vintage has matured (admin only)
+    ///
+    /// Behaves like `force_release`, but fails with `VintageNotMatured`
+    /// unless `is_vintage_matured` holds for the token. `force_release`
+    /// remains the unconditional emergency path.
+    ///
+    /// Requires approvals for
+    /// `ApprovalAction::VintageGatedRelease(token_id)` when an
+    /// approval threshold is configured, so approving this release does not
+    /// also approve an unconditional `force_release`.
+    pub fn force_release_respecting_vintage(
+        env: Env,
+        admin: Address,
+        token_id: u32,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+        Self::require_approvals(&env, ApprovalAction::VintageGatedRelease(token_id))?;
+
+        let record = storage::get_lock_record(&env, token_id).ok_or(TimeLockError::NotLocked)?;
+        if !Self::is_vintage_matured(env.clone(), token_id) {
+            return Err(TimeLockError::VintageNotMatured);
+        }
+        Self::release(&env, &record, true)?;
+
+        Ok(())
+    }
+
+    /// Place a dispute hold on a locked credit (admin only)
+    ///
+    /// While held, the credit is not released by `release_if_eligible`,
+    /// `batch_release`, `release_owned` or `claim_vested`, even after expiry;
+    /// those paths emit a `ReleaseHeld` event instead. Admin force releases
+    /// still override the hold.
+    pub fn place_dispute_hold(
+        env: Env,
+        caller: Address,
+        token_id: u32,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &caller)?;
+
+        storage::get_lock_record(&env, token_id).ok_or(TimeLockError::NotLocked)?;
+
+        storage::set_dispute_hold(&env, token_id);
+        emit_dispute_hold_event(&env, token_id, caller, true);
+        Ok(())
+    }
+
+    /// Clear a dispute hold so the credit can be released again (admin only)
+    pub fn clear_dispute_hold(
+        env: Env,
+        caller: Address,
+        token_id: u32,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &caller)?;
+
+        storage::remove_dispute_hold(&env, token_id);
+        emit_dispute_hold_event(&env, token_id, caller, false);
+        Ok(())
+    }
+
+    /// Check whether a credit is under a dispute hold
+    pub fn is_on_hold(env: Env, token_id: u32) -> bool {
+        storage::is_on_hold(&env, token_id)
+    }
+
+    /// Force-release every credit locked for `owner` (admin only)
+    ///
+    /// Releases at most `BATCH_WORK_BUDGET` locks per call. Released tokens
+    /// leave the owner's lock index, so the index itself is the resumption
+    /// cursor: call again while the returned remaining count is non-zero.
+    /// Returns `(released, remaining)`.
+    pub fn force_release_by_owner(
+        env: Env,
+        admin: Address,
+        owner: Address,
+    ) -> Result<(u32, u32), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+        let action_hash =
+            Self::require_approvals(&env, ApprovalAction::ForceReleaseByOwner(owner.clone()))?;
+
+        let owner_locks = storage::get_owner_locks(&env, &owner);
+        let end = BATCH_WORK_BUDGET.min(owner_locks.len());
+
+        let mut released = 0;
+        for i in 0..end {
+            if let Some(record) = storage::get_lock_record(&env, owner_locks.get_unchecked(i)) {
+                Self::release(&env, &record, true)?;
+                released += 1;
+            }
+        }
+
+        // Approvals cover the whole unwind, so keep them until it completes
+        let remaining = owner_locks.len() - end;
+        if remaining == 0 {
+            storage::remove_approvals(&env, &action_hash);
+        }
+
+        Ok((released, remaining))
+    }
+
+    /// Hand a lock over to another TimeLock, e.g. an upgraded version (admin only)
+    ///
+    /// The token is transferred to `destination`, which receives the lock
+    /// unchanged (unlock timestamp, release target, expiry action, vesting
+    /// and dispute hold) through its `import_lock`; the local record is
+    /// removed. `destination` must name this contract as its migration
+    /// source. Tokens that are not locked fail with `NotLocked`.
+    pub fn migrate_lock(
+        env: Env,
+        admin: Address,
+        token_id: u32,
+        destination: Address,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+        Self::migrate(&env, token_id, &destination)
+    }
+
+    /// Migrate several locks to `destination` (admin only)
+    ///
+    /// Processes at most `BATCH_WORK_BUDGET` entries per call, starting at
+    /// index `cursor` (0 starts fresh). Returns the cursor to resume from,
+    /// or 0 once the whole list has been processed. Any token that is not
+    /// locked fails the whole call with `NotLocked`.
+    pub fn migrate_locks(
+        env: Env,
+        admin: Address,
+        token_ids: Vec<u32>,
+        destination: Address,
+        cursor: u32,
+    ) -> Result<u32, TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        let end = cursor
+            .saturating_add(BATCH_WORK_BUDGET)
+            .min(token_ids.len());
+        for i in cursor..end {
+            Self::migrate(&env, token_ids.get_unchecked(i), &destination)?;
+        }
+
+        Ok(if end < token_ids.len() { end } else { 0 })
+    }
+
+    /// Receive a lock handed over by `migrate_lock` on the migration source
+    ///
+    /// Only the TimeLock configured with `set_migration_source` may call
+    /// this, after transferring the token to this contract. Per-owner lock
+    /// caps are not applied, so existing locks are never stranded.
+    pub fn import_lock(
+        env: Env,
+        source: Address,
+        record: LockRecord,
+        vesting: Option<VestingLock>,
+        on_hold: bool,
+    ) -> Result<(), TimeLockError> {
+        if storage::get_migration_source(&env) != Some(source.clone()) {
+            return Err(TimeLockError::NotMigrationSource);
+        }
+        source.require_auth();
+        Self::require_not_paused(&env)?;
+
+        let token_id = record.token_id;
+        if storage::has_lock_record(&env, token_id) {
+            return Err(TimeLockError::AlreadyLocked);
+        }
+
+        let carbon_asset = storage::get_carbon_asset_contract(&env)?;
+        if CarbonAssetClient::new(&env, &carbon_asset).owner_of(&token_id)
+            != env.current_contract_address()
+        {
+            return Err(TimeLockError::CustodyMismatch);
+        }
+
+        storage::set_lock_record(&env, &record);
+        storage::push_lock_id(&env, token_id);
+
+        let mut owner_locks = storage::get_owner_locks(&env, &record.owner);
+        owner_locks.push_back(token_id);
+        storage::set_owner_locks(&env, &record.owner, &owner_locks);
+
+        if let Some(vesting) = vesting {
+            storage::set_vesting(&env, token_id, &vesting);
+        }
+        if on_hold {
+            storage::set_dispute_hold(&env, token_id);
+        }
+        storage::set_total_amount_locked(
+            &env,
+            storage::get_total_amount_locked(&env) + record.amount,
+        );
+        storage::extend_instance_ttl(&env);
+
+        emit_lock_imported_event(&env, token_id, record.owner, source);
+        Ok(())
+    }
+
+    /// Set or clear the TimeLock allowed to hand over locks (admin only)
+    pub fn set_migration_source(
+        env: Env,
+        admin: Address,
+        source: Option<Address>,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_migration_source(&env, &source);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the TimeLock allowed to hand over locks, if configured
+    pub fn get_migration_source(env: Env) -> Option<Address> {
+        storage::get_migration_source(&env)
+    }
+
+    /// Move a lock to a new owner account, e.g. after key rotation (admin only)
+    ///
+    /// Requires the current owner's authorization as well. The credit is
+    /// released to `new_owner` unless the lock has an explicit `release_to`,
+    /// which is left unchanged. The per-owner lock cap applies to `new_owner`.
+    pub fn reassign_lock_owner(
+        env: Env,
+        admin: Address,
+        token_id: u32,
+        new_owner: Address,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+        Self::require_not_paused(&env)?;
+
+        let mut record =
+            storage::get_lock_record(&env, token_id).ok_or(TimeLockError::NotLocked)?;
+        let old_owner = record.owner.clone();
+        old_owner.require_auth();
+
+        let mut old_locks = storage::get_owner_locks(&env, &old_owner);
+        if let Some(index) = old_locks.first_index_of(token_id) {
+            old_locks.remove(index);
+            storage::set_owner_locks(&env, &old_owner, &old_locks);
+        }
+        let mut new_locks = storage::get_owner_locks(&env, &new_owner);
+        Self::check_owner_capacity(&env, &new_locks)?;
+        new_locks.push_back(token_id);
+        storage::set_owner_locks(&env, &new_owner, &new_locks);
+
+        record.owner = new_owner.clone();
+        storage::set_lock_record(&env, &record);
+        storage::extend_instance_ttl(&env);
+
+        emit_owner_reassigned_event(&env, token_id, old_owner, new_owner);
+        Ok(())
+    }
+
+    /// Get the IDs of all tokens currently locked for `owner`, in lock order
+    pub fn get_locks_by_owner(env: Env, owner: Address) -> Vec<u32> {
+        storage::get_owner_locks(&env, &owner)
+    }
+
+    /// Get the total quantity of credits held across `owner`'s active locks
+    ///
+    /// Reads the record of every lock in the owner's lock index, so the cost
+    /// grows with the owner's lock count, which `set_max_locks_per_owner`
+    /// bounds. Vesting claims are already deducted.
+    pub fn get_locked_amount_by_owner(env: Env, owner: Address) -> i128 {
+        let mut total = 0;
+        for token_id in storage::get_owner_locks(&env, &owner).iter() {
+            if let Some(record) = storage::get_lock_record(&env, token_id) {
+                total += record.amount;
+            }
+        }
+        total
+    }
+
+    /// Set the minimum time a released token must wait before it can be
+    /// locked again (admin only). Zero disables the cooldown.
+    pub fn set_relock_cooldown(
+        env: Env,
+        admin: Address,
+        seconds: u64,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_relock_cooldown(&env, seconds);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the relock cooldown in seconds
+    pub fn get_relock_cooldown(env: Env) -> u64 {
+        storage::get_relock_cooldown(&env)
+    }
+
+    /// Set how far in the future an unlock timestamp may be (admin only).
+    /// Zero removes the limit.
+    pub fn set_max_lock_horizon(
+        env: Env,
+        admin: Address,
+        seconds: u64,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_max_lock_horizon(&env, seconds);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the maximum lock horizon in seconds (0 when unlimited)
+    pub fn get_max_lock_horizon(env: Env) -> u64 {
+        storage::get_max_lock_horizon(&env)
+    }
+
+    /// Set how many locks a single owner may hold at once (admin only).
+    /// Zero removes the limit.
+    pub fn set_max_locks_per_owner(
+        env: Env,
+        admin: Address,
+        max_locks: u32,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_max_locks_per_owner(&env, max_locks);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the maximum number of concurrent locks per owner (0 when unlimited)
+    pub fn get_max_locks_per_owner(env: Env) -> u32 {
+        storage::get_max_locks_per_owner(&env)
+    }
+
+    /// Set the RetirementTracker used by `ExpiryAction::Retire` locks (admin only)
+    ///
+    /// The tracker only lets whitelisted contracts retire their own holdings,
+    /// so this contract must be added with the tracker's `set_trusted_retirer`;
+    /// until then retiring releases fail and the locks stay in place.
+    pub fn set_retirement_tracker(
+        env: Env,
+        admin: Address,
+        tracker: Address,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_retirement_tracker(&env, &tracker);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the RetirementTracker, if configured
+    pub fn get_retirement_tracker(env: Env) -> Option<Address> {
+        storage::get_retirement_tracker(&env)
+    }
+
+    /// Set the buffer pool used by `ExpiryAction::DivertToBuffer` locks (admin only)
+    pub fn set_buffer_pool(
+        env: Env,
+        admin: Address,
+        buffer_pool: Address,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_buffer_pool(&env, &buffer_pool);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the buffer pool, if configured
+    pub fn get_buffer_pool(env: Env) -> Option<Address> {
+        storage::get_buffer_pool(&env)
+    }
+
+    /// Set or clear the vintage oracle (admin only)
+    pub fn set_vintage_oracle(
+        env: Env,
+        admin: Address,
+        oracle: Option<Address>,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_vintage_oracle(&env, &oracle);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the vintage oracle, if configured
+    pub fn get_vintage_oracle(env: Env) -> Option<Address> {
+        storage::get_vintage_oracle(&env)
+    }
+
+    /// Set or clear the shared controller (admin only)
+    ///
+    /// While the controller's `is_paused` reports true, locking, releasing,
+    /// vesting claims and owner reassignment fail with `Paused`. Without a
+    /// controller the time lock is never paused.
+    pub fn set_controller(
+        env: Env,
+        admin: Address,
+        controller: Option<Address>,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_controller(&env, &controller);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the shared controller, if configured
+    pub fn get_controller(env: Env) -> Option<Address> {
+        storage::get_controller(&env)
+    }
+
+    /// Set or clear the reward paid per credit released by `keeper_release`
+    /// (admin only)
+    ///
+    /// # Errors
+    /// * `TimeLockError::InvalidKeeperReward` - Reward amount is not positive
+    pub fn set_keeper_reward(
+        env: Env,
+        admin: Address,
+        reward: Option<KeeperReward>,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        if reward.as_ref().is_some_and(|reward| reward.amount <= 0) {
+            return Err(TimeLockError::InvalidKeeperReward);
+        }
+
+        storage::set_keeper_reward(&env, &reward);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the keeper reward, if configured
+    pub fn get_keeper_reward(env: Env) -> Option<KeeperReward> {
+        storage::get_keeper_reward(&env)
+    }
+
+    /// Enable or disable vintage validation on new locks (admin only)
+    pub fn set_vintage_validation(
+        env: Env,
+        admin: Address,
+        enabled: bool,
+    ) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_vintage_validation(&env, enabled);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Whether new locks are validated against the vintage oracle
+    pub fn get_vintage_validation(env: Env) -> bool {
+        storage::get_vintage_validation(&env)
+    }
+
+    /// Apply several configuration values in one call (admin only)
+    ///
+    /// Only the fields set in `config` are written; the rest keep their
+    /// current value. A single `ConfigUpdated` event is emitted.
+    pub fn configure(env: Env, admin: Address, config: PartialConfig) -> Result<(), TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        if let Some(enabled) = config.vintage_validation {
+            storage::set_vintage_validation(&env, enabled);
+        }
+        if let Some(oracle) = &config.vintage_oracle {
+            storage::set_vintage_oracle(&env, &Some(oracle.clone()));
+        }
+        if let Some(seconds) = config.relock_cooldown {
+            storage::set_relock_cooldown(&env, seconds);
+        }
+        if let Some(seconds) = config.max_lock_horizon {
+            storage::set_max_lock_horizon(&env, seconds);
+        }
+        if let Some(max_locks) = config.max_locks_per_owner {
+            storage::set_max_locks_per_owner(&env, max_locks);
+        }
+        if let Some(tracker) = &config.retirement_tracker {
+            storage::set_retirement_tracker(&env, tracker);
+        }
+        if let Some(buffer_pool) = &config.buffer_pool {
+            storage::set_buffer_pool(&env, buffer_pool);
+        }
+        storage::extend_instance_ttl(&env);
+
+        emit_config_updated_event(&env, admin, config);
+
+        Ok(())
+    }
+
+    /// Extend the TTL of a token's lock record and its lock index entry
+    ///
+    /// Anyone may call this to keep an active lock from being archived.
+    pub fn bump_ttl(env: Env, token_id: u32) -> Result<(), TimeLockError> {
+        if !storage::has_lock_record(&env, token_id) {
+            return Err(TimeLockError::NotLocked);
+        }
+
+        storage::extend_lock_ttl(&env, token_id);
+        storage::extend_instance_ttl(&env);
+
+        Ok(())
+    }
+
+    /// Get the lock record for a token, if it is locked
+    pub fn get_lock_status(env: Env, token_id: u32) -> Option<LockRecord> {
+        storage::get_lock_record(&env, token_id)
+    }
+
+    /// Get the unlock timestamp of a locked token, or `None` if it is not locked
+    pub fn get_unlock_timestamp(env: Env, token_id: u32) -> Option<u64> {
+        storage::get_lock_record(&env, token_id).map(|record| record.unlock_timestamp)
+    }
+
+    /// Check whether a token's vintage has matured according to the oracle
+    ///
+    /// Independent of whether the token is locked or validation is enabled.
+    /// Returns `false` when no oracle is configured or it has no vintage for
+    /// the token, matching the zero response `lock_credit` rejects.
+    pub fn is_vintage_matured(env: Env, token_id: u32) -> bool {
+        let oracle = match storage::get_vintage_oracle(&env) {
+            Some(oracle) => oracle,
+            None => return false,
+        };
+
+        let vintage_unlock =
+            VintageOracleClient::new(&env, &oracle).get_unlock_timestamp(&token_id);
+        vintage_unlock != 0 && env.ledger().timestamp() >= vintage_unlock
+    }
+
+    /// Get the seconds remaining until a token can be released
+    ///
+    /// Returns 0 once the lock is eligible for release, or `None` if the
+    /// token is not locked.
+    pub fn time_until_unlock(env: Env, token_id: u32) -> Option<u64> {
+        let now = env.ledger().timestamp();
+        storage::get_lock_record(&env, token_id)
+            .map(|record| record.unlock_timestamp.saturating_sub(now))
+    }
+
+    /// Get the IDs of tokens that remain locked at `timestamp`, one page of
+    /// the lock index at a time
+    ///
+    /// Scans `BATCH_WORK_BUDGET` positions of the lock index starting at
+    /// `cursor` (0 starts fresh). Returns the cursor to resume from, or 0 once
+    /// the whole index has been scanned, along with the matching token IDs of
+    /// this page sorted by token ID ascending.
+    pub fn get_tokens_locked_until(env: Env, timestamp: u64, cursor: u32) -> (u32, Vec<u32>) {
+        let (next_cursor, records) = Self::scan_locks(&env, cursor);
+        let mut token_ids = Vec::new(&env);
+
+        for record in records.iter() {
+            if record.unlock_timestamp > timestamp {
+                let index = token_ids
+                    .binary_search(record.token_id)
+                    .unwrap_or_else(|index| index);
+                token_ids.insert(index, record.token_id);
+            }
+        }
+
+        (next_cursor, token_ids)
+    }
+
+    /// Get the IDs of tokens whose lock expires within the next `window_seconds`
+    ///
+    /// A token is included when its unlock timestamp falls in
+    /// `(now, now + window_seconds]`, so keepers can stage release jobs
+    /// ahead of time. Scans `BATCH_WORK_BUDGET` positions of the lock index
+    /// starting at `cursor` (0 starts fresh) and returns the cursor to resume
+    /// from, or 0 once the whole index has been scanned, with the page's
+    /// matches in index order.
+    pub fn get_locks_expiring_within(
+        env: Env,
+        window_seconds: u64,
+        cursor: u32,
+    ) -> (u32, Vec<u32>) {
+        let now = env.ledger().timestamp();
+        let window_end = now.saturating_add(window_seconds);
+        let (next_cursor, records) = Self::scan_locks(&env, cursor);
+        let mut token_ids = Vec::new(&env);
+
+        for record in records.iter() {
+            if record.unlock_timestamp > now && record.unlock_timestamp <= window_end {
+                token_ids.push_back(record.token_id);
+            }
+        }
+
+        (next_cursor, token_ids)
+    }
+
+    /// Get the lock records of tokens in the inclusive range `start_id..=end_id`
+    ///
+    /// Ids without an active lock are skipped; results are in token id order.
+    ///
+    /// # Errors
+    /// * `TimeLockError::InvalidRange` - Inverted range or more than `MAX_PAGE_SIZE` ids
+    pub fn get_locks_in_range(
+        env: Env,
+        start_id: u32,
+        end_id: u32,
+    ) -> Result<Vec<LockRecord>, TimeLockError> {
+        if start_id > end_id || end_id - start_id >= MAX_PAGE_SIZE {
+            return Err(TimeLockError::InvalidRange);
+        }
+
+        let mut locks = Vec::new(&env);
+        for token_id in start_id..=end_id {
+            if let Some(record) = storage::get_lock_record(&env, token_id) {
+                locks.push_back(record);
+            }
+        }
+
+        Ok(locks)
+    }
+
+    /// Count active locks by unlock time, for release schedule charts
+    ///
+    /// Bucket `i` counts locks unlocking in `[now + i * bucket_seconds,
+    /// now + (i + 1) * bucket_seconds)`; locks that are already expired fall
+    /// into bucket 0 and locks beyond the last bucket are not counted.
+    /// `buckets` is capped at `MAX_PAGE_SIZE`, and a zero `bucket_seconds`
+    /// yields an empty histogram.
+    ///
+    /// Counts the locks at `BATCH_WORK_BUDGET` positions of the lock index
+    /// starting at `cursor` (0 starts fresh); callers add up the histograms
+    /// of every page. Returns the cursor to resume from, or 0 once the whole
+    /// index has been scanned, along with this page's histogram.
+    pub fn get_unlock_histogram(
+        env: Env,
+        bucket_seconds: u64,
+        buckets: u32,
+        cursor: u32,
+    ) -> (u32, Vec<u32>) {
+        let buckets = buckets.min(MAX_PAGE_SIZE);
+        let mut histogram = Vec::new(&env);
+        if bucket_seconds == 0 {
+            return (0, histogram);
+        }
+        for _ in 0..buckets {
+            histogram.push_back(0u32);
+        }
+
+        let now = env.ledger().timestamp();
+        let (next_cursor, records) = Self::scan_locks(&env, cursor);
+        for record in records.iter() {
+            let bucket = record.unlock_timestamp.saturating_sub(now) / bucket_seconds;
+            if bucket < buckets as u64 {
+                let index = bucket as u32;
+                histogram.set(index, histogram.get_unchecked(index) + 1);
+            }
+        }
+
+        (next_cursor, histogram)
+    }
+
+    /// Get a page of lock records in lock index order
+    ///
+    /// Iterates the maintained lock index from position `start`, returning at
+    /// most `limit` records (capped at `MAX_PAGE_SIZE`). Locks are appended as
+    /// they are created; releasing one moves the last lock into its position.
+    pub fn get_all_locks(env: Env, start: u32, limit: u32) -> Vec<LockRecord> {
+        let end = start.saturating_add(limit.min(MAX_PAGE_SIZE));
+
+        let mut page = Vec::new(&env);
+        for token_id in storage::get_lock_ids(&env, start, end).iter() {
+            if let Some(record) = storage::get_lock_record(&env, token_id) {
+                page.push_back(record);
+            }
+        }
+
+        page
+    }
+
+    /// Get the total quantity of credits held across all active locks,
+    /// measured at lock time
+    pub fn get_total_amount_locked(env: Env) -> i128 {
+        storage::get_total_amount_locked(&env)
+    }
+
+    /// Get the total number of active locks
+    pub fn get_lock_count(env: Env) -> u32 {
+        storage::get_lock_count(&env)
+    }
+
+    /// Get the admin address
+    pub fn get_admin(env: Env) -> Result<Address, TimeLockError> {
+        storage::get_admin(&env)
+    }
+
+    /// Ledger timestamp and sequence as the TimeLock observes them, for
+    /// replay tests of unlock and vesting logic
+    pub fn get_ledger_context(env: Env) -> (u64, u32) {
+        (env.ledger().timestamp(), env.ledger().sequence())
+    }
+
+    /// Whether `who` is the admin; false before initialization
+    pub fn can_admin(env: Env, who: Address) -> bool {
+        storage::get_admin(&env).is_ok_and(|admin| admin == who)
+    }
+
+    /// Get the CarbonAsset contract address
+    pub fn get_carbon_asset_contract(env: Env) -> Result<Address, TimeLockError> {
+        storage::get_carbon_asset_contract(&env)
+    }
+
+    /// List every external contract the time lock is configured to call
+    ///
+    /// Labelled `carbon_asset`, `vintage_oracle`, `retirement_tracker`,
+    /// `buffer_pool` and `controller`; only configured contracts are included.
+    pub fn get_trusted_contracts(env: Env) -> Vec<(Symbol, Address)> {
+        let mut contracts = Vec::new(&env);
+        let configured = [
+            (
+                "carbon_asset",
+                storage::get_carbon_asset_contract(&env).ok(),
+            ),
+            ("vintage_oracle", storage::get_vintage_oracle(&env)),
+            ("retirement_tracker", storage::get_retirement_tracker(&env)),
+            ("buffer_pool", storage::get_buffer_pool(&env)),
+            ("controller", storage::get_controller(&env)),
+        ];
+        for (label, address) in configured {
+            if let Some(address) = address {
+                contracts.push_back((Symbol::new(&env, label), address));
+            }
+        }
+        contracts
+    }
+
+    /// Storage layout version of the live contract
+    ///
+    /// 0 means the contract predates storage versioning.
+    pub fn storage_version(env: Env) -> u32 {
+        storage::get_storage_version(&env)
+    }
+
+    /// Migrate storage to `STORAGE_VERSION` (admin only)
+    ///
+    /// No layout changes exist yet, so this only records the current version.
+    /// Returns the version now in effect.
+    pub fn migrate_storage(env: Env, admin: Address) -> Result<u32, TimeLockError> {
+        Self::require_admin(&env, &admin)?;
+
+        storage::set_storage_version(&env, STORAGE_VERSION);
+        storage::extend_instance_ttl(&env);
+
+        Ok(STORAGE_VERSION)
+    }
+
+    /// Contract interface version
     pub fn version(_env: Env) -> u32 {
         1
     }
+
+    // ========================================================================
+    // Internal Helpers
+    // ========================================================================
+
+    fn require_admin(env: &Env, caller: &Address) -> Result<(), TimeLockError> {
+        caller.require_auth();
+
+        if *caller != storage::get_admin(env)? {
+            return Err(TimeLockError::NotAdmin);
+        }
+
+        Ok(())
+    }
+
+    /// Validate `unlock_timestamp` against the vintage oracle when enabled,
+    /// returning the oracle's unlock timestamp for the record
+    fn check_vintage(
+        env: &Env,
+        token_id: u32,
+        unlock_timestamp: u64,
+    ) -> Result<Option<u64>, TimeLockError> {
+        if !storage::get_vintage_validation(env) {
+            return Ok(None);
+        }
+
+        let oracle = storage::get_vintage_oracle(env).ok_or(TimeLockError::VintageCheckMissing)?;
+        let vintage_unlock = VintageOracleClient::new(env, &oracle).get_unlock_timestamp(&token_id);
+        // A zero response means the oracle has no vintage for the token yet
+        if vintage_unlock == 0 {
+            return Err(TimeLockError::VintageUnavailable);
+        }
+        if unlock_timestamp < vintage_unlock {
+            return Err(TimeLockError::UnlockBeforeVintage);
+        }
+
+        Ok(Some(vintage_unlock))
+    }
+
+    /// Fail with `Paused` while the shared controller, if any, is paused
+    fn require_not_paused(env: &Env) -> Result<(), TimeLockError> {
+        match storage::get_controller(env) {
+            Some(controller) if ControllerClient::new(env, &controller).is_paused() => {
+                Err(TimeLockError::Paused)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Check the approval threshold for `action`, returning its hash
+    fn require_approvals(env: &Env, action: ApprovalAction) -> Result<BytesN<32>, TimeLockError> {
+        let action_hash = Self::get_action_hash(env.clone(), action);
+        let threshold = storage::get_approval_threshold(env);
+
+        if storage::get_approvals(env, &action_hash).len() < threshold {
+            return Err(TimeLockError::InsufficientApprovals);
+        }
+
+        Ok(action_hash)
+    }
+
+    /// Validate and record a new lock, then take custody of the token.
+    /// Callers are responsible for authorizing `caller`.
+    fn create_lock(
+        env: &Env,
+        caller: Address,
+        owner: Address,
+        token_id: u32,
+        unlock_timestamp: u64,
+        release_to: Option<Address>,
+        on_expiry: Option<ExpiryAction>,
+    ) -> Result<LockRecord, TimeLockError> {
+        let vintage_unlock = Self::check_new_lock(env, token_id, unlock_timestamp)?;
+
+        let carbon_asset = storage::get_carbon_asset_contract(env)?;
+        let asset_client = CarbonAssetClient::new(env, &carbon_asset);
+        // Checked up front so a missing token or wrong owner is a typed error
+        // rather than a trap inside the transfer
+        let holder = match asset_client.try_owner_of(&token_id) {
+            Ok(Ok(holder)) => holder,
+            _ => return Err(TimeLockError::TokenNotHeld),
+        };
+        if caller != holder {
+            return Err(TimeLockError::NotOwner);
+        }
+        if holder != owner {
+            return Err(TimeLockError::TokenNotHeld);
+        }
+
+        // Invariant: every token held by this contract has a lock record.
+        // The record and indexes are committed before the external transfer,
+        // and custody is verified afterwards, so any failure reverts both.
+        let record = LockRecord {
+            token_id,
+            owner: owner.clone(),
+            locked_at: env.ledger().timestamp(),
+            unlock_timestamp,
+            release_to,
+            vintage_unlock,
+            on_expiry: on_expiry.unwrap_or(ExpiryAction::ReturnToOwner),
+            amount: asset_client.amount_of(&token_id),
+        };
+        Self::record_lock(env, &record)?;
+
+        let contract_address = env.current_contract_address();
+        asset_client.transfer_from(&caller, &owner, &contract_address, &token_id);
+        if asset_client.owner_of(&token_id) != contract_address {
+            return Err(TimeLockError::CustodyMismatch);
+        }
+
+        emit_locked_event(env, token_id, owner, unlock_timestamp, false);
+
+        Ok(record)
+    }
+
+    /// Checks shared by every new lock, returning the oracle's vintage unlock
+    /// for the record
+    fn check_new_lock(
+        env: &Env,
+        token_id: u32,
+        unlock_timestamp: u64,
+    ) -> Result<Option<u64>, TimeLockError> {
+        Self::require_not_paused(env)?;
+
+        if storage::has_lock_record(env, token_id) {
+            return Err(TimeLockError::AlreadyLocked);
+        }
+
+        let now = env.ledger().timestamp();
+        if unlock_timestamp <= now {
+            return Err(TimeLockError::InvalidUnlockTime);
+        }
+
+        let max_horizon = storage::get_max_lock_horizon(env);
+        if max_horizon > 0 && unlock_timestamp > now.saturating_add(max_horizon) {
+            return Err(TimeLockError::LockBeyondHorizon);
+        }
+
+        if let Some(released_at) = storage::get_last_released(env, token_id) {
+            let cooldown = storage::get_relock_cooldown(env);
+            if now < released_at.saturating_add(cooldown) {
+                return Err(TimeLockError::RelockCooldownActive);
+            }
+        }
+
+        Self::check_vintage(env, token_id, unlock_timestamp)
+    }
+
+    /// Store a new lock record in the lock, owner and amount indexes
+    fn record_lock(env: &Env, record: &LockRecord) -> Result<(), TimeLockError> {
+        let mut owner_locks = storage::get_owner_locks(env, &record.owner);
+        Self::check_owner_capacity(env, &owner_locks)?;
+        owner_locks.push_back(record.token_id);
+        storage::set_owner_locks(env, &record.owner, &owner_locks);
+
+        storage::set_lock_record(env, record);
+        storage::push_lock_id(env, record.token_id);
+        storage::set_total_amount_locked(
+            env,
+            storage::get_total_amount_locked(env) + record.amount,
+        );
+        storage::extend_instance_ttl(env);
+
+        Ok(())
+    }
+
+    /// Reject adding a lock to an owner index that is already at the cap
+    fn check_owner_capacity(env: &Env, owner_locks: &Vec<u32>) -> Result<(), TimeLockError> {
+        let max_locks = storage::get_max_locks_per_owner(env);
+        if max_locks > 0 && owner_locks.len() >= max_locks {
+            return Err(TimeLockError::TooManyLocks);
+        }
+
+        Ok(())
+    }
+
+    /// Whether a non-forced release of the token must be skipped for a
+    /// dispute hold, emitting `ReleaseHeld` when it is
+    fn skip_held(env: &Env, token_id: u32) -> bool {
+        let held = storage::is_on_hold(env, token_id);
+        if held {
+            emit_release_held_event(env, token_id);
+        }
+        held
+    }
+
+    fn is_expired(env: &Env, record: &LockRecord) -> bool {
+        env.ledger().timestamp() >= record.unlock_timestamp
+    }
+
+    /// Address a release with `action` sends the credit to
+    fn release_target(
+        env: &Env,
+        record: &LockRecord,
+        action: ExpiryAction,
+    ) -> Result<Address, TimeLockError> {
+        match action {
+            ExpiryAction::ReturnToOwner => Ok(record.release_destination()),
+            ExpiryAction::Retire => {
+                storage::get_retirement_tracker(env).ok_or(TimeLockError::ExpiryTargetMissing)
+            }
+            ExpiryAction::DivertToBuffer => {
+                storage::get_buffer_pool(env).ok_or(TimeLockError::ExpiryTargetMissing)
+            }
+        }
+    }
+
+    /// Address a vesting claim sends credits to: the buffer pool for a
+    /// diverting lock, the release destination otherwise
+    fn claim_target(env: &Env, record: &LockRecord) -> Result<Address, TimeLockError> {
+        match record.on_expiry {
+            ExpiryAction::DivertToBuffer => {
+                Self::release_target(env, record, ExpiryAction::DivertToBuffer)
+            }
+            ExpiryAction::ReturnToOwner | ExpiryAction::Retire => Ok(record.release_destination()),
+        }
+    }
+
+    /// Credits of a vesting lock that have vested but not been claimed yet
+    fn claimable_vested(env: &Env, record: &LockRecord, vesting: &VestingLock) -> i128 {
+        let now = env.ledger().timestamp();
+        if now < vesting.cliff_timestamp {
+            return 0;
+        }
+
+        let total = record.amount + vesting.claimed;
+        let elapsed = i128::from(now - record.locked_at);
+        let vested = total * elapsed / i128::from(vesting.duration);
+        vested - vesting.claimed
+    }
+
+    /// Drop a lock's record, indexes, vesting schedule, hold and approvals
+    fn remove_lock(env: &Env, record: &LockRecord) {
+        for action in [
+            ApprovalAction::ForceRelease(record.token_id),
+            ApprovalAction::VintageGatedRelease(record.token_id),
+        ] {
+            storage::remove_approvals(env, &Self::get_action_hash(env.clone(), action));
+        }
+
+        storage::remove_lock_record(env, record.token_id);
+        storage::remove_lock_id(env, record.token_id);
+
+        let mut owner_locks = storage::get_owner_locks(env, &record.owner);
+        if let Some(index) = owner_locks.first_index_of(record.token_id) {
+            owner_locks.remove(index);
+            storage::set_owner_locks(env, &record.owner, &owner_locks);
+        }
+
+        storage::remove_vesting(env, record.token_id);
+        storage::remove_dispute_hold(env, record.token_id);
+        storage::set_total_amount_locked(
+            env,
+            storage::get_total_amount_locked(env) - record.amount,
+        );
+        storage::extend_instance_ttl(env);
+    }
+
+    fn migrate(env: &Env, token_id: u32, destination: &Address) -> Result<(), TimeLockError> {
+        Self::require_not_paused(env)?;
+
+        let record = storage::get_lock_record(env, token_id).ok_or(TimeLockError::NotLocked)?;
+        let vesting = storage::get_vesting(env, token_id);
+        let on_hold = storage::is_on_hold(env, token_id);
+        Self::remove_lock(env, &record);
+
+        let this_contract = env.current_contract_address();
+        let carbon_asset = storage::get_carbon_asset_contract(env)?;
+        CarbonAssetClient::new(env, &carbon_asset).transfer(&this_contract, destination, &token_id);
+        TimeLockClient::new(env, destination).import_lock(
+            &this_contract,
+            &record,
+            &vesting,
+            &on_hold,
+        );
+
+        emit_lock_migrated_event(env, token_id, record.owner, destination.clone());
+        Ok(())
+    }
+
+    /// Records of the locks at `BATCH_WORK_BUDGET` positions of the lock
+    /// index starting at `cursor`, with the cursor to resume from (0 at the end)
+    fn scan_locks(env: &Env, cursor: u32) -> (u32, Vec<LockRecord>) {
+        let end = cursor.saturating_add(BATCH_WORK_BUDGET);
+        let mut records = Vec::new(env);
+        for token_id in storage::get_lock_ids(env, cursor, end).iter() {
+            if let Some(record) = storage::get_lock_record(env, token_id) {
+                records.push_back(record);
+            }
+        }
+
+        let next_cursor = if end < storage::get_lock_count(env) {
+            end
+        } else {
+            0
+        };
+        (next_cursor, records)
+    }
+
+    /// Release the expired credits among `BATCH_WORK_BUDGET` entries of
+    /// `token_ids` starting at `cursor`, returning the released records
+    fn release_batch(
+        env: &Env,
+        token_ids: &Vec<u32>,
+        cursor: u32,
+    ) -> Result<(u32, Vec<LockRecord>), TimeLockError> {
+        let mut released = Vec::new(env);
+        let end = cursor
+            .saturating_add(BATCH_WORK_BUDGET)
+            .min(token_ids.len());
+
+        for i in cursor..end {
+            let token_id = token_ids.get_unchecked(i);
+            if let Some(record) = storage::get_lock_record(env, token_id) {
+                if Self::is_expired(env, &record) && !Self::skip_held(env, token_id) {
+                    Self::release(env, &record, false)?;
+                    released.push_back(record);
+                }
+            }
+        }
+
+        let next_cursor = if end < token_ids.len() { end } else { 0 };
+        Ok((next_cursor, released))
+    }
+
+    /// Token IDs of `records`, in order
+    fn token_ids_of(env: &Env, records: &Vec<LockRecord>) -> Vec<u32> {
+        Vec::from_iter(env, records.iter().map(|record| record.token_id))
+    }
+
+    /// Drop the lock record and dispatch the credit according to the lock's
+    /// expiry action. Forced releases always return the credit.
+    fn release(env: &Env, record: &LockRecord, forced: bool) -> Result<(), TimeLockError> {
+        Self::require_not_paused(env)?;
+
+        let action = if forced {
+            ExpiryAction::ReturnToOwner
+        } else {
+            record.on_expiry
+        };
+
+        // Resolve the target before touching state so a missing one is a clean error
+        let destination = Self::release_target(env, record, action)?;
+
+        Self::remove_lock(env, record);
+        storage::set_last_released(env, record.token_id, env.ledger().timestamp());
+
+        match action {
+            ExpiryAction::Retire => {
+                // The credit is held by this contract, so it retires as the
+                // entity; the tracker rejects this unless we are a trusted retirer
+                RetirementTrackerClient::new(env, &destination).retire(
+                    &record.token_id,
+                    &env.current_contract_address(),
+                    &None,
+                );
+            }
+            ExpiryAction::ReturnToOwner => {
+                let carbon_asset = storage::get_carbon_asset_contract(env)?;
+                CarbonAssetClient::new(env, &carbon_asset).transfer(
+                    &env.current_contract_address(),
+                    &destination,
+                    &record.token_id,
+                );
+            }
+            ExpiryAction::DivertToBuffer => {
+                Self::donate_to_pool(env, &destination, record.token_id)?;
+            }
+        }
+
+        emit_released_event(
+            env,
+            record.token_id,
+            record.owner.clone(),
+            destination,
+            forced,
+            action,
+        );
+
+        Ok(())
+    }
+
+    /// Hand `token_id` to the buffer pool through its `donate`, so the credit
+    /// enters the pool's custody and TVL. The pool pulls the credit from this
+    /// contract, so that transfer is authorized up front.
+    fn donate_to_pool(
+        env: &Env,
+        buffer_pool: &Address,
+        token_id: u32,
+    ) -> Result<(), TimeLockError> {
+        let this_contract = env.current_contract_address();
+        Self::authorize_asset_call(
+            env,
+            "transfer",
+            (this_contract.clone(), buffer_pool.clone(), token_id).into_val(env),
+        )?;
+        BufferPoolClient::new(env, buffer_pool).donate(&this_contract, &token_id);
+        Ok(())
+    }
+
+    /// Hand `amount` of `token_id` to the buffer pool through its
+    /// `donate_amount`, so the pool records what it received
+    fn donate_amount_to_pool(
+        env: &Env,
+        buffer_pool: &Address,
+        token_id: u32,
+        amount: i128,
+    ) -> Result<(), TimeLockError> {
+        let this_contract = env.current_contract_address();
+        Self::authorize_asset_call(
+            env,
+            "transfer_amount",
+            (this_contract.clone(), buffer_pool.clone(), token_id, amount).into_val(env),
+        )?;
+        BufferPoolClient::new(env, buffer_pool).donate_amount(&this_contract, &token_id, &amount);
+        Ok(())
+    }
+
+    /// Authorize a CarbonAsset call made on this contract's behalf by the
+    /// next contract it invokes
+    fn authorize_asset_call(env: &Env, fn_name: &str, args: Vec<Val>) -> Result<(), TimeLockError> {
+        let carbon_asset = storage::get_carbon_asset_contract(env)?;
+        env.authorize_as_current_contract(vec![
+            env,
+            InvokerContractAuthEntry::Contract(SubContractInvocation {
+                context: ContractContext {
+                    contract: carbon_asset,
+                    fn_name: Symbol::new(env, fn_name),
+                    args,
+                },
+                sub_invocations: vec![env],
+            }),
+        ]);
+        Ok(())
+    }
 }
+
+#[cfg(test)]
+mod test;
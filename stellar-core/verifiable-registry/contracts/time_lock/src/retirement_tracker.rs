@@ -0,0 +1,9 @@
+use soroban_sdk::{contractclient, Address, Env, String, Val};
+
+/// Subset of the RetirementTracker interface used for retire-on-expiry locks
+#[allow(dead_code)]
+#[contractclient(name = "RetirementTrackerClient")]
+pub trait RetirementTrackerInterface {
+    /// Retire `token_id`, burning it from `retiring_entity`
+    fn retire(env: Env, token_id: u32, retiring_entity: Address, reason: Option<String>) -> Val;
+}
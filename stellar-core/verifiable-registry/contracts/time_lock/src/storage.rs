@@ -0,0 +1,527 @@
+use crate::types::{KeeperReward, LockRecord, TimeLockError, VestingLock};
+use soroban_sdk::{contracttype, Address, BytesN, Env, Vec};
+
+const DAY_IN_LEDGERS: u32 = 17280; // Approximately 1 day worth of ledgers (5s per ledger)
+const INSTANCE_BUMP_AMOUNT: u32 = 30 * DAY_IN_LEDGERS; // 30 days
+const INSTANCE_LIFETIME_THRESHOLD: u32 = INSTANCE_BUMP_AMOUNT - DAY_IN_LEDGERS;
+// Lock records must outlive the longest expected lock, so they are kept
+// ~120 days ahead and re-extended once less than ~90 days remain.
+pub const PERSISTENT_BUMP_AMOUNT: u32 = 120 * DAY_IN_LEDGERS;
+const PERSISTENT_LIFETIME_THRESHOLD: u32 = PERSISTENT_BUMP_AMOUNT - 30 * DAY_IN_LEDGERS;
+// Number of token IDs kept in each page of the lock index
+const LOCK_ID_PAGE_SIZE: u32 = 100;
+
+#[contracttype]
+#[derive(Clone)]
+pub enum StorageKey {
+    Admin,
+    CarbonAssetContract,
+    LockRecord(u32),   // token_id -> LockRecord
+    LockIds(u32),      // position / LOCK_ID_PAGE_SIZE -> Vec<u32> page of locked token IDs
+    LockPosition(u32), // token_id -> position in the lock index
+    LockCount,         // Number of active locks, i.e. positions in the lock index
+    RelockCooldown,
+    LastReleased(u32),   // token_id -> release timestamp
+    OwnerLocks(Address), // owner -> Vec<u32> of token IDs locked for that owner
+    VintageOracle,
+    VintageValidation,
+    RetirementTracker,
+    BufferPool,
+    StorageVersion,
+    TotalAmountLocked,
+    Approvers,
+    ApprovalThreshold,
+    Approvals(BytesN<32>), // action hash -> Vec<Address> of approvers
+    MaxLockHorizon,
+    MaxLocksPerOwner,
+    Vesting(u32),     // token_id -> VestingLock
+    DisputeHold(u32), // token_id -> () while a quality dispute blocks release
+    Controller,
+    MigrationSource, // TimeLock allowed to hand over locks through `import_lock`
+    KeeperReward,
+    KeeperPayout, // temporary; set while a keeper reward is being paid
+}
+
+/// Extend the TTL of instance storage
+pub fn extend_instance_ttl(env: &Env) {
+    env.storage()
+        .instance()
+        .extend_ttl(INSTANCE_LIFETIME_THRESHOLD, INSTANCE_BUMP_AMOUNT);
+}
+
+fn extend_persistent_ttl(env: &Env, key: &StorageKey) {
+    env.storage().persistent().extend_ttl(
+        key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+/// Extend the TTL of a lock's record, index position and index page
+pub fn extend_lock_ttl(env: &Env, token_id: u32) {
+    let storage = env.storage().persistent();
+    if storage.has(&StorageKey::LockRecord(token_id)) {
+        extend_persistent_ttl(env, &StorageKey::LockRecord(token_id));
+    }
+    if let Some(position) = storage.get::<_, u32>(&StorageKey::LockPosition(token_id)) {
+        extend_persistent_ttl(env, &StorageKey::LockPosition(token_id));
+        extend_persistent_ttl(env, &StorageKey::LockIds(position / LOCK_ID_PAGE_SIZE));
+    }
+}
+
+// Admin storage functions
+pub fn has_admin(env: &Env) -> bool {
+    env.storage().instance().has(&StorageKey::Admin)
+}
+
+pub fn get_admin(env: &Env) -> Result<Address, TimeLockError> {
+    env.storage()
+        .instance()
+        .get(&StorageKey::Admin)
+        .ok_or(TimeLockError::NotInitialized)
+}
+
+pub fn set_admin(env: &Env, admin: &Address) {
+    env.storage().instance().set(&StorageKey::Admin, admin);
+}
+
+// CarbonAsset contract storage functions
+pub fn get_carbon_asset_contract(env: &Env) -> Result<Address, TimeLockError> {
+    env.storage()
+        .instance()
+        .get(&StorageKey::CarbonAssetContract)
+        .ok_or(TimeLockError::NotInitialized)
+}
+
+pub fn set_carbon_asset_contract(env: &Env, contract: &Address) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::CarbonAssetContract, contract);
+}
+
+// Vintage oracle storage functions
+pub fn get_vintage_oracle(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&StorageKey::VintageOracle)
+}
+
+pub fn set_vintage_oracle(env: &Env, oracle: &Option<Address>) {
+    match oracle {
+        Some(oracle) => env
+            .storage()
+            .instance()
+            .set(&StorageKey::VintageOracle, oracle),
+        None => env.storage().instance().remove(&StorageKey::VintageOracle),
+    }
+}
+
+pub fn get_migration_source(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&StorageKey::MigrationSource)
+}
+
+pub fn set_migration_source(env: &Env, source: &Option<Address>) {
+    match source {
+        Some(source) => env
+            .storage()
+            .instance()
+            .set(&StorageKey::MigrationSource, source),
+        None => env
+            .storage()
+            .instance()
+            .remove(&StorageKey::MigrationSource),
+    }
+}
+
+pub fn get_controller(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&StorageKey::Controller)
+}
+
+pub fn set_controller(env: &Env, controller: &Option<Address>) {
+    match controller {
+        Some(controller) => env
+            .storage()
+            .instance()
+            .set(&StorageKey::Controller, controller),
+        None => env.storage().instance().remove(&StorageKey::Controller),
+    }
+}
+
+pub fn get_vintage_validation(env: &Env) -> bool {
+    env.storage()
+        .instance()
+        .get(&StorageKey::VintageValidation)
+        .unwrap_or(false)
+}
+
+pub fn set_vintage_validation(env: &Env, enabled: bool) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::VintageValidation, &enabled);
+}
+
+// Expiry target storage functions
+pub fn get_retirement_tracker(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&StorageKey::RetirementTracker)
+}
+
+pub fn set_retirement_tracker(env: &Env, tracker: &Address) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::RetirementTracker, tracker);
+}
+
+pub fn get_buffer_pool(env: &Env) -> Option<Address> {
+    env.storage().instance().get(&StorageKey::BufferPool)
+}
+
+pub fn set_buffer_pool(env: &Env, buffer_pool: &Address) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::BufferPool, buffer_pool);
+}
+
+pub fn get_keeper_reward(env: &Env) -> Option<KeeperReward> {
+    env.storage().instance().get(&StorageKey::KeeperReward)
+}
+
+pub fn set_keeper_reward(env: &Env, reward: &Option<KeeperReward>) {
+    match reward {
+        Some(reward) => env
+            .storage()
+            .instance()
+            .set(&StorageKey::KeeperReward, reward),
+        None => env.storage().instance().remove(&StorageKey::KeeperReward),
+    }
+}
+
+pub fn is_keeper_payout_active(env: &Env) -> bool {
+    env.storage().temporary().has(&StorageKey::KeeperPayout)
+}
+
+pub fn set_keeper_payout_active(env: &Env, active: bool) {
+    if active {
+        env.storage()
+            .temporary()
+            .set(&StorageKey::KeeperPayout, &());
+    } else {
+        env.storage().temporary().remove(&StorageKey::KeeperPayout);
+    }
+}
+
+// Storage layout version functions
+pub fn get_storage_version(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&StorageKey::StorageVersion)
+        .unwrap_or(0)
+}
+
+pub fn set_storage_version(env: &Env, version: u32) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::StorageVersion, &version);
+}
+
+// Locked amount storage functions
+pub fn get_total_amount_locked(env: &Env) -> i128 {
+    env.storage()
+        .instance()
+        .get(&StorageKey::TotalAmountLocked)
+        .unwrap_or(0)
+}
+
+pub fn set_total_amount_locked(env: &Env, amount: i128) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::TotalAmountLocked, &amount);
+}
+
+// Approval storage functions
+pub fn get_approvers(env: &Env) -> Vec<Address> {
+    env.storage()
+        .instance()
+        .get(&StorageKey::Approvers)
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_approvers(env: &Env, approvers: &Vec<Address>) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::Approvers, approvers);
+}
+
+pub fn get_approval_threshold(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&StorageKey::ApprovalThreshold)
+        .unwrap_or(0)
+}
+
+pub fn set_approval_threshold(env: &Env, threshold: u32) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::ApprovalThreshold, &threshold);
+}
+
+pub fn get_approvals(env: &Env, action_hash: &BytesN<32>) -> Vec<Address> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::Approvals(action_hash.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_approvals(env: &Env, action_hash: &BytesN<32>, approvals: &Vec<Address>) {
+    let key = StorageKey::Approvals(action_hash.clone());
+    env.storage().persistent().set(&key, approvals);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub fn remove_approvals(env: &Env, action_hash: &BytesN<32>) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::Approvals(action_hash.clone()));
+}
+
+// Lock record storage functions
+pub fn has_lock_record(env: &Env, token_id: u32) -> bool {
+    env.storage()
+        .persistent()
+        .has(&StorageKey::LockRecord(token_id))
+}
+
+pub fn get_lock_record(env: &Env, token_id: u32) -> Option<LockRecord> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::LockRecord(token_id))
+}
+
+pub fn set_lock_record(env: &Env, record: &LockRecord) {
+    let key = StorageKey::LockRecord(record.token_id);
+    env.storage().persistent().set(&key, record);
+    extend_persistent_ttl(env, &key);
+}
+
+pub fn remove_lock_record(env: &Env, token_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::LockRecord(token_id));
+}
+
+// Lock index storage functions
+//
+// The index lists every locked token ID at positions 0..count, split into
+// pages of LOCK_ID_PAGE_SIZE so no single entry grows with the lock count.
+pub fn get_lock_count(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&StorageKey::LockCount)
+        .unwrap_or(0)
+}
+
+fn get_lock_id_page(env: &Env, page: u32) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::LockIds(page))
+        .unwrap_or(Vec::new(env))
+}
+
+fn set_lock_id_page(env: &Env, page: u32, ids: &Vec<u32>) {
+    let key = StorageKey::LockIds(page);
+    if ids.is_empty() {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+
+    env.storage().persistent().set(&key, ids);
+    extend_persistent_ttl(env, &key);
+}
+
+fn set_lock_position(env: &Env, token_id: u32, position: u32) {
+    let key = StorageKey::LockPosition(token_id);
+    env.storage().persistent().set(&key, &position);
+    extend_persistent_ttl(env, &key);
+}
+
+/// Append `token_id` to the end of the lock index
+pub fn push_lock_id(env: &Env, token_id: u32) {
+    let position = get_lock_count(env);
+    let page = position / LOCK_ID_PAGE_SIZE;
+    let mut ids = get_lock_id_page(env, page);
+    ids.push_back(token_id);
+    set_lock_id_page(env, page, &ids);
+    set_lock_position(env, token_id, position);
+    env.storage()
+        .instance()
+        .set(&StorageKey::LockCount, &(position + 1));
+}
+
+/// Remove `token_id` from the lock index, moving the last entry into its slot
+pub fn remove_lock_id(env: &Env, token_id: u32) {
+    let position_key = StorageKey::LockPosition(token_id);
+    let position: u32 = match env.storage().persistent().get(&position_key) {
+        Some(position) => position,
+        None => return,
+    };
+    env.storage().persistent().remove(&position_key);
+
+    let last = get_lock_count(env) - 1;
+    let last_page = last / LOCK_ID_PAGE_SIZE;
+    let mut last_ids = get_lock_id_page(env, last_page);
+    let moved = last_ids.pop_back_unchecked();
+
+    if position != last {
+        let page = position / LOCK_ID_PAGE_SIZE;
+        if page == last_page {
+            last_ids.set(position % LOCK_ID_PAGE_SIZE, moved);
+        } else {
+            let mut ids = get_lock_id_page(env, page);
+            ids.set(position % LOCK_ID_PAGE_SIZE, moved);
+            set_lock_id_page(env, page, &ids);
+        }
+        set_lock_position(env, moved, position);
+    }
+    set_lock_id_page(env, last_page, &last_ids);
+    env.storage().instance().set(&StorageKey::LockCount, &last);
+}
+
+/// Token IDs at positions `start..end` of the lock index
+pub fn get_lock_ids(env: &Env, start: u32, end: u32) -> Vec<u32> {
+    let end = end.min(get_lock_count(env));
+    let mut ids = Vec::new(env);
+    let mut page = Vec::new(env);
+    for position in start..end {
+        if position == start || position % LOCK_ID_PAGE_SIZE == 0 {
+            page = get_lock_id_page(env, position / LOCK_ID_PAGE_SIZE);
+        }
+        ids.push_back(page.get_unchecked(position % LOCK_ID_PAGE_SIZE));
+    }
+    ids
+}
+
+// Relock cooldown storage functions
+pub fn get_relock_cooldown(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&StorageKey::RelockCooldown)
+        .unwrap_or(0)
+}
+
+pub fn set_relock_cooldown(env: &Env, seconds: u64) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::RelockCooldown, &seconds);
+}
+
+pub fn get_max_lock_horizon(env: &Env) -> u64 {
+    env.storage()
+        .instance()
+        .get(&StorageKey::MaxLockHorizon)
+        .unwrap_or(0)
+}
+
+pub fn set_max_lock_horizon(env: &Env, seconds: u64) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::MaxLockHorizon, &seconds);
+}
+
+pub fn get_max_locks_per_owner(env: &Env) -> u32 {
+    env.storage()
+        .instance()
+        .get(&StorageKey::MaxLocksPerOwner)
+        .unwrap_or(0)
+}
+
+pub fn set_max_locks_per_owner(env: &Env, max_locks: u32) {
+    env.storage()
+        .instance()
+        .set(&StorageKey::MaxLocksPerOwner, &max_locks);
+}
+
+pub fn get_last_released(env: &Env, token_id: u32) -> Option<u64> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::LastReleased(token_id))
+}
+
+pub fn set_last_released(env: &Env, token_id: u32, timestamp: u64) {
+    let key = StorageKey::LastReleased(token_id);
+    env.storage().persistent().set(&key, &timestamp);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+// Vesting schedule storage functions
+pub fn get_vesting(env: &Env, token_id: u32) -> Option<VestingLock> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::Vesting(token_id))
+}
+
+pub fn set_vesting(env: &Env, token_id: u32, vesting: &VestingLock) {
+    let key = StorageKey::Vesting(token_id);
+    env.storage().persistent().set(&key, vesting);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub fn remove_vesting(env: &Env, token_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::Vesting(token_id));
+}
+
+// Dispute hold storage functions
+pub fn is_on_hold(env: &Env, token_id: u32) -> bool {
+    env.storage()
+        .persistent()
+        .has(&StorageKey::DisputeHold(token_id))
+}
+
+pub fn set_dispute_hold(env: &Env, token_id: u32) {
+    let key = StorageKey::DisputeHold(token_id);
+    env.storage().persistent().set(&key, &());
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
+
+pub fn remove_dispute_hold(env: &Env, token_id: u32) {
+    env.storage()
+        .persistent()
+        .remove(&StorageKey::DisputeHold(token_id));
+}
+
+// Owner lock index storage functions
+pub fn get_owner_locks(env: &Env, owner: &Address) -> Vec<u32> {
+    env.storage()
+        .persistent()
+        .get(&StorageKey::OwnerLocks(owner.clone()))
+        .unwrap_or(Vec::new(env))
+}
+
+pub fn set_owner_locks(env: &Env, owner: &Address, ids: &Vec<u32>) {
+    let key = StorageKey::OwnerLocks(owner.clone());
+    if ids.is_empty() {
+        env.storage().persistent().remove(&key);
+        return;
+    }
+
+    env.storage().persistent().set(&key, ids);
+    env.storage().persistent().extend_ttl(
+        &key,
+        PERSISTENT_LIFETIME_THRESHOLD,
+        PERSISTENT_BUMP_AMOUNT,
+    );
+}
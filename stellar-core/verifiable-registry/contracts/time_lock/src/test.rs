@@ -0,0 +1,2098 @@
+#![cfg(test)]
+
+use soroban_sdk::{
+    contract, contractimpl,
+    testutils::{storage::Persistent as _, Address as _, Events as _, Ledger},
+    token::{StellarAssetClient, TokenClient},
+    vec, Address, Env, Event, String, Symbol, Vec,
+};
+
+use crate::events::{
+    ConfigUpdated, CreditLocked, KeeperRewarded, LockOwnerReassigned, ReleaseHeld,
+};
+use crate::storage::{StorageKey, PERSISTENT_BUMP_AMOUNT};
+use crate::{
+    error_code, ApprovalAction, ExpiryAction, KeeperReward, PartialConfig, TimeLock,
+    TimeLockClient, TimeLockError, BATCH_WORK_BUDGET, MAX_PAGE_SIZE, STORAGE_VERSION,
+};
+
+// ========== Mock CarbonAsset ==========
+
+#[contract]
+pub struct MockCarbonAsset;
+
+#[contractimpl]
+impl MockCarbonAsset {
+    pub fn mint(env: Env, to: Address, token_id: u32) {
+        env.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn owner_of(env: Env, token_id: u32) -> Address {
+        env.storage().persistent().get(&token_id).unwrap()
+    }
+
+    pub fn transfer_from(env: Env, spender: Address, from: Address, to: Address, token_id: u32) {
+        spender.require_auth();
+        assert_eq!(Self::owner_of(env.clone(), token_id), from);
+        env.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn transfer(env: Env, from: Address, to: Address, token_id: u32) {
+        from.require_auth();
+        assert_eq!(Self::owner_of(env.clone(), token_id), from);
+        env.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn burn(env: Env, token_id: u32, from: Address) {
+        assert_eq!(Self::owner_of(env.clone(), token_id), from);
+        env.storage().persistent().remove(&token_id);
+    }
+
+    pub fn exists(env: Env, token_id: u32) -> bool {
+        env.storage().persistent().has(&token_id)
+    }
+
+    pub fn set_amount(env: Env, token_id: u32, amount: i128) {
+        env.storage().instance().set(&token_id, &amount);
+    }
+
+    pub fn amount_of(env: Env, token_id: u32) -> i128 {
+        env.storage().instance().get(&token_id).unwrap_or(0)
+    }
+
+    pub fn transfer_amount(env: Env, from: Address, to: Address, token_id: u32, amount: i128) {
+        from.require_auth();
+        assert_eq!(Self::owner_of(env.clone(), token_id), from);
+        let remaining = Self::amount_of(env.clone(), token_id) - amount;
+        env.storage().instance().set(&token_id, &remaining);
+        let received = Self::received(env.clone(), to.clone(), token_id);
+        env.storage()
+            .persistent()
+            .set(&(to, token_id), &(received + amount));
+    }
+
+    pub fn received(env: Env, to: Address, token_id: u32) -> i128 {
+        env.storage().persistent().get(&(to, token_id)).unwrap_or(0)
+    }
+
+    /// Mint `token_id` straight into `time_lock` and lock it for `to`, like
+    /// an auto-locking mint
+    pub fn mint_locked(env: Env, time_lock: Address, to: Address, token_id: u32, unlock: u64) {
+        env.storage().persistent().set(&token_id, &time_lock);
+        let amount = Self::amount_of(env.clone(), token_id);
+        TimeLockClient::new(&env, &time_lock)
+            .lock_from_asset(&to, &token_id, &amount, &unlock, &None, &None);
+    }
+}
+
+// ========== Mock asset that drops transfers ==========
+
+#[contract]
+pub struct NoopTransferAsset;
+
+#[contractimpl]
+impl NoopTransferAsset {
+    pub fn owner_of(env: Env, token_id: u32) -> Address {
+        env.storage().persistent().get(&token_id).unwrap()
+    }
+
+    pub fn mint(env: Env, to: Address, token_id: u32) {
+        env.storage().persistent().set(&token_id, &to);
+    }
+
+    pub fn amount_of(_env: Env, _token_id: u32) -> i128 {
+        1
+    }
+
+    pub fn transfer_from(
+        _env: Env,
+        _spender: Address,
+        _from: Address,
+        _to: Address,
+        _token_id: u32,
+    ) {
+    }
+}
+
+// ========== Mock RetirementTracker ==========
+
+#[contract]
+pub struct MockRetirementTracker;
+
+#[contractimpl]
+impl MockRetirementTracker {
+    pub fn init(env: Env, asset: Address) {
+        env.storage().instance().set(&0u32, &asset);
+    }
+
+    pub fn retire(
+        env: Env,
+        token_id: u32,
+        retiring_entity: Address,
+        _reason: Option<String>,
+    ) -> u32 {
+        retiring_entity.require_auth();
+        // Like the real tracker, contracts may only retire once trusted
+        assert!(Self::is_trusted_retirer(
+            env.clone(),
+            retiring_entity.clone()
+        ));
+        let asset: Address = env.storage().instance().get(&0u32).unwrap();
+        MockCarbonAssetClient::new(&env, &asset).burn(&token_id, &retiring_entity);
+        env.storage().persistent().set(&token_id, &retiring_entity);
+        token_id
+    }
+
+    pub fn retired_by(env: Env, token_id: u32) -> Option<Address> {
+        env.storage().persistent().get(&token_id)
+    }
+
+    pub fn set_trusted_retirer(env: Env, contract: Address, trusted: bool) {
+        env.storage().persistent().set(&contract, &trusted);
+    }
+
+    pub fn is_trusted_retirer(env: Env, contract: Address) -> bool {
+        env.storage().persistent().get(&contract).unwrap_or(false)
+    }
+}
+
+// ========== Mock BufferPool ==========
+
+#[contract]
+pub struct MockBufferPool;
+
+#[contractimpl]
+impl MockBufferPool {
+    pub fn init(env: Env, asset: Address) {
+        env.storage().instance().set(&0u32, &asset);
+    }
+
+    pub fn donate(env: Env, from: Address, token_id: u32) {
+        from.require_auth();
+        let asset: Address = env.storage().instance().get(&0u32).unwrap();
+        MockCarbonAssetClient::new(&env, &asset).transfer(
+            &from,
+            &env.current_contract_address(),
+            &token_id,
+        );
+        env.storage().persistent().set(&token_id, &from);
+    }
+
+    pub fn donate_amount(env: Env, from: Address, token_id: u32, amount: i128) {
+        from.require_auth();
+        let asset: Address = env.storage().instance().get(&0u32).unwrap();
+        let pool = env.current_contract_address();
+        MockCarbonAssetClient::new(&env, &asset).transfer_amount(&from, &pool, &token_id, &amount);
+        let key = (from, token_id);
+        let donated: i128 = env.storage().persistent().get(&key).unwrap_or(0);
+        env.storage().persistent().set(&key, &(donated + amount));
+    }
+
+    pub fn donated_amount(env: Env, from: Address, token_id: u32) -> i128 {
+        env.storage()
+            .persistent()
+            .get(&(from, token_id))
+            .unwrap_or(0)
+    }
+
+    pub fn donor_of(env: Env, token_id: u32) -> Option<Address> {
+        env.storage().persistent().get(&token_id)
+    }
+}
+
+// ========== Mock Vintage Oracle ==========
+
+#[contract]
+pub struct MockVintageOracle;
+
+#[contractimpl]
+impl MockVintageOracle {
+    pub fn set_unlock_timestamp(env: Env, token_id: u32, unlock_timestamp: u64) {
+        env.storage().persistent().set(&token_id, &unlock_timestamp);
+    }
+
+    pub fn get_unlock_timestamp(env: Env, token_id: u32) -> u64 {
+        env.storage().persistent().get(&token_id).unwrap_or(0)
+    }
+}
+
+struct TestSetup<'a> {
+    env: Env,
+    admin: Address,
+    contract_id: Address,
+    client: TimeLockClient<'a>,
+    asset: MockCarbonAssetClient<'a>,
+}
+
+fn setup() -> TestSetup<'static> {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let asset_id = env.register(MockCarbonAsset, ());
+    let contract_id = env.register(TimeLock, ());
+
+    let client = TimeLockClient::new(&env, &contract_id);
+    let asset = MockCarbonAssetClient::new(&env, &asset_id);
+    client.initialize(&admin, &asset_id);
+
+    TestSetup {
+        env,
+        admin,
+        contract_id,
+        client,
+        asset,
+    }
+}
+
+fn mint_and_lock(s: &TestSetup, owner: &Address, token_id: u32, unlock_timestamp: u64) {
+    s.asset.mint(owner, &token_id);
+    s.client
+        .lock_credit(owner, owner, &token_id, &unlock_timestamp, &None, &None);
+}
+
+// ========== Contract Tests ==========
+
+#[test]
+fn test_initialize() {
+    let s = setup();
+
+    assert_eq!(s.client.get_admin(), s.admin);
+    assert_eq!(s.client.get_carbon_asset_contract(), s.asset.address);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #1)")]
+fn test_double_initialization() {
+    let s = setup();
+    s.client.initialize(&s.admin, &s.asset.address);
+}
+
+#[test]
+fn test_can_admin() {
+    let env = Env::default();
+    let client = TimeLockClient::new(&env, &env.register(TimeLock, ()));
+    assert!(!client.can_admin(&Address::generate(&env)));
+
+    let s = setup();
+    assert!(s.client.can_admin(&s.admin));
+    assert!(!s.client.can_admin(&Address::generate(&s.env)));
+}
+
+#[test]
+fn test_get_ledger_context() {
+    let s = setup();
+    s.env.ledger().set_timestamp(12_345);
+    s.env.ledger().set_sequence_number(678);
+    assert_eq!(s.client.get_ledger_context(), (12_345, 678));
+}
+
+#[test]
+fn test_count_releasable() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    mint_and_lock(&s, &owner, 1, 2_000);
+    mint_and_lock(&s, &owner, 2, 2_000);
+    mint_and_lock(&s, &owner, 3, 5_000);
+    mint_and_lock(&s, &owner, 4, 2_000);
+    s.client.place_dispute_hold(&s.admin, &4);
+
+    // 1 and 2 are expired, 3 is still locked, 4 is held and 9 was never locked
+    s.env.ledger().set_timestamp(2_000);
+    let token_ids = vec![&s.env, 1, 2, 3, 4, 9];
+    assert_eq!(s.client.count_releasable(&token_ids), 2);
+    assert_eq!(s.client.count_releasable(&vec![&s.env, 3, 9]), 0);
+
+    s.client.batch_release(&token_ids, &0);
+    assert_eq!(s.client.count_releasable(&token_ids), 0);
+
+    let too_many = Vec::from_iter(&s.env, 0..=MAX_PAGE_SIZE);
+    assert_eq!(
+        s.client.try_count_releasable(&too_many),
+        Err(Ok(TimeLockError::InvalidRange))
+    );
+}
+
+#[test]
+fn test_lock_credit() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 2_000);
+
+    let record = s.client.get_lock_status(&1).unwrap();
+    assert_eq!(record.owner, owner);
+    assert_eq!(record.locked_at, 1_000);
+    assert_eq!(record.unlock_timestamp, 2_000);
+    assert_eq!(s.asset.owner_of(&1), s.contract_id);
+    assert_eq!(s.client.get_unlock_timestamp(&1), Some(2_000));
+    assert_eq!(s.client.get_unlock_timestamp(&2), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #4)")]
+fn test_lock_already_locked_fails() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 2_000);
+    s.client
+        .lock_credit(&owner, &owner, &1, &3_000, &None, &None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #6)")]
+fn test_lock_past_unlock_fails() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 1_000);
+}
+
+#[test]
+fn test_release_if_eligible() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 2_000);
+
+    assert!(!s.client.release_if_eligible(&1));
+    assert!(s.client.get_lock_status(&1).is_some());
+
+    s.env.ledger().set_timestamp(2_000);
+    assert!(s.client.release_if_eligible(&1));
+    assert!(s.client.get_lock_status(&1).is_none());
+    assert_eq!(s.asset.owner_of(&1), owner);
+}
+
+#[test]
+fn test_batch_release_skips_active_locks() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 1_500);
+    mint_and_lock(&s, &owner, 2, 3_000);
+    mint_and_lock(&s, &owner, 3, 1_500);
+
+    s.env.ledger().set_timestamp(2_000);
+    let (cursor, released) = s.client.batch_release(&vec![&s.env, 1, 2, 3, 4], &0);
+
+    assert_eq!(cursor, 0);
+    assert_eq!(released, vec![&s.env, 1, 3]);
+    assert!(s.client.get_lock_status(&2).is_some());
+}
+
+#[test]
+fn test_keeper_release_pays_reward_per_release() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let keeper = Address::generate(&s.env);
+
+    let reward_token = s
+        .env
+        .register_stellar_asset_contract_v2(s.admin.clone())
+        .address();
+    let balances = TokenClient::new(&s.env, &reward_token);
+    StellarAssetClient::new(&s.env, &reward_token).mint(&s.contract_id, &1_000);
+
+    // Without a reward the keeper path releases as `batch_release` does
+    mint_and_lock(&s, &owner, 1, 1_500);
+    s.env.ledger().set_timestamp(2_000);
+    s.client.keeper_release(&keeper, &vec![&s.env, 1], &0);
+    assert_eq!(balances.balance(&keeper), 0);
+
+    assert_eq!(
+        s.client.try_set_keeper_reward(
+            &s.admin,
+            &Some(KeeperReward {
+                token: reward_token.clone(),
+                amount: 0,
+                min_lock_duration: 0,
+            })
+        ),
+        Err(Ok(TimeLockError::InvalidKeeperReward))
+    );
+    let reward = KeeperReward {
+        token: reward_token.clone(),
+        amount: 10,
+        min_lock_duration: 0,
+    };
+    s.client.set_keeper_reward(&s.admin, &Some(reward.clone()));
+    assert_eq!(s.client.get_keeper_reward(), Some(reward));
+
+    mint_and_lock(&s, &owner, 2, 2_500);
+    mint_and_lock(&s, &owner, 3, 9_000);
+    mint_and_lock(&s, &owner, 4, 2_500);
+    s.env.ledger().set_timestamp(3_000);
+
+    let (_, released) = s.client.keeper_release(&keeper, &vec![&s.env, 2, 3, 4], &0);
+    assert_eq!(released, vec![&s.env, 2, 4]);
+    assert_eq!(
+        vec![&s.env, s.env.events().all().last().unwrap()],
+        vec![
+            &s.env,
+            (
+                s.contract_id.clone(),
+                KeeperRewarded {
+                    keeper: keeper.clone(),
+                    token: reward_token.clone(),
+                    amount: 20,
+                    released: 2,
+                }
+                .topics(&s.env),
+                KeeperRewarded {
+                    keeper: keeper.clone(),
+                    token: reward_token.clone(),
+                    amount: 20,
+                    released: 2,
+                }
+                .data(&s.env),
+            )
+        ]
+    );
+
+    assert_eq!(balances.balance(&keeper), 20);
+    assert_eq!(balances.balance(&s.contract_id), 980);
+
+    // Nothing released, nothing paid
+    s.client.keeper_release(&keeper, &vec![&s.env, 3], &0);
+    assert_eq!(balances.balance(&keeper), 20);
+}
+
+#[test]
+fn test_keeper_reward_skips_own_and_short_locks() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let keeper = Address::generate(&s.env);
+
+    let reward_token = s
+        .env
+        .register_stellar_asset_contract_v2(s.admin.clone())
+        .address();
+    let balances = TokenClient::new(&s.env, &reward_token);
+    StellarAssetClient::new(&s.env, &reward_token).mint(&s.contract_id, &1_000);
+    s.client.set_keeper_reward(
+        &s.admin,
+        &Some(KeeperReward {
+            token: reward_token.clone(),
+            amount: 10,
+            min_lock_duration: 500,
+        }),
+    );
+
+    // An owner cycling a one-second lock through the keeper path earns nothing
+    mint_and_lock(&s, &owner, 1, 1_001);
+    s.env.ledger().set_timestamp(1_001);
+    let (_, released) = s.client.keeper_release(&owner, &vec![&s.env, 1], &0);
+    assert_eq!(released, vec![&s.env, 1]);
+    assert_eq!(balances.balance(&owner), 0);
+
+    // Nor does releasing one's own long lock
+    mint_and_lock(&s, &owner, 2, 2_000);
+    s.env.ledger().set_timestamp(2_000);
+    s.client.keeper_release(&owner, &vec![&s.env, 2], &0);
+    assert_eq!(balances.balance(&owner), 0);
+
+    // A third-party keeper is paid only for the lock that ran long enough
+    mint_and_lock(&s, &owner, 3, 2_100);
+    mint_and_lock(&s, &owner, 4, 2_600);
+    s.env.ledger().set_timestamp(2_600);
+    let (_, released) = s.client.keeper_release(&keeper, &vec![&s.env, 3, 4], &0);
+    assert_eq!(released, vec![&s.env, 3, 4]);
+    assert_eq!(balances.balance(&keeper), 10);
+    assert_eq!(balances.balance(&s.contract_id), 990);
+}
+
+#[test]
+fn test_release_owned_only_releases_callers_tokens() {
+    let s = setup();
+    let alice = Address::generate(&s.env);
+    let bob = Address::generate(&s.env);
+
+    mint_and_lock(&s, &alice, 1, 1_500);
+    mint_and_lock(&s, &bob, 2, 1_500);
+    mint_and_lock(&s, &alice, 3, 1_500);
+    mint_and_lock(&s, &alice, 4, 5_000);
+
+    s.env.ledger().set_timestamp(2_000);
+    let released = s.client.release_owned(&alice, &vec![&s.env, 1, 2, 3, 4]);
+
+    assert_eq!(released, vec![&s.env, 1, 3]);
+    assert_eq!(s.asset.owner_of(&1), alice);
+    assert_eq!(s.asset.owner_of(&3), alice);
+    assert_eq!(s.asset.owner_of(&2), s.contract_id);
+    assert!(s.client.get_lock_status(&2).is_some());
+    assert!(s.client.get_lock_status(&4).is_some());
+}
+
+#[test]
+fn test_force_release() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 5_000);
+    s.client.force_release(&s.admin, &1);
+
+    assert!(s.client.get_lock_status(&1).is_none());
+    assert_eq!(s.asset.owner_of(&1), owner);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #3)")]
+fn test_force_release_not_admin_fails() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 5_000);
+    s.client.force_release(&owner, &1);
+}
+
+#[test]
+fn test_time_until_unlock() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 1_500);
+    mint_and_lock(&s, &owner, 2, 4_000);
+    s.env.ledger().set_timestamp(2_000);
+
+    assert_eq!(s.client.time_until_unlock(&2), Some(2_000));
+    assert_eq!(s.client.time_until_unlock(&1), Some(0));
+    assert_eq!(s.client.time_until_unlock(&3), None);
+}
+
+#[test]
+fn test_get_tokens_locked_until() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 1_500);
+    mint_and_lock(&s, &owner, 2, 3_000);
+    mint_and_lock(&s, &owner, 3, 4_000);
+
+    assert_eq!(
+        s.client.get_tokens_locked_until(&2_000, &0),
+        (0, vec![&s.env, 2, 3])
+    );
+}
+
+#[test]
+fn test_get_locks_expiring_within() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 1_500);
+    mint_and_lock(&s, &owner, 2, 2_000);
+    mint_and_lock(&s, &owner, 3, 2_001);
+    mint_and_lock(&s, &owner, 4, 1_200);
+
+    // Now is 1_000: (1_000, 2_000]
+    assert_eq!(
+        s.client.get_locks_expiring_within(&1_000, &0),
+        (0, vec![&s.env, 1, 2, 4])
+    );
+
+    // Already expired locks are not in the window
+    s.env.ledger().set_timestamp(1_600);
+    assert_eq!(
+        s.client.get_locks_expiring_within(&400, &0),
+        (0, vec![&s.env, 2])
+    );
+    assert_eq!(s.client.get_locks_expiring_within(&0, &0).1.len(), 0);
+    assert_eq!(
+        s.client.get_locks_expiring_within(&u64::MAX, &0),
+        (0, vec![&s.env, 2, 3])
+    );
+}
+
+#[test]
+fn test_get_locks_in_range() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    for token_id in 10..=20 {
+        mint_and_lock(&s, &owner, token_id, 2_000);
+    }
+    s.client.force_release(&s.admin, &13);
+    s.client.force_release(&s.admin, &14);
+
+    let locks = s.client.get_locks_in_range(&12, &16);
+    let ids: Vec<u32> = Vec::from_iter(&s.env, locks.iter().map(|record| record.token_id));
+    assert_eq!(ids, vec![&s.env, 12, 15, 16]);
+    assert_eq!(s.client.get_locks_in_range(&30, &40).len(), 0);
+
+    let result = s.client.try_get_locks_in_range(&16, &12);
+    assert_eq!(result, Err(Ok(TimeLockError::InvalidRange)));
+    let result = s.client.try_get_locks_in_range(&0, &MAX_PAGE_SIZE);
+    assert_eq!(result, Err(Ok(TimeLockError::InvalidRange)));
+}
+
+#[test]
+fn test_get_unlock_histogram() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    // Now is 1_000; buckets are 1_000 seconds wide
+    for (token_id, unlock) in [(1, 1_200), (2, 1_999), (3, 2_000), (4, 4_500), (5, 9_000)] {
+        mint_and_lock(&s, &owner, token_id, unlock);
+    }
+
+    assert_eq!(
+        s.client.get_unlock_histogram(&1_000, &4, &0),
+        (0, vec![&s.env, 2, 1, 0, 1])
+    );
+
+    // Expired locks count towards the first bucket
+    s.env.ledger().set_timestamp(2_500);
+    assert_eq!(
+        s.client.get_unlock_histogram(&1_000, &3, &0),
+        (0, vec![&s.env, 3, 0, 1])
+    );
+
+    assert_eq!(s.client.get_unlock_histogram(&0, &4, &0).1.len(), 0);
+    assert_eq!(
+        s.client
+            .get_unlock_histogram(&1_000, &(MAX_PAGE_SIZE + 1), &0)
+            .1
+            .len(),
+        MAX_PAGE_SIZE
+    );
+}
+
+#[test]
+fn test_lock_extends_record_ttl() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 2_000);
+
+    s.env.as_contract(&s.contract_id, || {
+        let ttl = s
+            .env
+            .storage()
+            .persistent()
+            .get_ttl(&StorageKey::LockRecord(1));
+        assert!(ttl >= PERSISTENT_BUMP_AMOUNT - 1);
+    });
+}
+
+#[test]
+fn test_bump_ttl() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 2_000);
+    s.client.bump_ttl(&1);
+
+    assert_eq!(s.client.try_bump_ttl(&2), Err(Ok(TimeLockError::NotLocked)));
+}
+
+#[test]
+fn test_get_all_locks_paginates() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    for token_id in 1..=5 {
+        mint_and_lock(&s, &owner, token_id, 2_000);
+    }
+    s.client.force_release(&s.admin, &2);
+
+    assert_eq!(s.client.get_lock_count(), 4);
+
+    // Releasing token 2 moved the last lock, token 5, into its position
+    let first = s.client.get_all_locks(&0, &3);
+    assert_eq!(first.len(), 3);
+    assert_eq!(first.get(0).unwrap().token_id, 1);
+    assert_eq!(first.get(1).unwrap().token_id, 5);
+    assert_eq!(first.get(2).unwrap().token_id, 3);
+
+    let second = s.client.get_all_locks(&3, &3);
+    assert_eq!(second.len(), 1);
+    assert_eq!(second.get(0).unwrap().token_id, 4);
+
+    assert!(s.client.get_all_locks(&10, &3).is_empty());
+}
+
+#[test]
+fn test_relock_within_cooldown_fails() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    s.client.set_relock_cooldown(&s.admin, &600);
+    mint_and_lock(&s, &owner, 1, 2_000);
+
+    s.env.ledger().set_timestamp(2_000);
+    assert!(s.client.release_if_eligible(&1));
+
+    let result = s
+        .client
+        .try_lock_credit(&owner, &owner, &1, &5_000, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::RelockCooldownActive)));
+
+    s.env.ledger().set_timestamp(2_600);
+    s.client
+        .lock_credit(&owner, &owner, &1, &5_000, &None, &None);
+    assert!(s.client.get_lock_status(&1).is_some());
+}
+
+#[test]
+fn test_relock_without_cooldown() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 2_000);
+
+    s.env.ledger().set_timestamp(2_000);
+    s.client.release_if_eligible(&1);
+    s.client
+        .lock_credit(&owner, &owner, &1, &3_000, &None, &None);
+
+    assert_eq!(s.client.get_relock_cooldown(), 0);
+    assert!(s.client.get_lock_status(&1).is_some());
+}
+
+#[test]
+fn test_get_tokens_locked_until_is_sorted() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    for token_id in [42, 7, 19, 3, 100] {
+        mint_and_lock(&s, &owner, token_id, 5_000);
+    }
+
+    assert_eq!(
+        s.client.get_tokens_locked_until(&2_000, &0),
+        (0, vec![&s.env, 3, 7, 19, 42, 100])
+    );
+}
+
+#[test]
+fn test_lock_index_spans_pages() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    s.client.set_max_locks_per_owner(&s.admin, &0);
+
+    let total = MAX_PAGE_SIZE + 10;
+    for token_id in 1..=total {
+        mint_and_lock(&s, &owner, token_id, 2_000 + u64::from(token_id));
+    }
+    // Release locks from the first page, the second page and the last slot
+    for token_id in [1, 50, MAX_PAGE_SIZE + 5, total] {
+        s.client.force_release(&s.admin, &token_id);
+    }
+    assert_eq!(s.client.get_lock_count(), total - 4);
+
+    // Every remaining lock is listed exactly once across the pages
+    let mut listed = Vec::new(&s.env);
+    for record in s.client.get_all_locks(&0, &MAX_PAGE_SIZE).iter().chain(
+        s.client
+            .get_all_locks(&MAX_PAGE_SIZE, &MAX_PAGE_SIZE)
+            .iter(),
+    ) {
+        assert!(!listed.contains(record.token_id));
+        listed.push_back(record.token_id);
+    }
+    assert_eq!(listed.len(), total - 4);
+    for token_id in [1, 50, MAX_PAGE_SIZE + 5, total] {
+        assert!(!listed.contains(token_id));
+    }
+
+    // The cursor views walk the same index one budget at a time
+    let (mut cursor, mut found) = (0, 0);
+    loop {
+        let (next_cursor, token_ids) = s.client.get_tokens_locked_until(&0, &cursor);
+        found += token_ids.len();
+        if next_cursor == 0 {
+            break;
+        }
+        assert_eq!(next_cursor, cursor + BATCH_WORK_BUDGET);
+        cursor = next_cursor;
+    }
+    assert_eq!(found, total - 4);
+
+    s.client.bump_ttl(&2);
+    s.env.as_contract(&s.contract_id, || {
+        assert!(!s.env.storage().persistent().has(&StorageKey::LockRecord(1)));
+        let ttl = s
+            .env
+            .storage()
+            .persistent()
+            .get_ttl(&StorageKey::LockIds(0));
+        assert!(ttl >= PERSISTENT_BUMP_AMOUNT - 1);
+    });
+}
+
+#[test]
+fn test_release_to_beneficiary() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let settlement = Address::generate(&s.env);
+
+    s.asset.mint(&owner, &1);
+    let record = s
+        .client
+        .lock_credit(&owner, &owner, &1, &2_000, &Some(settlement.clone()), &None);
+    assert_eq!(record.release_to, Some(settlement.clone()));
+
+    s.env.ledger().set_timestamp(2_000);
+    assert!(s.client.release_if_eligible(&1));
+
+    assert_eq!(s.asset.owner_of(&1), settlement);
+}
+
+#[test]
+fn test_batch_release_resumes_from_cursor() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    let total = BATCH_WORK_BUDGET + 5;
+    let mut token_ids = Vec::new(&s.env);
+    for token_id in 1..=total {
+        mint_and_lock(&s, &owner, token_id, 1_500);
+        token_ids.push_back(token_id);
+    }
+
+    s.env.ledger().set_timestamp(2_000);
+
+    let (cursor, released) = s.client.batch_release(&token_ids, &0);
+    assert_eq!(cursor, BATCH_WORK_BUDGET);
+    assert_eq!(released.len(), BATCH_WORK_BUDGET);
+
+    let (cursor, released) = s.client.batch_release(&token_ids, &cursor);
+    assert_eq!(cursor, 0);
+    assert_eq!(released.len(), 5);
+    assert_eq!(s.client.get_lock_count(), 0);
+}
+
+#[test]
+fn test_lock_by_owner() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    s.asset.mint(&owner, &1);
+
+    s.client
+        .lock_credit(&owner, &owner, &1, &2_000, &None, &None);
+    assert_eq!(s.asset.owner_of(&1), s.contract_id);
+}
+
+#[test]
+fn test_lock_by_asset_contract() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    s.asset.set_amount(&1, &40);
+    // The asset authorizes as the direct invoker; nobody else signs
+    s.env.set_auths(&[]);
+
+    s.asset.mint_locked(&s.contract_id, &owner, &1, &2_000);
+
+    let record = s.client.get_lock_status(&1).unwrap();
+    assert_eq!(record.owner, owner);
+    assert_eq!(record.amount, 40);
+    assert_eq!(s.asset.owner_of(&1), s.contract_id);
+    assert_eq!(s.client.get_locks_by_owner(&owner), vec![&s.env, 1]);
+    assert_eq!(s.client.get_total_amount_locked(), 40);
+
+    // Only the asset itself may call the asset path
+    let result = s
+        .client
+        .try_lock_from_asset(&owner, &2, &1, &2_000, &None, &None);
+    assert!(result.is_err());
+    assert!(s.client.get_lock_status(&2).is_none());
+
+    // The owner path no longer accepts the asset as caller
+    s.env.mock_all_auths();
+    s.asset.mint(&owner, &3);
+    let result = s
+        .client
+        .try_lock_credit(&s.asset.address, &owner, &3, &2_000, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::NotOwner)));
+
+    s.env.ledger().set_timestamp(2_000);
+    assert!(s.client.release_if_eligible(&1));
+    assert_eq!(s.asset.owner_of(&1), owner);
+}
+
+#[test]
+fn test_lock_by_third_party_fails() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let stranger = Address::generate(&s.env);
+    s.asset.mint(&owner, &1);
+
+    let result = s
+        .client
+        .try_lock_credit(&stranger, &owner, &1, &2_000, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::NotOwner)));
+    assert_eq!(s.asset.owner_of(&1), owner);
+}
+
+#[test]
+fn test_lock_token_not_held_by_owner_fails() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let holder = Address::generate(&s.env);
+    s.asset.mint(&holder, &1);
+
+    // A lock is only taken for the owner that actually holds the token
+    let result = s
+        .client
+        .try_lock_credit(&holder, &owner, &1, &2_000, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::TokenNotHeld)));
+    assert_eq!(s.asset.owner_of(&1), holder);
+
+    let result = s
+        .client
+        .try_lock_credit(&owner, &owner, &9, &2_000, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::TokenNotHeld)));
+}
+
+#[test]
+fn test_reassign_lock_owner() {
+    let s = setup();
+    let old_owner = Address::generate(&s.env);
+    let new_owner = Address::generate(&s.env);
+
+    let result = s.client.try_reassign_lock_owner(&s.admin, &1, &new_owner);
+    assert_eq!(result, Err(Ok(TimeLockError::NotLocked)));
+
+    mint_and_lock(&s, &old_owner, 1, 1_500);
+    s.client.reassign_lock_owner(&s.admin, &1, &new_owner);
+
+    let expected = LockOwnerReassigned {
+        token_id: 1,
+        old_owner: old_owner.clone(),
+        new_owner: new_owner.clone(),
+    };
+    assert_eq!(
+        vec![&s.env, s.env.events().all().last().unwrap()],
+        vec![
+            &s.env,
+            (
+                s.contract_id.clone(),
+                expected.topics(&s.env),
+                expected.data(&s.env)
+            )
+        ]
+    );
+    assert_eq!(s.client.get_lock_status(&1).unwrap().owner, new_owner);
+    assert_eq!(s.client.get_locks_by_owner(&old_owner).len(), 0);
+    assert_eq!(s.client.get_locks_by_owner(&new_owner), vec![&s.env, 1]);
+
+    s.env.ledger().set_timestamp(2_000);
+    s.client.release_if_eligible(&1);
+    assert_eq!(s.asset.owner_of(&1), new_owner);
+
+    let other = Address::generate(&s.env);
+    mint_and_lock(&s, &old_owner, 2, 5_000);
+    let result = s.client.try_reassign_lock_owner(&other, &2, &new_owner);
+    assert_eq!(result, Err(Ok(TimeLockError::NotAdmin)));
+}
+
+#[test]
+fn test_force_release_by_owner() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+
+    let lock_count = BATCH_WORK_BUDGET + 2;
+    for token_id in 0..lock_count {
+        mint_and_lock(&s, &owner, token_id, 5_000);
+    }
+    mint_and_lock(&s, &other, 100, 5_000);
+    assert_eq!(s.client.get_locks_by_owner(&owner).len(), lock_count);
+
+    let (released, remaining) = s.client.force_release_by_owner(&s.admin, &owner);
+    assert_eq!((released, remaining), (BATCH_WORK_BUDGET, 2));
+
+    let (released, remaining) = s.client.force_release_by_owner(&s.admin, &owner);
+    assert_eq!((released, remaining), (2, 0));
+
+    for token_id in 0..lock_count {
+        assert_eq!(s.asset.owner_of(&token_id), owner);
+    }
+    assert_eq!(s.client.get_locks_by_owner(&owner).len(), 0);
+    assert_eq!(s.client.get_locks_by_owner(&other), vec![&s.env, 100]);
+    assert_eq!(s.client.get_lock_count(), 1);
+}
+
+#[test]
+fn test_lock_stores_vintage_unlock() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let oracle = MockVintageOracleClient::new(&s.env, &s.env.register(MockVintageOracle, ()));
+    oracle.set_unlock_timestamp(&1, &3_000);
+    oracle.set_unlock_timestamp(&2, &3_000);
+
+    s.client
+        .set_vintage_oracle(&s.admin, &Some(oracle.address.clone()));
+    s.client.set_vintage_validation(&s.admin, &true);
+
+    mint_and_lock(&s, &owner, 1, 4_000);
+    let record = s.client.get_lock_status(&1).unwrap();
+    assert_eq!(record.vintage_unlock, Some(3_000));
+
+    // The snapshot survives later oracle changes
+    oracle.set_unlock_timestamp(&1, &9_000);
+    assert_eq!(
+        s.client.get_lock_status(&1).unwrap().vintage_unlock,
+        Some(3_000)
+    );
+
+    s.asset.mint(&owner, &2);
+    let result = s
+        .client
+        .try_lock_credit(&owner, &owner, &2, &2_000, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::UnlockBeforeVintage)));
+}
+
+#[test]
+fn test_lock_without_vintage_validation() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 2_000);
+    assert_eq!(s.client.get_lock_status(&1).unwrap().vintage_unlock, None);
+
+    s.client.set_vintage_validation(&s.admin, &true);
+    s.asset.mint(&owner, &2);
+    let result = s
+        .client
+        .try_lock_credit(&owner, &owner, &2, &2_000, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::VintageCheckMissing)));
+}
+
+#[test]
+fn test_is_vintage_matured() {
+    let s = setup();
+    assert!(!s.client.is_vintage_matured(&1));
+
+    let oracle = MockVintageOracleClient::new(&s.env, &s.env.register(MockVintageOracle, ()));
+    s.client
+        .set_vintage_oracle(&s.admin, &Some(oracle.address.clone()));
+    oracle.set_unlock_timestamp(&1, &500);
+    oracle.set_unlock_timestamp(&2, &3_000);
+
+    // Now is 1_000; token 3 has no vintage
+    assert!(s.client.is_vintage_matured(&1));
+    assert!(!s.client.is_vintage_matured(&2));
+    assert!(!s.client.is_vintage_matured(&3));
+
+    s.env.ledger().set_timestamp(3_000);
+    assert!(s.client.is_vintage_matured(&2));
+}
+
+#[test]
+fn test_lock_rejects_unknown_vintage() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let oracle = MockVintageOracleClient::new(&s.env, &s.env.register(MockVintageOracle, ()));
+    s.client
+        .set_vintage_oracle(&s.admin, &Some(oracle.address.clone()));
+    s.client.set_vintage_validation(&s.admin, &true);
+
+    s.asset.mint(&owner, &1);
+    let result = s
+        .client
+        .try_lock_credit(&owner, &owner, &1, &2_000, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::VintageUnavailable)));
+    assert_eq!(s.client.get_lock_status(&1), None);
+
+    oracle.set_unlock_timestamp(&1, &1_500);
+    s.client
+        .lock_credit(&owner, &owner, &1, &2_000, &None, &None);
+    assert_eq!(
+        s.client.get_lock_status(&1).unwrap().vintage_unlock,
+        Some(1_500)
+    );
+}
+
+#[test]
+fn test_expiry_return_to_owner_by_default() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    mint_and_lock(&s, &owner, 1, 2_000);
+    assert_eq!(
+        s.client.get_lock_status(&1).unwrap().on_expiry,
+        ExpiryAction::ReturnToOwner
+    );
+
+    s.env.ledger().set_timestamp(2_000);
+    assert!(s.client.release_if_eligible(&1));
+    assert_eq!(s.asset.owner_of(&1), owner);
+}
+
+#[test]
+fn test_expiry_retire() {
+    let s = setup();
+    s.env.mock_all_auths_allowing_non_root_auth();
+    let owner = Address::generate(&s.env);
+    let tracker =
+        MockRetirementTrackerClient::new(&s.env, &s.env.register(MockRetirementTracker, ()));
+    tracker.init(&s.asset.address);
+    tracker.set_trusted_retirer(&s.contract_id, &true);
+    s.client.set_retirement_tracker(&s.admin, &tracker.address);
+
+    s.asset.mint(&owner, &1);
+    s.client.lock_credit(
+        &owner,
+        &owner,
+        &1,
+        &2_000,
+        &None,
+        &Some(ExpiryAction::Retire),
+    );
+
+    s.env.ledger().set_timestamp(2_000);
+    assert!(s.client.release_if_eligible(&1));
+    assert!(!s.asset.exists(&1));
+    assert_eq!(tracker.retired_by(&1), Some(s.contract_id.clone()));
+    assert!(s.client.get_lock_status(&1).is_none());
+}
+
+#[test]
+fn test_expiry_retire_requires_trusted_retirer() {
+    let s = setup();
+    s.env.mock_all_auths_allowing_non_root_auth();
+    let owner = Address::generate(&s.env);
+    let tracker =
+        MockRetirementTrackerClient::new(&s.env, &s.env.register(MockRetirementTracker, ()));
+    tracker.init(&s.asset.address);
+    s.client.set_retirement_tracker(&s.admin, &tracker.address);
+
+    s.asset.mint(&owner, &1);
+    s.client.lock_credit(
+        &owner,
+        &owner,
+        &1,
+        &2_000,
+        &None,
+        &Some(ExpiryAction::Retire),
+    );
+
+    // Until the tracker trusts the time lock the release fails and the lock stays
+    s.env.ledger().set_timestamp(2_000);
+    assert!(s.client.try_release_if_eligible(&1).is_err());
+    assert!(s.client.get_lock_status(&1).is_some());
+    assert_eq!(s.asset.owner_of(&1), s.contract_id);
+    assert_eq!(tracker.retired_by(&1), None);
+
+    tracker.set_trusted_retirer(&s.contract_id, &true);
+    assert!(s.client.release_if_eligible(&1));
+    assert_eq!(tracker.retired_by(&1), Some(s.contract_id.clone()));
+}
+
+#[test]
+fn test_expiry_divert_to_buffer() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let buffer_pool = MockBufferPoolClient::new(&s.env, &s.env.register(MockBufferPool, ()));
+    buffer_pool.init(&s.asset.address);
+
+    s.asset.mint(&owner, &1);
+    s.client.lock_credit(
+        &owner,
+        &owner,
+        &1,
+        &2_000,
+        &None,
+        &Some(ExpiryAction::DivertToBuffer),
+    );
+
+    s.env.ledger().set_timestamp(2_000);
+    let result = s.client.try_release_if_eligible(&1);
+    assert_eq!(result, Err(Ok(TimeLockError::ExpiryTargetMissing)));
+
+    s.client.set_buffer_pool(&s.admin, &buffer_pool.address);
+    // No mocked auths: the time lock authorizes the pool's pull itself
+    s.env.set_auths(&[]);
+    assert!(s.client.release_if_eligible(&1));
+    // The credit enters the pool through its donate entry point
+    assert_eq!(s.asset.owner_of(&1), buffer_pool.address);
+    assert_eq!(buffer_pool.donor_of(&1), Some(s.contract_id.clone()));
+}
+
+#[test]
+fn test_storage_version() {
+    let s = setup();
+    assert_eq!(s.client.storage_version(), STORAGE_VERSION);
+
+    assert_eq!(s.client.migrate_storage(&s.admin), STORAGE_VERSION);
+    assert_eq!(s.client.storage_version(), STORAGE_VERSION);
+
+    let stranger = Address::generate(&s.env);
+    let result = s.client.try_migrate_storage(&stranger);
+    assert_eq!(result, Err(Ok(TimeLockError::NotAdmin)));
+}
+
+#[test]
+fn test_total_amount_locked() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    s.asset.set_amount(&1, &100);
+    s.asset.set_amount(&2, &250);
+
+    mint_and_lock(&s, &owner, 1, 2_000);
+    mint_and_lock(&s, &owner, 2, 3_000);
+    assert_eq!(s.client.get_lock_status(&2).unwrap().amount, 250);
+    assert_eq!(s.client.get_total_amount_locked(), 350);
+
+    s.env.ledger().set_timestamp(2_000);
+    s.client.release_if_eligible(&1);
+    assert_eq!(s.client.get_total_amount_locked(), 250);
+
+    s.client.force_release(&s.admin, &2);
+    assert_eq!(s.client.get_total_amount_locked(), 0);
+}
+
+#[test]
+fn test_get_locked_amount_by_owner() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+    s.asset.set_amount(&1, &100);
+    s.asset.set_amount(&2, &250);
+    s.asset.set_amount(&3, &40);
+    s.asset.set_amount(&4, &7);
+
+    assert_eq!(s.client.get_locked_amount_by_owner(&owner), 0);
+    mint_and_lock(&s, &owner, 1, 2_000);
+    mint_and_lock(&s, &owner, 2, 3_000);
+    mint_and_lock(&s, &owner, 3, 4_000);
+    mint_and_lock(&s, &other, 4, 2_000);
+    assert_eq!(s.client.get_locked_amount_by_owner(&owner), 390);
+    assert_eq!(s.client.get_locked_amount_by_owner(&other), 7);
+
+    s.env.ledger().set_timestamp(2_000);
+    s.client.release_if_eligible(&1);
+    assert_eq!(s.client.get_locked_amount_by_owner(&owner), 290);
+}
+
+#[test]
+fn test_force_release_requires_approvals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let approvers = vec![
+        &env,
+        Address::generate(&env),
+        Address::generate(&env),
+        Address::generate(&env),
+    ];
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let client = TimeLockClient::new(&env, &env.register(TimeLock, ()));
+    client.initialize_with_approvers(&admin, &asset.address, &approvers, &2);
+
+    asset.mint(&owner, &1);
+    client.lock_credit(&owner, &owner, &1, &5_000, &None, &None);
+
+    let action_hash = client.get_action_hash(&ApprovalAction::ForceRelease(1));
+    let result = client.try_force_release(&admin, &1);
+    assert_eq!(result, Err(Ok(TimeLockError::InsufficientApprovals)));
+
+    // Repeat approvals from one approver count once
+    client.approve_action(&approvers.get_unchecked(0), &action_hash);
+    assert_eq!(
+        client.approve_action(&approvers.get_unchecked(0), &action_hash),
+        1
+    );
+    let result = client.try_force_release(&admin, &1);
+    assert_eq!(result, Err(Ok(TimeLockError::InsufficientApprovals)));
+
+    let stranger = Address::generate(&env);
+    let result = client.try_approve_action(&stranger, &action_hash);
+    assert_eq!(result, Err(Ok(TimeLockError::NotApprover)));
+
+    client.approve_action(&approvers.get_unchecked(2), &action_hash);
+    client.force_release(&admin, &1);
+
+    assert_eq!(asset.owner_of(&1), owner);
+    assert_eq!(client.get_approval_count(&action_hash), 0);
+}
+
+#[test]
+fn test_force_release_approvals_do_not_outlive_the_lock() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let approvers = vec![&env, Address::generate(&env), Address::generate(&env)];
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let client = TimeLockClient::new(&env, &env.register(TimeLock, ()));
+    client.initialize_with_approvers(&admin, &asset.address, &approvers, &2);
+
+    asset.mint(&owner, &1);
+    client.lock_credit(&owner, &owner, &1, &2_000, &None, &None);
+    let first_hash = client.get_action_hash(&ApprovalAction::ForceRelease(1));
+    for approver in approvers.iter() {
+        client.approve_action(&approver, &first_hash);
+    }
+
+    // The approved lock expires and is released normally instead
+    env.ledger().set_timestamp(2_000);
+    assert!(client.release_if_eligible(&1));
+    assert_eq!(client.get_approval_count(&first_hash), 0);
+
+    // A new lock of the same token needs fresh approvals
+    env.ledger().set_timestamp(2_500);
+    client.lock_credit(&owner, &owner, &1, &9_000, &None, &None);
+    let second_hash = client.get_action_hash(&ApprovalAction::ForceRelease(1));
+    assert_ne!(second_hash, first_hash);
+    for approver in approvers.iter() {
+        client.approve_action(&approver, &first_hash);
+    }
+    let result = client.try_force_release(&admin, &1);
+    assert_eq!(result, Err(Ok(TimeLockError::InsufficientApprovals)));
+
+    for approver in approvers.iter() {
+        client.approve_action(&approver, &second_hash);
+    }
+    client.force_release(&admin, &1);
+    assert_eq!(asset.owner_of(&1), owner);
+    assert_eq!(client.get_approval_count(&second_hash), 0);
+}
+
+#[test]
+fn test_initialize_with_invalid_threshold_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+
+    let admin = Address::generate(&env);
+    let approvers = vec![&env, Address::generate(&env)];
+    let client = TimeLockClient::new(&env, &env.register(TimeLock, ()));
+
+    let result = client.try_initialize_with_approvers(&admin, &admin, &approvers, &2);
+    assert_eq!(result, Err(Ok(TimeLockError::InvalidThreshold)));
+    let result = client.try_initialize_with_approvers(&admin, &admin, &approvers, &0);
+    assert_eq!(result, Err(Ok(TimeLockError::InvalidThreshold)));
+}
+
+#[test]
+fn test_held_tokens_always_have_lock_records() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    for token_id in 1..=4 {
+        mint_and_lock(&s, &owner, token_id, 1_000 + 1_000 * token_id as u64);
+    }
+    s.env.ledger().set_timestamp(3_000);
+    s.client.batch_release(&vec![&s.env, 1, 2, 3, 4], &0);
+
+    for token_id in 1..=4 {
+        let held = s.asset.owner_of(&token_id) == s.contract_id;
+        assert_eq!(held, s.client.get_lock_status(&token_id).is_some());
+    }
+    assert_eq!(s.client.get_lock_count(), 2);
+}
+
+#[test]
+fn test_lock_without_custody_fails() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let asset = NoopTransferAssetClient::new(&env, &env.register(NoopTransferAsset, ()));
+    let client = TimeLockClient::new(&env, &env.register(TimeLock, ()));
+    client.initialize(&admin, &asset.address);
+    asset.mint(&owner, &1);
+
+    let result = client.try_lock_credit(&owner, &owner, &1, &2_000, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::CustodyMismatch)));
+    assert!(client.get_lock_status(&1).is_none());
+    assert_eq!(client.get_lock_count(), 0);
+    assert_eq!(client.get_total_amount_locked(), 0);
+}
+
+#[test]
+fn test_lock_beyond_horizon_fails() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    s.asset.mint(&owner, &1);
+
+    s.client.set_max_lock_horizon(&s.admin, &10_000);
+    let result = s
+        .client
+        .try_lock_credit(&owner, &owner, &1, &11_001, &None, &None);
+    assert_eq!(result, Err(Ok(TimeLockError::LockBeyondHorizon)));
+
+    s.client
+        .lock_credit(&owner, &owner, &1, &11_000, &None, &None);
+    assert!(s.client.get_lock_status(&1).is_some());
+}
+
+#[test]
+fn test_unlimited_horizon_by_default() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+
+    assert_eq!(s.client.get_max_lock_horizon(), 0);
+    mint_and_lock(&s, &owner, 1, u64::MAX);
+    assert!(s.client.get_lock_status(&1).is_some());
+}
+
+#[test]
+fn test_error_codes_are_stable() {
+    assert_eq!(error_code(TimeLockError::AlreadyInitialized), 1);
+    assert_eq!(error_code(TimeLockError::NotInitialized), 2);
+    assert_eq!(error_code(TimeLockError::NotAdmin), 3);
+    assert_eq!(error_code(TimeLockError::AlreadyLocked), 4);
+    assert_eq!(error_code(TimeLockError::NotLocked), 5);
+    assert_eq!(error_code(TimeLockError::InvalidUnlockTime), 6);
+    assert_eq!(error_code(TimeLockError::RelockCooldownActive), 7);
+    assert_eq!(error_code(TimeLockError::NotOwner), 8);
+    assert_eq!(error_code(TimeLockError::VintageCheckMissing), 9);
+    assert_eq!(error_code(TimeLockError::UnlockBeforeVintage), 10);
+    assert_eq!(error_code(TimeLockError::ExpiryTargetMissing), 11);
+    assert_eq!(error_code(TimeLockError::NotApprover), 12);
+    assert_eq!(error_code(TimeLockError::InsufficientApprovals), 13);
+    assert_eq!(error_code(TimeLockError::InvalidThreshold), 14);
+    assert_eq!(error_code(TimeLockError::CustodyMismatch), 15);
+    assert_eq!(error_code(TimeLockError::LockBeyondHorizon), 16);
+    assert_eq!(error_code(TimeLockError::VintageUnavailable), 17);
+    assert_eq!(error_code(TimeLockError::InvalidRange), 18);
+    assert_eq!(error_code(TimeLockError::NotVesting), 19);
+    assert_eq!(error_code(TimeLockError::TooManyLocks), 20);
+    assert_eq!(error_code(TimeLockError::VintageNotMatured), 21);
+    assert_eq!(error_code(TimeLockError::Paused), 22);
+    assert_eq!(error_code(TimeLockError::NotMigrationSource), 23);
+    assert_eq!(error_code(TimeLockError::TokenNotHeld), 24);
+    assert_eq!(error_code(TimeLockError::KeeperPayoutActive), 25);
+    assert_eq!(error_code(TimeLockError::InvalidKeeperReward), 26);
+}
+
+#[test]
+fn test_lock_event_flags_asset_initiated_locks() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    s.asset.mint(&owner, &1);
+
+    for (token_id, initiated_by_asset) in [(1, false), (2, true)] {
+        if initiated_by_asset {
+            s.asset
+                .mint_locked(&s.contract_id, &owner, &token_id, &2_000);
+        } else {
+            s.client
+                .lock_credit(&owner, &owner, &token_id, &2_000, &None, &None);
+        }
+
+        let expected = CreditLocked {
+            token_id,
+            owner: owner.clone(),
+            unlock_timestamp: 2_000,
+            initiated_by_asset,
+        };
+        let last = s.env.events().all().last().unwrap();
+        assert_eq!(
+            vec![&s.env, last],
+            vec![
+                &s.env,
+                (
+                    s.contract_id.clone(),
+                    expected.topics(&s.env),
+                    expected.data(&s.env)
+                )
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_configure_updates_two_fields_at_once() {
+    let s = setup();
+
+    let config = PartialConfig {
+        relock_cooldown: Some(600),
+        max_lock_horizon: Some(86_400),
+        ..Default::default()
+    };
+    s.client.configure(&s.admin, &config);
+
+    let expected = ConfigUpdated {
+        admin: s.admin.clone(),
+        config: config.clone(),
+    };
+    assert_eq!(
+        vec![&s.env, s.env.events().all().last().unwrap()],
+        vec![
+            &s.env,
+            (
+                s.contract_id.clone(),
+                expected.topics(&s.env),
+                expected.data(&s.env)
+            )
+        ]
+    );
+
+    assert_eq!(s.client.get_relock_cooldown(), 600);
+    assert_eq!(s.client.get_max_lock_horizon(), 86_400);
+    assert!(!s.client.get_vintage_validation());
+    assert_eq!(s.client.get_retirement_tracker(), None);
+}
+
+#[test]
+fn test_configure_requires_admin() {
+    let s = setup();
+    let outsider = Address::generate(&s.env);
+
+    let config = PartialConfig {
+        vintage_validation: Some(true),
+        ..Default::default()
+    };
+    assert_eq!(
+        s.client.try_configure(&outsider, &config),
+        Err(Ok(TimeLockError::NotAdmin))
+    );
+    assert!(!s.client.get_vintage_validation());
+}
+
+/// Lock token 1 holding 1000 credits, vesting from t=1000 to t=2000 with a cliff at t=1250
+fn setup_vesting() -> (TestSetup<'static>, Address) {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    s.asset.mint(&owner, &1);
+    s.asset.set_amount(&1, &1_000);
+    s.client
+        .lock_vesting(&owner, &owner, &1, &1_250, &1_000, &None);
+    (s, owner)
+}
+
+#[test]
+fn test_claim_vested_at_cliff() {
+    let (s, owner) = setup_vesting();
+    assert_eq!(s.client.get_unlock_timestamp(&1), Some(2_000));
+
+    s.env.ledger().set_timestamp(1_249);
+    assert_eq!(s.client.claim_vested(&1), 0);
+    assert_eq!(s.asset.received(&owner, &1), 0);
+
+    // A quarter of the period has elapsed at the cliff
+    s.env.ledger().set_timestamp(1_250);
+    assert_eq!(s.client.claim_vested(&1), 250);
+    assert_eq!(s.asset.received(&owner, &1), 250);
+    assert_eq!(s.client.get_vesting(&1).unwrap().claimed, 250);
+    assert_eq!(s.client.get_lock_status(&1).unwrap().amount, 750);
+    assert_eq!(s.client.get_total_amount_locked(), 750);
+}
+
+#[test]
+fn test_claim_vested_mid_vesting() {
+    let (s, owner) = setup_vesting();
+
+    s.env.ledger().set_timestamp(1_300);
+    assert_eq!(s.client.claim_vested(&1), 300);
+
+    s.env.ledger().set_timestamp(1_500);
+    assert_eq!(s.client.claim_vested(&1), 200);
+    // Claiming again at the same time sends nothing
+    assert_eq!(s.client.claim_vested(&1), 0);
+
+    assert_eq!(s.asset.received(&owner, &1), 500);
+    assert_eq!(s.client.get_vesting(&1).unwrap().claimed, 500);
+    assert_eq!(s.asset.owner_of(&1), s.contract_id);
+
+    mint_and_lock(&s, &owner, 2, 5_000);
+    assert_eq!(
+        s.client.try_claim_vested(&2),
+        Err(Ok(TimeLockError::NotVesting))
+    );
+}
+
+#[test]
+fn test_claim_vested_diverts_to_buffer_pool() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let pool = MockBufferPoolClient::new(&s.env, &s.env.register(MockBufferPool, ()));
+    pool.init(&s.asset.address);
+    let buffer_pool = pool.address.clone();
+    s.asset.mint(&owner, &1);
+    s.asset.set_amount(&1, &1_000);
+    s.client.lock_vesting(
+        &owner,
+        &owner,
+        &1,
+        &1_250,
+        &1_000,
+        &Some(ExpiryAction::DivertToBuffer),
+    );
+
+    s.env.ledger().set_timestamp(1_300);
+    assert_eq!(
+        s.client.try_claim_vested(&1),
+        Err(Ok(TimeLockError::ExpiryTargetMissing))
+    );
+    assert_eq!(s.client.get_vesting(&1).unwrap().claimed, 0);
+
+    s.client.set_buffer_pool(&s.admin, &buffer_pool);
+    assert_eq!(
+        s.client.preview_release(&1),
+        Some((buffer_pool.clone(), 300, true))
+    );
+    // No mocked auths: the time lock authorizes the pool's pull itself
+    s.env.set_auths(&[]);
+    assert_eq!(s.client.claim_vested(&1), 300);
+    assert_eq!(s.asset.received(&buffer_pool, &1), 300);
+    assert_eq!(pool.donated_amount(&s.contract_id, &1), 300);
+    s.env.mock_all_auths();
+
+    s.env.ledger().set_timestamp(1_500);
+    assert_eq!(s.client.claim_vested(&1), 200);
+    // Already-diverted credits are not sent again
+    assert_eq!(s.client.claim_vested(&1), 0);
+    assert_eq!(s.asset.received(&buffer_pool, &1), 500);
+    assert_eq!(pool.donated_amount(&s.contract_id, &1), 500);
+    assert_eq!(s.asset.received(&owner, &1), 0);
+    assert_eq!(s.client.get_vesting(&1).unwrap().claimed, 500);
+
+    // The remainder follows the token to the pool at the end of the period
+    s.env.ledger().set_timestamp(2_000);
+    assert_eq!(s.client.claim_vested(&1), 500);
+    assert_eq!(s.asset.owner_of(&1), buffer_pool);
+    assert_eq!(pool.donor_of(&1), Some(s.contract_id.clone()));
+}
+
+#[test]
+fn test_claim_vested_fully_vested() {
+    let (s, owner) = setup_vesting();
+
+    s.env.ledger().set_timestamp(1_600);
+    assert_eq!(s.client.claim_vested(&1), 600);
+
+    s.env.ledger().set_timestamp(2_000);
+    assert_eq!(s.client.claim_vested(&1), 400);
+
+    assert_eq!(s.asset.owner_of(&1), owner);
+    assert_eq!(s.client.get_lock_status(&1), None);
+    assert_eq!(s.client.get_vesting(&1), None);
+    assert_eq!(s.client.get_total_amount_locked(), 0);
+    assert_eq!(
+        s.client.try_claim_vested(&1),
+        Err(Ok(TimeLockError::NotLocked))
+    );
+}
+
+#[test]
+fn test_lock_vesting_rejects_cliff_outside_period() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    s.asset.mint(&owner, &1);
+
+    assert_eq!(
+        s.client
+            .try_lock_vesting(&owner, &owner, &1, &2_001, &1_000, &None),
+        Err(Ok(TimeLockError::InvalidUnlockTime))
+    );
+}
+
+#[test]
+fn test_max_locks_per_owner() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let other = Address::generate(&s.env);
+    assert_eq!(s.client.get_max_locks_per_owner(), 0);
+
+    s.client.set_max_locks_per_owner(&s.admin, &2);
+    mint_and_lock(&s, &owner, 1, 5_000);
+    mint_and_lock(&s, &owner, 2, 5_000);
+
+    s.asset.mint(&owner, &3);
+    assert_eq!(
+        s.client
+            .try_lock_credit(&owner, &owner, &3, &5_000, &None, &None),
+        Err(Ok(TimeLockError::TooManyLocks))
+    );
+    assert_eq!(s.client.get_locks_by_owner(&owner).len(), 2);
+
+    // Other owners are unaffected, but cannot be handed locks beyond the cap
+    mint_and_lock(&s, &other, 4, 5_000);
+    mint_and_lock(&s, &other, 5, 5_000);
+    assert_eq!(
+        s.client.try_reassign_lock_owner(&s.admin, &1, &other),
+        Err(Ok(TimeLockError::TooManyLocks))
+    );
+
+    // Releasing a lock frees a slot
+    s.client.force_release(&s.admin, &1);
+    s.client
+        .lock_credit(&owner, &owner, &3, &5_000, &None, &None);
+}
+
+#[test]
+fn test_preview_release() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let heir = Address::generate(&s.env);
+    s.asset.mint(&owner, &1);
+    s.asset.set_amount(&1, &40);
+    s.client
+        .lock_credit(&owner, &owner, &1, &5_000, &Some(heir.clone()), &None);
+
+    assert_eq!(
+        s.client.preview_release(&1),
+        Some((heir.clone(), 40, false))
+    );
+
+    s.env.ledger().set_timestamp(5_000);
+    assert_eq!(s.client.preview_release(&1), Some((heir.clone(), 40, true)));
+    // Previewing does not release the lock
+    assert!(s.client.get_lock_status(&1).is_some());
+
+    s.client.release_if_eligible(&1);
+    assert_eq!(s.client.preview_release(&1), None);
+}
+
+#[test]
+fn test_preview_release_vesting_and_missing_target() {
+    let (s, owner) = setup_vesting();
+
+    s.env.ledger().set_timestamp(1_100);
+    assert_eq!(
+        s.client.preview_release(&1),
+        Some((owner.clone(), 0, false))
+    );
+    s.env.ledger().set_timestamp(1_500);
+    assert_eq!(
+        s.client.preview_release(&1),
+        Some((owner.clone(), 500, true))
+    );
+    assert_eq!(s.client.claim_vested(&1), 500);
+
+    s.asset.mint(&owner, &2);
+    s.client.lock_credit(
+        &owner,
+        &owner,
+        &2,
+        &1_600,
+        &None,
+        &Some(ExpiryAction::DivertToBuffer),
+    );
+    s.env.ledger().set_timestamp(1_600);
+    assert_eq!(
+        s.client.preview_release(&2),
+        Some((owner.clone(), 0, false))
+    );
+}
+
+#[test]
+fn test_get_trusted_contracts() {
+    let s = setup();
+    let asset_id = s.client.get_carbon_asset_contract();
+    assert_eq!(
+        s.client.get_trusted_contracts(),
+        vec![
+            &s.env,
+            (Symbol::new(&s.env, "carbon_asset"), asset_id.clone())
+        ]
+    );
+
+    let oracle = Address::generate(&s.env);
+    let tracker = Address::generate(&s.env);
+    let buffer_pool = Address::generate(&s.env);
+    s.client.configure(
+        &s.admin,
+        &PartialConfig {
+            vintage_oracle: Some(oracle.clone()),
+            retirement_tracker: Some(tracker.clone()),
+            buffer_pool: Some(buffer_pool.clone()),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        s.client.get_trusted_contracts(),
+        vec![
+            &s.env,
+            (Symbol::new(&s.env, "carbon_asset"), asset_id),
+            (Symbol::new(&s.env, "vintage_oracle"), oracle),
+            (Symbol::new(&s.env, "retirement_tracker"), tracker),
+            (Symbol::new(&s.env, "buffer_pool"), buffer_pool),
+        ]
+    );
+}
+
+#[test]
+fn test_force_release_respecting_vintage_matured() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let oracle = MockVintageOracleClient::new(&s.env, &s.env.register(MockVintageOracle, ()));
+    s.client
+        .set_vintage_oracle(&s.admin, &Some(oracle.address.clone()));
+    mint_and_lock(&s, &owner, 1, 5_000);
+
+    oracle.set_unlock_timestamp(&1, &900);
+    s.client.force_release_respecting_vintage(&s.admin, &1);
+
+    assert_eq!(s.asset.owner_of(&1), owner);
+    assert_eq!(s.client.get_lock_status(&1), None);
+}
+
+#[test]
+fn test_force_release_respecting_vintage_has_its_own_approvals() {
+    let env = Env::default();
+    env.mock_all_auths();
+    env.ledger().set_timestamp(1_000);
+
+    let admin = Address::generate(&env);
+    let owner = Address::generate(&env);
+    let approvers = vec![&env, Address::generate(&env), Address::generate(&env)];
+    let asset = MockCarbonAssetClient::new(&env, &env.register(MockCarbonAsset, ()));
+    let oracle = MockVintageOracleClient::new(&env, &env.register(MockVintageOracle, ()));
+    let client = TimeLockClient::new(&env, &env.register(TimeLock, ()));
+    client.initialize_with_approvers(&admin, &asset.address, &approvers, &2);
+    client.set_vintage_oracle(&admin, &Some(oracle.address.clone()));
+
+    for token_id in 1..=2 {
+        asset.mint(&owner, &token_id);
+        client.lock_credit(&owner, &owner, &token_id, &5_000, &None, &None);
+    }
+    oracle.set_unlock_timestamp(&1, &900);
+
+    let vintage_hash = client.get_action_hash(&ApprovalAction::VintageGatedRelease(1));
+    assert_ne!(
+        vintage_hash,
+        client.get_action_hash(&ApprovalAction::ForceRelease(1))
+    );
+    for approver in approvers.iter() {
+        client.approve_action(&approver, &vintage_hash);
+    }
+
+    // Approving the vintage-gated release does not approve an unconditional one
+    let result = client.try_force_release(&admin, &1);
+    assert_eq!(result, Err(Ok(TimeLockError::InsufficientApprovals)));
+    client.force_release_respecting_vintage(&admin, &1);
+    assert_eq!(asset.owner_of(&1), owner);
+    assert_eq!(client.get_approval_count(&vintage_hash), 0);
+
+    // Nor the other way round
+    let force_hash = client.get_action_hash(&ApprovalAction::ForceRelease(2));
+    for approver in approvers.iter() {
+        client.approve_action(&approver, &force_hash);
+    }
+    oracle.set_unlock_timestamp(&2, &900);
+    let result = client.try_force_release_respecting_vintage(&admin, &2);
+    assert_eq!(result, Err(Ok(TimeLockError::InsufficientApprovals)));
+}
+
+#[test]
+fn test_force_release_respecting_vintage_immature() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    mint_and_lock(&s, &owner, 1, 5_000);
+
+    // Without an oracle the vintage cannot be shown to have matured
+    assert_eq!(
+        s.client.try_force_release_respecting_vintage(&s.admin, &1),
+        Err(Ok(TimeLockError::VintageNotMatured))
+    );
+
+    let oracle = MockVintageOracleClient::new(&s.env, &s.env.register(MockVintageOracle, ()));
+    s.client
+        .set_vintage_oracle(&s.admin, &Some(oracle.address.clone()));
+    oracle.set_unlock_timestamp(&1, &3_000);
+    assert_eq!(
+        s.client.try_force_release_respecting_vintage(&s.admin, &1),
+        Err(Ok(TimeLockError::VintageNotMatured))
+    );
+    assert_eq!(s.asset.owner_of(&1), s.contract_id);
+
+    // The unconditional path still works for emergencies
+    s.client.force_release(&s.admin, &1);
+    assert_eq!(s.asset.owner_of(&1), owner);
+}
+
+#[test]
+fn test_dispute_hold_blocks_release_until_cleared() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    mint_and_lock(&s, &owner, 1, 1_500);
+    mint_and_lock(&s, &owner, 2, 1_500);
+
+    s.client.place_dispute_hold(&s.admin, &1);
+    assert!(s.client.is_on_hold(&1));
+    assert!(!s.client.is_on_hold(&2));
+
+    s.env.ledger().set_timestamp(2_000);
+    assert!(!s.client.release_if_eligible(&1));
+    assert_eq!(
+        vec![&s.env, s.env.events().all().last().unwrap()],
+        vec![
+            &s.env,
+            (
+                s.contract_id.clone(),
+                ReleaseHeld { token_id: 1 }.topics(&s.env),
+                ReleaseHeld { token_id: 1 }.data(&s.env),
+            ),
+        ]
+    );
+    assert!(!s.client.preview_release(&1).unwrap().2);
+
+    let (_, released) = s.client.batch_release(&vec![&s.env, 1, 2], &0);
+    assert_eq!(released, vec![&s.env, 2]);
+    assert!(s.client.release_owned(&owner, &vec![&s.env, 1]).is_empty());
+    assert_eq!(s.asset.owner_of(&1), s.contract_id);
+
+    s.client.clear_dispute_hold(&s.admin, &1);
+    assert!(!s.client.is_on_hold(&1));
+    assert!(s.client.release_if_eligible(&1));
+    assert_eq!(s.asset.owner_of(&1), owner);
+}
+
+#[test]
+fn test_force_release_overrides_dispute_hold() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    mint_and_lock(&s, &owner, 1, 1_500);
+
+    let non_admin = Address::generate(&s.env);
+    assert_eq!(
+        s.client.try_place_dispute_hold(&non_admin, &1),
+        Err(Ok(TimeLockError::NotAdmin))
+    );
+    assert_eq!(
+        s.client.try_place_dispute_hold(&s.admin, &9),
+        Err(Ok(TimeLockError::NotLocked))
+    );
+
+    s.client.place_dispute_hold(&s.admin, &1);
+    s.client.force_release(&s.admin, &1);
+
+    assert_eq!(s.asset.owner_of(&1), owner);
+    assert!(!s.client.is_on_hold(&1));
+}
+
+#[contract]
+pub struct MockController;
+
+#[contractimpl]
+impl MockController {
+    pub fn set_paused(env: Env, paused: bool) {
+        env.storage()
+            .instance()
+            .set(&Symbol::new(&env, "paused"), &paused);
+    }
+
+    pub fn is_paused(env: Env) -> bool {
+        env.storage()
+            .instance()
+            .get(&Symbol::new(&env, "paused"))
+            .unwrap_or(false)
+    }
+}
+
+#[test]
+fn test_controller_pause_blocks_lock() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let controller = MockControllerClient::new(&s.env, &s.env.register(MockController, ()));
+    s.asset.mint(&owner, &1);
+
+    s.client
+        .set_controller(&s.admin, &Some(controller.address.clone()));
+    assert_eq!(s.client.get_controller(), Some(controller.address.clone()));
+
+    controller.set_paused(&true);
+    assert_eq!(
+        s.client
+            .try_lock_credit(&owner, &owner, &1, &2_000, &None, &None),
+        Err(Ok(TimeLockError::Paused))
+    );
+    assert_eq!(s.asset.owner_of(&1), owner);
+
+    controller.set_paused(&false);
+    s.client
+        .lock_credit(&owner, &owner, &1, &2_000, &None, &None);
+
+    controller.set_paused(&true);
+    s.env.ledger().set_timestamp(2_000);
+    assert_eq!(
+        s.client.try_release_if_eligible(&1),
+        Err(Ok(TimeLockError::Paused))
+    );
+}
+
+fn setup_migration_destination(s: &TestSetup) -> TimeLockClient<'static> {
+    let destination = TimeLockClient::new(&s.env, &s.env.register(TimeLock, ()));
+    destination.initialize(&s.admin, &s.asset.address);
+    destination.set_migration_source(&s.admin, &Some(s.contract_id.clone()));
+    destination
+}
+
+#[test]
+fn test_migrate_lock_preserves_record() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let destination = setup_migration_destination(&s);
+    mint_and_lock(&s, &owner, 1, 5_000);
+    s.asset.set_amount(&1, &40);
+    let record = s.client.get_lock_status(&1).unwrap();
+
+    s.client.migrate_lock(&s.admin, &1, &destination.address);
+
+    assert_eq!(s.asset.owner_of(&1), destination.address);
+    assert_eq!(s.client.get_lock_status(&1), None);
+    assert!(s.client.get_locks_by_owner(&owner).is_empty());
+    assert_eq!(destination.get_lock_status(&1), Some(record));
+    assert_eq!(destination.get_unlock_timestamp(&1), Some(5_000));
+    assert_eq!(destination.get_locks_by_owner(&owner), vec![&s.env, 1]);
+
+    // The destination releases on the original schedule
+    s.env.ledger().set_timestamp(5_000);
+    assert!(destination.release_if_eligible(&1));
+    assert_eq!(s.asset.owner_of(&1), owner);
+}
+
+#[test]
+fn test_migrate_locks_in_batches() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let destination = setup_migration_destination(&s);
+    let count = BATCH_WORK_BUDGET + 2;
+    let mut token_ids = Vec::new(&s.env);
+    for token_id in 1..=count {
+        mint_and_lock(&s, &owner, token_id, 5_000);
+        token_ids.push_back(token_id);
+    }
+    s.client.place_dispute_hold(&s.admin, &1);
+
+    let cursor = s
+        .client
+        .migrate_locks(&s.admin, &token_ids, &destination.address, &0);
+    assert_eq!(cursor, BATCH_WORK_BUDGET);
+    let cursor = s
+        .client
+        .migrate_locks(&s.admin, &token_ids, &destination.address, &cursor);
+    assert_eq!(cursor, 0);
+
+    assert_eq!(s.client.get_lock_count(), 0);
+    assert_eq!(destination.get_lock_count(), count);
+    assert!(destination.is_on_hold(&1));
+}
+
+#[test]
+fn test_migrate_lock_rejects_unlocked_and_untrusted() {
+    let s = setup();
+    let owner = Address::generate(&s.env);
+    let destination = setup_migration_destination(&s);
+    mint_and_lock(&s, &owner, 1, 5_000);
+
+    assert_eq!(
+        s.client
+            .try_migrate_lock(&s.admin, &2, &destination.address),
+        Err(Ok(TimeLockError::NotLocked))
+    );
+
+    destination.set_migration_source(&s.admin, &None);
+    assert!(s
+        .client
+        .try_migrate_lock(&s.admin, &1, &destination.address)
+        .is_err());
+    assert_eq!(s.asset.owner_of(&1), s.contract_id);
+    assert!(s.client.get_lock_status(&1).is_some());
+}
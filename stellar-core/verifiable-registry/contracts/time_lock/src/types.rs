@@ -0,0 +1,178 @@
+use soroban_sdk::{contracterror, contracttype, Address};
+
+/// What happens to a credit when its lock expires
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExpiryAction {
+    /// Send the credit to its release destination
+    ReturnToOwner,
+    /// Retire the credit through the RetirementTracker, which must trust this
+    /// contract as a retirer
+    Retire,
+    /// Donate the credit to the buffer pool
+    DivertToBuffer,
+}
+
+/// Sensitive admin operation that requires approvals once a threshold is set
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ApprovalAction {
+    /// `force_release` of a token's current lock
+    ForceRelease(u32),
+    /// `force_release_by_owner` for an owner
+    ForceReleaseByOwner(Address),
+    /// `force_release_respecting_vintage` of a token's current lock
+    VintageGatedRelease(u32),
+}
+
+/// Linear vesting schedule attached to a lock created by `lock_vesting`
+///
+/// Credits vest linearly from `locked_at` to `unlock_timestamp` of the lock
+/// record; nothing can be claimed before the cliff.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct VestingLock {
+    /// Ledger timestamp before which no credits can be claimed
+    pub cliff_timestamp: u64,
+    /// Length of the vesting period in seconds
+    pub duration: u64,
+    /// Credits already claimed through `claim_vested`
+    pub claimed: i128,
+}
+
+/// Reward paid to keepers for each credit released through `keeper_release`
+///
+/// Paid out of this contract's balance of `token`, which anyone can top up
+/// with a plain token transfer.
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct KeeperReward {
+    /// Token contract the reward is paid in
+    pub token: Address,
+    /// Amount paid per released credit
+    pub amount: i128,
+    /// Minimum time between a lock's creation and its unlock for its release
+    /// to earn the reward
+    pub min_lock_duration: u64,
+}
+
+/// Set of configuration values applied together by `configure`
+///
+/// Fields left as `None` keep their current value.
+#[contracttype]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PartialConfig {
+    /// Enable or disable vintage validation on new locks
+    pub vintage_validation: Option<bool>,
+    /// Vintage oracle queried when validation is enabled
+    pub vintage_oracle: Option<Address>,
+    /// Relock cooldown in seconds
+    pub relock_cooldown: Option<u64>,
+    /// Maximum lock horizon in seconds (0 removes the limit)
+    pub max_lock_horizon: Option<u64>,
+    /// Maximum concurrent locks per owner (0 removes the limit)
+    pub max_locks_per_owner: Option<u32>,
+    /// RetirementTracker used by `ExpiryAction::Retire` locks
+    pub retirement_tracker: Option<Address>,
+    /// Buffer pool used by `ExpiryAction::DivertToBuffer` locks
+    pub buffer_pool: Option<Address>,
+}
+
+/// Lock record describing a credit held in escrow until its unlock time
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LockRecord {
+    /// ID of the locked CarbonAsset token
+    pub token_id: u32,
+    /// Address the credit is returned to on release
+    pub owner: Address,
+    /// Ledger close timestamp when the lock was created
+    pub locked_at: u64,
+    /// Ledger timestamp from which the credit can be released
+    pub unlock_timestamp: u64,
+    /// Optional address the credit is sent to on release instead of the owner
+    pub release_to: Option<Address>,
+    /// Unlock timestamp asserted by the vintage oracle when the lock was created
+    pub vintage_unlock: Option<u64>,
+    /// Action taken when the lock expires
+    pub on_expiry: ExpiryAction,
+    /// Quantity of credits held by the lock; vesting claims reduce it
+    pub amount: i128,
+}
+
+impl LockRecord {
+    /// Address that receives the credit when the lock is released
+    pub fn release_destination(&self) -> Address {
+        self.release_to
+            .clone()
+            .unwrap_or_else(|| self.owner.clone())
+    }
+}
+
+/// Contract error types
+///
+/// Error codes are part of the public interface: discriminants are assigned
+/// explicitly and must never be renumbered or reused.
+#[contracterror]
+#[derive(Copy, Clone, Debug, Eq, PartialEq, PartialOrd, Ord)]
+#[repr(u32)]
+pub enum TimeLockError {
+    /// Contract already initialized
+    AlreadyInitialized = 1,
+    /// Contract has not been initialized yet
+    NotInitialized = 2,
+    /// Caller is not the admin
+    NotAdmin = 3,
+    /// Token is already locked
+    AlreadyLocked = 4,
+    /// Token has no lock record
+    NotLocked = 5,
+    /// Unlock timestamp is not in the future
+    InvalidUnlockTime = 6,
+    /// Token was released too recently to be locked again
+    RelockCooldownActive = 7,
+    /// Caller is neither the token owner nor the CarbonAsset contract
+    NotOwner = 8,
+    /// Vintage validation is enabled but no vintage oracle is configured
+    VintageCheckMissing = 9,
+    /// Unlock timestamp is earlier than the vintage oracle allows
+    UnlockBeforeVintage = 10,
+    /// The lock's expiry action targets a contract that is not configured
+    ExpiryTargetMissing = 11,
+    /// Approver is not in the configured approver set
+    NotApprover = 12,
+    /// Fewer approvals than the threshold exist for the action
+    InsufficientApprovals = 13,
+    /// Approval threshold is zero or exceeds the number of approvers
+    InvalidThreshold = 14,
+    /// The CarbonAsset transfer did not leave the token in the contract's custody
+    CustodyMismatch = 15,
+    /// Unlock timestamp is further out than the configured maximum lock horizon
+    LockBeyondHorizon = 16,
+    /// Vintage validation is enabled but the oracle has no vintage for the token
+    VintageUnavailable = 17,
+    /// Token id range is inverted or wider than `MAX_PAGE_SIZE`, or more than
+    /// `MAX_PAGE_SIZE` ids were supplied
+    InvalidRange = 18,
+    /// The token's lock has no vesting schedule
+    NotVesting = 19,
+    /// The owner already holds the configured maximum number of locks
+    TooManyLocks = 20,
+    /// The vintage oracle does not report the token's vintage as matured
+    VintageNotMatured = 21,
+    /// The shared controller has paused the deployment
+    Paused = 22,
+    /// Caller is not the TimeLock configured as this contract's migration source
+    NotMigrationSource = 23,
+    /// The token does not exist or is not held by the owner the lock names
+    TokenNotHeld = 24,
+    /// A keeper reward payout is already in progress
+    KeeperPayoutActive = 25,
+    /// Keeper reward amount is not positive
+    InvalidKeeperReward = 26,
+}
+
+/// Stable numeric code of a `TimeLockError`, as seen by cross-contract callers
+pub fn error_code(error: TimeLockError) -> u32 {
+    error as u32
+}
@@ -0,0 +1,9 @@
+use soroban_sdk::{contractclient, Env};
+
+/// Oracle reporting the earliest timestamp a token's vintage allows release
+#[allow(dead_code)]
+#[contractclient(name = "VintageOracleClient")]
+pub trait VintageOracleInterface {
+    /// Earliest unlock timestamp permitted by the vintage of `token_id`
+    fn get_unlock_timestamp(env: Env, token_id: u32) -> u64;
+}